@@ -1,15 +1,350 @@
 extern crate bindgen;
 
-use std::{env, path::PathBuf, process::Command};
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
 const TA_LIB_PATH: &str = "ta-lib-0.4.0";
 
+/// The name of the library we link against with `use_system_lib`, unless
+/// overridden with `SYSTEM_LIB_NAME_ENV_VAR`.
+const DEFAULT_SYSTEM_LIB_NAME: &str = "ta_lib";
+
+/// Env var with the name of the system's TA lib, for builds naming the
+/// archive differently, e.g. `ta-lib` for `libta-lib.a`.
+const SYSTEM_LIB_NAME_ENV_VAR: &str = "TA_LIB_NAME";
+
+/// Env vars with (path-separated) directories searched for the system's TA
+/// lib, in order.
+const SYSTEM_LIB_ENV_VARS: [&str; 2] = ["TA_LIB_LIB_DIR", "LIBRARY_PATH"];
+
+/// Directories searched for the system's TA lib after those from
+/// `SYSTEM_LIB_ENV_VARS`.
+const SYSTEM_LIB_DIRS: [&str; 6] = [
+    "/usr/local/lib",
+    "/usr/lib",
+    "/usr/lib64",
+    "/usr/lib/x86_64-linux-gnu",
+    "/usr/lib/aarch64-linux-gnu",
+    "/opt/homebrew/lib",
+];
+
+/// Suffixes of the file names the system's TA lib may have, with the kind of
+/// library each is, in order of preference.
+const SYSTEM_LIB_SUFFIXES: [(&str, &str); 4] = [
+    (".a", "static"),
+    (".lib", "static"),
+    (".so", "dylib"),
+    (".dylib", "dylib"),
+];
+
+/// `{file_names}` is replaced with the file names of the library and
+/// `{checked}` with the list of env vars and directories looked at.
+const SYSTEM_LIB_NOT_FOUND: &str = "Could not find the TA C library but the \
+    `use_system_lib` feature is enabled.
+
+Looked for {file_names} in:
+{checked}
+
+Install TA-Lib (e.g. `brew install ta-lib` or from https://ta-lib.org/), set `TA_LIB_LIB_DIR` \
+    to the directory containing the library, set `TA_LIB_NAME` if your TA-Lib build names \
+    the library differently, e.g. to `ta-lib` for `libta-lib.a`, or disable the \
+    `use_system_lib` feature to build the TA C library included with this crate from source.";
+
+/// The name of the system's TA lib to link, from `SYSTEM_LIB_NAME_ENV_VAR` or
+/// `DEFAULT_SYSTEM_LIB_NAME`.
+///
+/// Panics if the name is empty or given as a file name.
+fn system_lib_name() -> String {
+    println!("cargo:rerun-if-env-changed={}", SYSTEM_LIB_NAME_ENV_VAR);
+
+    let name =
+        env::var(SYSTEM_LIB_NAME_ENV_VAR).unwrap_or_else(|_| DEFAULT_SYSTEM_LIB_NAME.to_string());
+
+    if name.is_empty()
+        || SYSTEM_LIB_SUFFIXES
+            .iter()
+            .any(|(suffix, _)| name.ends_with(suffix))
+    {
+        panic!(
+            "`{}` must be the name of the library without the `lib` prefix and the file \
+             extension, e.g. `ta-lib` for `libta-lib.a`, but is `{}`.",
+            SYSTEM_LIB_NAME_ENV_VAR, name
+        );
+    }
+
+    name
+}
+
+/// The file names the system's TA lib `name` may have, e.g. `libta_lib.a`
+/// or `ta_lib.lib`, with the kind of library each is.
+fn system_lib_file_names(name: &str) -> Vec<(String, &'static str)> {
+    SYSTEM_LIB_SUFFIXES
+        .iter()
+        .map(|(suffix, kind)| {
+            // MSVC libraries have no `lib` prefix.
+            let prefix = if *suffix == ".lib" { "" } else { "lib" };
+            (format!("{}{}{}", prefix, name, suffix), *kind)
+        })
+        .collect()
+}
+
+/// The kind of the system's TA lib in `dir`, `static` or `dylib`, if it is
+/// there.
+fn system_lib_kind(dir: &Path, file_names: &[(String, &'static str)]) -> Option<&'static str> {
+    file_names
+        .iter()
+        .find(|(file_name, _)| dir.join(file_name).is_file())
+        .map(|(_, kind)| *kind)
+}
+
+/// Find the system's TA lib, `lib<name>.a` or one of the other
+/// `system_lib_file_names()`, and return the kind of library found and the
+/// directory containing it.
+///
+/// Directories from `SYSTEM_LIB_ENV_VARS` are returned as `Some` so the caller
+/// can add them to the linker search path. The standard directories are
+/// searched by the linker anyway.
+///
+/// Panics with a message listing everything that was checked if the library
+/// can not be found.
+fn find_system_lib(name: &str) -> (&'static str, Option<PathBuf>) {
+    let file_names = system_lib_file_names(name);
+    let mut checked = Vec::new();
+
+    for env_var in SYSTEM_LIB_ENV_VARS {
+        println!("cargo:rerun-if-env-changed={}", env_var);
+
+        match env::var_os(env_var) {
+            Some(paths) => {
+                for dir in env::split_paths(&paths) {
+                    if let Some(kind) = system_lib_kind(&dir, &file_names) {
+                        return (kind, Some(dir));
+                    }
+                    checked.push(format!("  * `{}` (from `{}`)", dir.display(), env_var));
+                }
+            }
+            None => checked.push(format!("  * `{}` (not set)", env_var)),
+        }
+    }
+
+    for dir in SYSTEM_LIB_DIRS {
+        if let Some(kind) = system_lib_kind(Path::new(dir), &file_names) {
+            return (kind, None);
+        }
+        checked.push(format!("  * `{}`", dir));
+    }
+
+    panic!(
+        "{}",
+        SYSTEM_LIB_NOT_FOUND
+            .replace(
+                "{file_names}",
+                &file_names
+                    .iter()
+                    .map(|(file_name, _)| format!("`{}`", file_name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+            .replace("{checked}", &checked.join("\n"))
+    );
+}
+
+/// Rust target triples whose cross toolchains are prefixed with a GNU triple
+/// differing in more than the vendor.
+const GNU_TRIPLES: [(&str, &str); 9] = [
+    ("arm-unknown-linux-gnueabi", "arm-linux-gnueabi"),
+    ("arm-unknown-linux-gnueabihf", "arm-linux-gnueabihf"),
+    ("armv7-unknown-linux-gnueabi", "arm-linux-gnueabi"),
+    ("armv7-unknown-linux-gnueabihf", "arm-linux-gnueabihf"),
+    ("armv7-unknown-linux-musleabihf", "arm-linux-musleabihf"),
+    ("i686-pc-windows-gnu", "i686-w64-mingw32"),
+    ("riscv64gc-unknown-linux-gnu", "riscv64-linux-gnu"),
+    ("thumbv7neon-unknown-linux-gnueabihf", "arm-linux-gnueabihf"),
+    ("x86_64-pc-windows-gnu", "x86_64-w64-mingw32"),
+];
+
+/// Turn a Rust target triple into the GNU triple cross toolchains are
+/// prefixed with, e.g. `armv7-unknown-linux-gnueabihf` into
+/// `arm-linux-gnueabihf`.
+///
+/// Triples not in [`GNU_TRIPLES`] lose their `unknown` vendor, e.g.
+/// `aarch64-unknown-linux-gnu` becomes `aarch64-linux-gnu`. Apple's, e.g.
+/// `aarch64-apple-darwin`, are GNU triples already.
+fn gnu_triple(triple: &str) -> String {
+    GNU_TRIPLES
+        .iter()
+        .find(|(rust_triple, _)| triple == *rust_triple)
+        .map(|(_, gnu_triple)| gnu_triple.to_string())
+        .unwrap_or_else(|| triple.replace("-unknown-", "-"))
+}
+
+/// Turn a Rust target triple into the canonical `cpu-vendor-os` triple
+/// `configure` expects.
+///
+/// Rust's are canonical already, except for the MinGW ones, e.g.
+/// `x86_64-pc-windows-gnu`, which `configure` knows as their GNU triple.
+fn canonical_triple(triple: &str) -> String {
+    if triple.ends_with("-windows-gnu") {
+        gnu_triple(triple)
+    } else {
+        triple.to_string()
+    }
+}
+
+/// The C compiler of the Apple `target`, i.e. clang from Xcode building for
+/// the target's architecture, if building on an Apple `host`.
+///
+/// Apple's toolchain builds for all its targets so there is no cross compiler
+/// prefixed with the target's triple.
+fn apple_cc(target: &str, host: &str) -> Option<String> {
+    if !target.contains("-apple-") || !host.contains("-apple-") {
+        return None;
+    }
+
+    let arch = match target.split('-').next() {
+        Some("aarch64") => "arm64",
+        arch => arch?,
+    };
+
+    Some(format!("clang -arch {}", arch))
+}
+
+/// Whether `program` is in one of the directories on the `PATH`.
+fn is_on_path(program: &str) -> bool {
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+/// Make `configure` build the TA C library for `target` instead of `host`.
+///
+/// `configure` picks up the cross compiler, archiver etc. prefixed with the
+/// GNU triple of `target`, e.g. `aarch64-linux-gnu-gcc`. On Apple hosts
+/// building for another Apple target it uses clang for the target's
+/// architecture. The compiler can be overridden with `CC_<target>`,
+/// `TARGET_CC` or `CC`, in this order, as with the `cc` crate.
+///
+/// Panics if none is set and the prefixed compiler is not on the `PATH`.
+/// `configure` would silently fall back to the host's compiler otherwise,
+/// which fails only when linking.
+fn configure_cross_compilation(configure: &mut Command, target: &str, host: &str) {
+    configure
+        .arg(format!("--build={}", gnu_triple(host)))
+        .arg(format!("--host={}", gnu_triple(target)))
+        // The `config.sub` shipped with TA-Lib 0.4.0 predates e.g. `aarch64`.
+        // We pass canonical triples so we skip it.
+        .env("ac_cv_build", canonical_triple(host))
+        .env("ac_cv_host", canonical_triple(target));
+
+    let cc_env_vars = [
+        format!("CC_{}", target),
+        format!("CC_{}", target.replace('-', "_")),
+        "TARGET_CC".to_string(),
+        "CC".to_string(),
+    ];
+
+    for env_var in &cc_env_vars {
+        println!("cargo:rerun-if-env-changed={}", env_var);
+
+        if let Some(cc) = env::var_os(env_var) {
+            configure.env("CC", cc);
+            return;
+        }
+    }
+
+    if let Some(cc) = apple_cc(target, host) {
+        configure.env("CC", cc);
+        return;
+    }
+
+    let cc = format!("{}-gcc", gnu_triple(target));
+
+    if !is_on_path(&cc) {
+        panic!(
+            "Could not find the C cross compiler `{}` to build the TA C library for `{}`.\n\n\
+             Install the cross toolchain for `{}`, set one of {} to the C compiler to use or \
+             enable the `use_system_lib` feature to link a TA C library built for the target.",
+            cc,
+            target,
+            target,
+            cc_env_vars
+                .iter()
+                .map(|env_var| format!("`{}`", env_var))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+}
+
+/// Run a step of building the TA C library, panicking with its output should
+/// it fail.
+fn run(command: &mut Command, step: &str) {
+    let output = command
+        .output()
+        .unwrap_or_else(|error| panic!("Failed to {} TA C library: {}", step, error));
+
+    if !output.status.success() {
+        panic!(
+            "Failed to {} TA C library.\n\n{}{}",
+            step,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
+
 fn main() {
     let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
 
-    println!("cargo:rustc-link-lib=static=ta_lib");
+    let mut include_paths = vec![out_path.join("include")];
+
+    if cfg!(feature = "use_system_lib") {
+        if env::var("CARGO_CFG_TARGET_OS").unwrap() == "windows" {
+            // On Windows there is no system wide lib & include path. Look for
+            // a TA lib installed with `vcpkg install ta-lib`. This emits the
+            // link instructions for the triplet matching our target.
+            println!("cargo:rerun-if-env-changed=VCPKG_ROOT");
+            println!("cargo:rerun-if-env-changed=VCPKGRS_TRIPLET");
+
+            let ta_lib = vcpkg::Config::new()
+                .find_package("ta-lib")
+                .unwrap_or_else(|error| {
+                    panic!(
+                        "Could not find the TA C library via vcpkg but the `use_system_lib` \
+                         feature is enabled: {}\n\nChecked `VCPKG_ROOT` ({}) and \
+                         `VCPKGRS_TRIPLET` ({}). Install it with `vcpkg install ta-lib` and \
+                         set `VCPKG_ROOT` if vcpkg is not on the path or disable the \
+                         `use_system_lib` feature to build the TA C library included with \
+                         this crate from source.",
+                        error,
+                        env::var("VCPKG_ROOT").unwrap_or_else(|_| "not set".into()),
+                        env::var("VCPKGRS_TRIPLET").unwrap_or_else(|_| "not set".into()),
+                    )
+                });
+
+            include_paths.extend(ta_lib.include_paths);
+        } else {
+            let name = system_lib_name();
+
+            // Fail here, legibly, rather than with a linker error.
+            let (kind, lib_dir) = find_system_lib(&name);
 
-    if !cfg!(feature = "use_system_lib") {
+            if let Some(lib_dir) = lib_dir {
+                println!("cargo:rustc-link-search=native={}", lib_dir.display());
+
+                let include_dir = lib_dir.join("..").join("include");
+                if include_dir.is_dir() {
+                    include_paths.push(include_dir);
+                }
+            }
+
+            println!("cargo:rustc-link-lib={}={}", kind, name);
+        }
+    } else {
+        println!("cargo:rustc-link-lib=static=ta_lib");
         println!(
             "cargo:rustc-link-search=native={}",
             out_path.join("lib").display()
@@ -28,22 +363,38 @@ fn main() {
         eprintln!("{}", ta_lib.display());
         */
 
-        Command::new("./configure")
+        let mut configure = Command::new("./configure");
+        configure
             .current_dir(TA_LIB_PATH)
-            .arg(format!("--prefix={}", out_path.display()))
-            .output()
-            .expect("Failed to execute TA C library configure script.");
+            .arg(format!("--prefix={}", out_path.display()));
 
-        Command::new("make")
-            .current_dir(TA_LIB_PATH)
-            .arg("install")
-            .output()
-            .expect("Failed to build TA C library.");
+        let target = env::var("TARGET").unwrap();
+        let host = env::var("HOST").unwrap();
+
+        if target != host {
+            configure_cross_compilation(&mut configure, &target, &host);
+        }
+
+        run(&mut configure, "configure");
+
+        // Objects of an earlier build may be for a different target.
+        run(
+            Command::new("make").current_dir(TA_LIB_PATH).arg("clean"),
+            "clean",
+        );
+        run(
+            Command::new("make").current_dir(TA_LIB_PATH).arg("install"),
+            "build",
+        );
     }
 
     let bindings = bindgen::Builder::default()
         .header("wrapper.h")
-        .clang_arg(format!("-I{}", out_path.join("include").display()))
+        .clang_args(
+            include_paths
+                .iter()
+                .map(|include_path| format!("-I{}", include_path.display())),
+        )
         .allowlist_function("TA_.*")
         .allowlist_type("TA_.*")
         .allowlist_var("TA_.*")
@@ -13,5 +13,12 @@
 //!
 //! * `use_system_lib` – Use the system's installed C TA lib instead of building
 //!   from source.
+//!
+//!   `libta_lib.a` is looked for in `TA_LIB_LIB_DIR`, `LIBRARY_PATH` and the
+//!   standard library directories.
+//!
+//!   On Windows the library is looked up with
+//!   [`vcpkg`](https://github.com/microsoft/vcpkg), e.g. after
+//!   `vcpkg install ta-lib:x64-windows-static-md`.
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
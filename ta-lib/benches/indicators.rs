@@ -0,0 +1,79 @@
+//! Throughput of the allocating, `_into` and streaming variants of SMA, EMA
+//! and RSI over a long series.
+//!
+//! Run with `cargo bench`.
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ta_lib::*;
+
+const LEN: usize = 1_000_000;
+const PERIOD: usize = 14;
+
+/// A deterministic, wiggly price series.
+fn prices() -> Vec<f64> {
+    (0..LEN)
+        .map(|index| {
+            let x = index as f64;
+            100.0 + 10.0 * (x / 50.0).sin() + (x * 0.7).sin()
+        })
+        .collect()
+}
+
+macro_rules! bench_indicator {
+    ($criterion:ident, $input:ident, $name:literal, $fn_name:ident, $into_fn_name:ident, $state:ident) => {
+        let mut group = $criterion.benchmark_group($name);
+        group.throughput(Throughput::Elements(LEN as _));
+
+        group.bench_function(BenchmarkId::new("allocating", LEN), |bencher| {
+            bencher.iter(|| $fn_name(black_box(&$input), Some(PERIOD)).unwrap())
+        });
+
+        let mut out = Vec::new();
+        group.bench_function(BenchmarkId::new("into", LEN), |bencher| {
+            bencher.iter(|| $into_fn_name(black_box(&$input), Some(PERIOD), &mut out).unwrap())
+        });
+
+        group.bench_function(BenchmarkId::new("streaming", LEN), |bencher| {
+            bencher.iter(|| {
+                let mut state = $state::new(PERIOD).unwrap();
+                black_box(&$input)
+                    .iter()
+                    .filter_map(|value| state.next(*value))
+                    .fold(0.0, |sum, value| sum + value)
+            })
+        });
+
+        group.finish();
+    };
+}
+
+fn indicators(criterion: &mut Criterion) {
+    let input = prices();
+
+    bench_indicator!(
+        criterion,
+        input,
+        "sma",
+        simple_moving_average,
+        simple_moving_average_into,
+        SmaState
+    );
+    bench_indicator!(
+        criterion,
+        input,
+        "ema",
+        exponential_moving_average,
+        exponential_moving_average_into,
+        EmaState
+    );
+    bench_indicator!(
+        criterion,
+        input,
+        "rsi",
+        relative_strength_index,
+        relative_strength_index_into,
+        RsiState
+    );
+}
+
+criterion_group!(benches, indicators);
+criterion_main!(benches);
@@ -0,0 +1,80 @@
+//! Feed arbitrary inputs and parameters to the wrappers.
+//!
+//! The wrappers must never panic, apart from their documented preconditions,
+//! nor read memory the C library did not write. Run with:
+//!
+//! ```text
+//! cargo +nightly fuzz run wrappers
+//! ```
+#![no_main]
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use ta_lib::*;
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+    volume: Vec<f64>,
+    // Small enough to hit valid periods often, large enough to also hit
+    // periods the C library rejects.
+    period: u8,
+    other_period: u8,
+    moving_average_type: u8,
+    factor: f64,
+}
+
+fuzz_target!(|input: Input| {
+    let Input {
+        high,
+        low,
+        close,
+        volume,
+        period,
+        other_period,
+        moving_average_type,
+        factor,
+    } = input;
+
+    let period = Some(period as usize);
+    let other_period = Some(other_period as usize);
+    let moving_average_type = Some(
+        MovingAverageType::ALL[moving_average_type as usize % MovingAverageType::ALL.len()],
+    );
+
+    let _ = simple_moving_average(&close, period);
+    let _ = exponential_moving_average(&close, period);
+    let _ = relative_strength_index(&close, period);
+    let _ = standard_deviation(&close, period, Some(factor));
+    let _ = moving_average(&close, period, moving_average_type);
+    let _ = bollinger_bands(&close, period, Some(factor), Some(factor), moving_average_type);
+    let _ = moving_average_convergence_divergence(&close, period, other_period, period);
+    let _ = mesa_adaptive_moving_average(&close, Some(factor), Some(factor / 2.0));
+    let _ = absolute_price_oscillator(&close, period, other_period, moving_average_type);
+    let _ = stochastic_relative_strength_index(
+        &close,
+        period,
+        other_period,
+        period,
+        moving_average_type,
+    );
+
+    // Inputs of different lengths are an error, not a panic.
+    let _ = average_true_range(&high, &low, &close, period);
+    let _ = average_directional_movement_index(&high, &low, &close, period);
+    let _ = true_range(&high, &low, &close);
+    let _ = stochastic(
+        &high,
+        &low,
+        &close,
+        period,
+        other_period,
+        moving_average_type,
+        period,
+        moving_average_type,
+    );
+
+    let _ = on_balance_volume(&close, &volume);
+    let _ = divide_checked(&close, &volume, OnZero::Error);
+});
@@ -0,0 +1,655 @@
+//! Wrappers around TA-Lib's abstract interface which describes every function
+//! of the library at runtime.
+use crate::{
+    check_aligned, check_input_len, check_lookback, check_range, checked_begin, output_len, Error,
+    IndicatorOutput,
+};
+use std::{
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_void},
+    ptr,
+};
+use ta_lib_sys as ta;
+
+/// The components of a price input, in the order [`compute_by_name()`] takes
+/// them.
+const PRICE_COMPONENTS: [u32; 6] = [
+    ta::TA_IN_PRICE_OPEN,
+    ta::TA_IN_PRICE_HIGH,
+    ta::TA_IN_PRICE_LOW,
+    ta::TA_IN_PRICE_CLOSE,
+    ta::TA_IN_PRICE_VOLUME,
+    ta::TA_IN_PRICE_OPENINTEREST,
+];
+
+/// Description of a TA function, as reported by the linked C library.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuncInfo {
+    /// The TA-Lib name of the function, e.g. `"BBANDS"`.
+    pub name: String,
+    /// The group the function belongs to, e.g. `"Overlap Studies"`.
+    pub group: String,
+    /// A short description of the function.
+    pub hint: String,
+    /// The names of the input series, e.g. `"inReal"` or `"inPriceHLC"`.
+    pub inputs: Vec<String>,
+    /// The optional parameters the function accepts.
+    pub parameters: Vec<ParameterInfo>,
+    /// The names of the output series, e.g. `"outRealUpperBand"`.
+    pub outputs: Vec<String>,
+}
+
+/// Description of an optional parameter of a TA function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterInfo {
+    /// The name of the parameter, e.g. `"optInTimePeriod"`.
+    pub name: String,
+    /// A human readable name, e.g. `"Time Period"`.
+    pub display_name: String,
+    pub hint: String,
+    /// The value TA-Lib uses when the parameter is not specified.
+    pub default: f64,
+    /// The values the parameter accepts.
+    pub range: ParameterRange,
+}
+
+/// The values an optional parameter of a TA function accepts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterRange {
+    Real {
+        min: f64,
+        max: f64,
+    },
+    Integer {
+        min: i32,
+        max: i32,
+    },
+    /// One of a list of values with their descriptions, e.g. a
+    /// [`MovingAverageType`](crate::MovingAverageType).
+    RealList(Vec<(f64, String)>),
+    IntegerList(Vec<(i32, String)>),
+}
+
+/// Look up the description of the TA function with the given `name`.
+///
+/// Returns `None` if the linked library does not know the function.
+///
+/// ```
+/// # use ta_lib::*;
+/// let info = function_info("SMA").unwrap();
+///
+/// assert_eq!("optInTimePeriod", info.parameters[0].name);
+/// ```
+pub fn function_info(name: &str) -> Option<FuncInfo> {
+    let handle = func_handle(name)?;
+
+    let mut func_info = ptr::null();
+
+    unsafe {
+        if ta::TA_GetFuncInfo(handle, &mut func_info) != ta::TA_RetCode_TA_SUCCESS {
+            return None;
+        }
+
+        let func_info = &*func_info;
+
+        Some(FuncInfo {
+            name: to_string(func_info.name),
+            group: to_string(func_info.group),
+            hint: to_string(func_info.hint),
+            inputs: (0..func_info.nbInput)
+                .filter_map(|index| {
+                    let mut info = ptr::null();
+                    (ta::TA_GetInputParameterInfo(handle, index, &mut info)
+                        == ta::TA_RetCode_TA_SUCCESS)
+                        .then(|| to_string((*info).paramName))
+                })
+                .collect(),
+            parameters: (0..func_info.nbOptInput)
+                .filter_map(|index| {
+                    let mut info = ptr::null();
+                    (ta::TA_GetOptInputParameterInfo(handle, index, &mut info)
+                        == ta::TA_RetCode_TA_SUCCESS)
+                        .then(|| parameter_info(&*info))
+                })
+                .collect(),
+            outputs: (0..func_info.nbOutput)
+                .filter_map(|index| {
+                    let mut info = ptr::null();
+                    (ta::TA_GetOutputParameterInfo(handle, index, &mut info)
+                        == ta::TA_RetCode_TA_SUCCESS)
+                        .then(|| to_string((*info).paramName))
+                })
+                .collect(),
+        })
+    }
+}
+
+/// The names of all functions the linked C library provides.
+///
+/// With `use_system_lib` an older or custom TA lib may lack functions this
+/// crate wraps. Check this list to degrade gracefully instead of failing at
+/// runtime.
+///
+/// ```
+/// # use ta_lib::*;
+/// assert!(available_functions().iter().any(|name| name == "SMA"));
+/// ```
+pub fn available_functions() -> Vec<String> {
+    unsafe extern "C" fn push_name(func_info: *const ta::TA_FuncInfo, names: *mut c_void) {
+        (*(names as *mut Vec<String>)).push(to_string((*func_info).name));
+    }
+
+    let mut names = Vec::new();
+
+    unsafe {
+        ta::TA_ForEachFunc(
+            Some(push_name),
+            &mut names as *mut Vec<String> as *mut c_void,
+        );
+    }
+
+    names
+}
+
+/// Compute the TA function with the given `name`, e.g. `"SMA"`.
+///
+/// `inputs` are the series the function takes, in the order
+/// [`FuncInfo::inputs`] lists them. A price input like `"inPriceHLC"` takes
+/// one series for each of its components, in the order open, high, low,
+/// close, volume and open interest. All series must have the same length.
+///
+/// `parameters` are the values of the leading optional parameters, in the
+/// order [`FuncInfo::parameters`] lists them. The remaining ones get their
+/// default. Integer parameters, e.g. periods, must be integral values;
+/// others are rejected with [`Error::BadParam`] rather than truncated.
+///
+/// The columns are named after the function's outputs, e.g. `"outReal"`.
+/// Integer outputs, e.g. of candlestick patterns, are converted to `f64`.
+///
+/// Returns [`Error::Unsupported`] if the linked library does not provide the
+/// function.
+///
+/// ```
+/// # use ta_lib::*;
+/// let close = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+///
+/// let sma = compute_by_name("SMA", &[&close], &[3.0])?;
+///
+/// assert_eq!(
+///     simple_moving_average(&close, Some(3))?.values,
+///     sma.columns[0].1
+/// );
+/// assert_eq!(2, sma.begin);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn compute_by_name(
+    name: &str,
+    inputs: &[&[f64]],
+    parameters: &[f64],
+) -> Result<IndicatorOutput, Error> {
+    let (handle, func_info) = func_handle_and_info(name)?;
+
+    let len = inputs.first().map_or(0, |input| input.len());
+
+    check_range(0, len)?;
+
+    trace_span!("compute_by_name", len, name, parameters);
+
+    check_input_len(len)?;
+
+    check_aligned(inputs)?;
+
+    let params = ParamHolder::with_parameters(handle, func_info, name, parameters)?;
+
+    let mut inputs = inputs.iter();
+
+    for index in 0..func_info.nbInput {
+        let mut info = ptr::null();
+        unsafe { ta::TA_GetInputParameterInfo(handle, index, &mut info) };
+        let info = unsafe { &*info };
+
+        let ret_code = match info.type_ {
+            ta::TA_InputParameterType_TA_Input_Price => {
+                let mut components = [ptr::null(); 6];
+                for (component, flag) in components.iter_mut().zip(PRICE_COMPONENTS) {
+                    if 0 != info.flags as u32 & flag {
+                        *component = next_input(&mut inputs, name)?.as_ptr();
+                    }
+                }
+
+                unsafe {
+                    ta::TA_SetInputParamPricePtr(
+                        params.0,
+                        index,
+                        components[0],
+                        components[1],
+                        components[2],
+                        components[3],
+                        components[4],
+                        components[5],
+                    )
+                }
+            }
+            ta::TA_InputParameterType_TA_Input_Real => unsafe {
+                ta::TA_SetInputParamRealPtr(
+                    params.0,
+                    index,
+                    next_input(&mut inputs, name)?.as_ptr(),
+                )
+            },
+            _ => {
+                return Err(Error::BadParam(format!(
+                    "{} takes integer inputs which are not supported",
+                    name
+                )))
+            }
+        };
+
+        if ret_code != ta::TA_RetCode_TA_SUCCESS {
+            return Err(Error::Computation(format!(
+                "Could not set input {} of {}; error: {:?}",
+                index, name, ret_code
+            )));
+        }
+    }
+
+    if 0 != inputs.len() {
+        return Err(input_count_mismatch(name));
+    }
+
+    let mut lookback = 0;
+    unsafe { ta::TA_GetLookback(params.0, &mut lookback) };
+
+    let capacity = check_lookback(len, lookback)?;
+
+    // Integer outputs are written to `integers` and converted afterwards.
+    let mut outputs = Vec::new();
+
+    for index in 0..func_info.nbOutput {
+        let mut info = ptr::null();
+        unsafe { ta::TA_GetOutputParameterInfo(handle, index, &mut info) };
+        let info = unsafe { &*info };
+
+        let mut reals: Vec<f64> = Vec::with_capacity(capacity);
+        let mut integers: Vec<i32> = Vec::new();
+
+        unsafe {
+            if ta::TA_OutputParameterType_TA_Output_Integer == info.type_ {
+                integers.reserve_exact(capacity);
+                ta::TA_SetOutputParamIntegerPtr(params.0, index, integers.as_mut_ptr());
+            } else {
+                ta::TA_SetOutputParamRealPtr(params.0, index, reals.as_mut_ptr());
+            }
+        }
+
+        outputs.push((to_string(info.paramName), reals, integers));
+    }
+
+    let mut out_begin = 0;
+    let mut out_size = 0;
+
+    unsafe {
+        let ret_code = ta::TA_CallFunc(params.0, 0, (len - 1) as _, &mut out_begin, &mut out_size);
+
+        match ret_code {
+            ta::TA_RetCode_TA_SUCCESS => Ok(IndicatorOutput {
+                columns: outputs
+                    .into_iter()
+                    .map(|(name, mut reals, mut integers)| {
+                        if 0 == integers.capacity() {
+                            reals.set_len(output_len(out_size, reals.capacity()));
+                        } else {
+                            integers.set_len(output_len(out_size, integers.capacity()));
+                            reals = integers.into_iter().map(f64::from).collect();
+                        }
+                        (name, reals)
+                    })
+                    .collect(),
+                begin: checked_begin(out_begin, len, capacity),
+            }),
+            _ => {
+                trace_error!(ret_code);
+
+                Err(Error::Computation(format!(
+                    "Could not compute {}; error: {:?}",
+                    name, ret_code
+                )))
+            }
+        }
+    }
+}
+
+/// Compute the lookback of the TA function `name`, e.g. `"SMA"`, for the given
+/// `parameters`.
+///
+/// This is the number of inputs [`compute_by_name()`] consumes before the
+/// first output value, e.g. to size the history to request for an indicator
+/// given by name. `parameters` are as for [`compute_by_name()`].
+///
+/// Returns [`Error::BadParam`] if the parameters are out of range and
+/// [`Error::Unsupported`] if the linked library does not provide the
+/// function.
+///
+/// ```
+/// # use ta_lib::*;
+/// assert_eq!(9, lookback_by_name("SMA", &[10.0])?);
+/// assert_eq!(
+///     simple_moving_average_lookback(Some(10)),
+///     Some(lookback_by_name("SMA", &[10.0])?)
+/// );
+/// # Ok::<(), Error>(())
+/// ```
+pub fn lookback_by_name(name: &str, parameters: &[f64]) -> Result<usize, Error> {
+    let (handle, func_info) = func_handle_and_info(name)?;
+
+    let params = ParamHolder::with_parameters(handle, func_info, name, parameters)?;
+
+    let mut lookback = 0;
+    let ret_code = unsafe { ta::TA_GetLookback(params.0, &mut lookback) };
+
+    if ret_code != ta::TA_RetCode_TA_SUCCESS {
+        return Err(Error::Computation(format!(
+            "Could not compute the lookback of {}; error: {:?}",
+            name, ret_code
+        )));
+    }
+
+    // The lookback functions return `-1` for parameters out of range.
+    usize::try_from(lookback).map_err(|_| {
+        Error::BadParam(format!(
+            "Parameters {:?} are out of range for {}",
+            parameters, name
+        ))
+    })
+}
+
+/// Frees the parameters of a [`compute_by_name()`] call when dropped.
+struct ParamHolder(*mut ta::TA_ParamHolder);
+
+impl ParamHolder {
+    /// Allocate the parameters of the function `handle` and set its leading
+    /// optional ones to `parameters`.
+    fn with_parameters(
+        handle: *const ta::TA_FuncHandle,
+        func_info: &ta::TA_FuncInfo,
+        name: &str,
+        parameters: &[f64],
+    ) -> Result<Self, Error> {
+        if func_info.nbOptInput < parameters.len() as _ {
+            return Err(Error::BadParam(format!(
+                "{} takes {} parameters but {} were given",
+                name,
+                func_info.nbOptInput,
+                parameters.len()
+            )));
+        }
+
+        let mut params = ptr::null_mut();
+        if unsafe { ta::TA_ParamHolderAlloc(handle, &mut params) } != ta::TA_RetCode_TA_SUCCESS {
+            return Err(Error::Computation(format!(
+                "Could not allocate parameters of {}",
+                name
+            )));
+        }
+        let params = ParamHolder(params);
+
+        for (index, value) in parameters.iter().enumerate() {
+            let mut info = ptr::null();
+            unsafe { ta::TA_GetOptInputParameterInfo(handle, index as _, &mut info) };
+
+            let ret_code = match unsafe { (*info).type_ } {
+                ta::TA_OptInputParameterType_TA_OptInput_RealRange
+                | ta::TA_OptInputParameterType_TA_OptInput_RealList => unsafe {
+                    ta::TA_SetOptInputParamReal(params.0, index as _, *value)
+                },
+                _ => {
+                    // Reject what an `as` cast would silently truncate or
+                    // saturate.
+                    if value.fract() != 0.0
+                        || !(f64::from(i32::MIN)..=f64::from(i32::MAX)).contains(value)
+                    {
+                        return Err(Error::BadParam(format!(
+                            "Parameter {} of {} must be an integer but is {}",
+                            index, name, value
+                        )));
+                    }
+
+                    unsafe { ta::TA_SetOptInputParamInteger(params.0, index as _, *value as _) }
+                }
+            };
+
+            if ret_code != ta::TA_RetCode_TA_SUCCESS {
+                return Err(Error::BadParam(format!(
+                    "Could not set parameter {} of {} to {}",
+                    index, name, value
+                )));
+            }
+        }
+
+        Ok(params)
+    }
+}
+
+impl Drop for ParamHolder {
+    fn drop(&mut self) {
+        unsafe { ta::TA_ParamHolderFree(self.0) };
+    }
+}
+
+fn next_input<'a>(
+    inputs: &mut std::slice::Iter<'_, &'a [f64]>,
+    name: &str,
+) -> Result<&'a [f64], Error> {
+    inputs
+        .next()
+        .copied()
+        .ok_or_else(|| input_count_mismatch(name))
+}
+
+fn input_count_mismatch(name: &str) -> Error {
+    Error::BadParam(format!(
+        "{} takes a different number of input series; see function_info(\"{}\").inputs",
+        name, name,
+    ))
+}
+
+/// The handle and info of the function `name`, or [`Error::Unsupported`].
+fn func_handle_and_info(
+    name: &str,
+) -> Result<(*const ta::TA_FuncHandle, &'static ta::TA_FuncInfo), Error> {
+    let unsupported = || Error::Unsupported {
+        name: name.to_string(),
+    };
+
+    let handle = func_handle(name).ok_or_else(unsupported)?;
+
+    let mut func_info = ptr::null();
+    match unsafe { ta::TA_GetFuncInfo(handle, &mut func_info) } {
+        ta::TA_RetCode_TA_SUCCESS => Ok((handle, unsafe { &*func_info })),
+        _ => Err(unsupported()),
+    }
+}
+
+fn func_handle(name: &str) -> Option<*const ta::TA_FuncHandle> {
+    let name = CString::new(name).ok()?;
+    let mut handle = ptr::null();
+
+    match unsafe { ta::TA_GetFuncHandle(name.as_ptr(), &mut handle) } {
+        ta::TA_RetCode_TA_SUCCESS => Some(handle),
+        _ => None,
+    }
+}
+
+unsafe fn parameter_info(info: &ta::TA_OptInputParameterInfo) -> ParameterInfo {
+    let range = match info.type_ {
+        ta::TA_OptInputParameterType_TA_OptInput_RealRange => {
+            let range = &*(info.dataSet as *const ta::TA_RealRange);
+            ParameterRange::Real {
+                min: range.min,
+                max: range.max,
+            }
+        }
+        ta::TA_OptInputParameterType_TA_OptInput_IntegerRange => {
+            let range = &*(info.dataSet as *const ta::TA_IntegerRange);
+            ParameterRange::Integer {
+                min: range.min,
+                max: range.max,
+            }
+        }
+        ta::TA_OptInputParameterType_TA_OptInput_RealList => {
+            let list = &*(info.dataSet as *const ta::TA_RealList);
+            ParameterRange::RealList(
+                (0..list.nbElement as usize)
+                    .map(|index| {
+                        let pair = &*list.data.add(index);
+                        (pair.value, to_string(pair.string))
+                    })
+                    .collect(),
+            )
+        }
+        _ => {
+            let list = &*(info.dataSet as *const ta::TA_IntegerList);
+            ParameterRange::IntegerList(
+                (0..list.nbElement as usize)
+                    .map(|index| {
+                        let pair = &*list.data.add(index);
+                        (pair.value, to_string(pair.string))
+                    })
+                    .collect(),
+            )
+        }
+    };
+
+    ParameterInfo {
+        name: to_string(info.paramName),
+        display_name: to_string(info.displayName),
+        hint: to_string(info.hint),
+        default: info.defaultValue,
+        range,
+    }
+}
+
+fn to_string(string: *const c_char) -> String {
+    if string.is_null() {
+        String::new()
+    } else {
+        unsafe { CStr::from_ptr(string) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+#[test]
+fn test_function_info() {
+    let info = function_info("BBANDS").unwrap();
+
+    assert_eq!("BBANDS", info.name);
+    assert_eq!(3, info.outputs.len());
+    assert_eq!(
+        vec!["outRealUpperBand", "outRealMiddleBand", "outRealLowerBand"],
+        info.outputs
+    );
+    assert_eq!(vec!["inReal"], info.inputs);
+    assert_eq!(4, info.parameters.len());
+    assert_eq!(5.0, info.parameters[0].default);
+    assert!(matches!(
+        info.parameters[0].range,
+        ParameterRange::Integer { min: 2, .. }
+    ));
+    assert!(matches!(
+        &info.parameters[3].range,
+        ParameterRange::IntegerList(list) if list.len() == 9
+    ));
+}
+
+#[test]
+fn test_function_info_unknown() {
+    assert!(function_info("NOT_A_FUNCTION").is_none());
+}
+
+#[test]
+fn test_available_functions() {
+    let names = available_functions();
+
+    assert!(!names.is_empty());
+    assert!(names.iter().any(|name| name == "SMA"));
+    assert!(names.iter().all(|name| function_info(name).is_some()));
+}
+
+#[test]
+fn test_compute_by_name() {
+    let high = [3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0];
+    let low = [1.0, 2.0, 3.0, 4.0, 4.5, 5.0, 6.0, 8.0];
+    let close = [2.0, 3.0, 4.0, 5.0, 6.0, 6.5, 8.0, 9.0];
+
+    let atr = compute_by_name("ATR", &[&high, &low, &close], &[3.0]).unwrap();
+    let crate::Output {
+        values: expected,
+        begin,
+    } = crate::average_true_range(high, low, close, Some(3)).unwrap();
+
+    assert_eq!(begin, atr.begin);
+    assert_eq!(vec![("outReal".to_string(), expected)], atr.columns);
+
+    let bands = compute_by_name("BBANDS", &[&close], &[]).unwrap();
+
+    assert_eq!(3, bands.columns.len());
+    assert_eq!(4, bands.begin);
+
+    assert!(matches!(
+        compute_by_name("ATR", &[&high, &low], &[]),
+        Err(Error::BadParam(_))
+    ));
+    assert!(matches!(
+        compute_by_name("SMA", &[&close], &[3.0, 4.0]),
+        Err(Error::BadParam(_))
+    ));
+    assert!(matches!(
+        compute_by_name("SMA", &[&close], &[1.0]),
+        Err(Error::Computation(_))
+    ));
+}
+
+#[test]
+fn test_compute_by_name_unsupported() {
+    assert!(matches!(
+        compute_by_name("NOT_A_FUNCTION", &[&[1.0, 2.0]], &[]),
+        Err(Error::Unsupported { name }) if name == "NOT_A_FUNCTION"
+    ));
+}
+
+#[test]
+fn test_lookback_by_name() {
+    assert_eq!(9, lookback_by_name("SMA", &[10.0]).unwrap());
+    assert_eq!(
+        crate::average_true_range_lookback(Some(14)),
+        Some(lookback_by_name("ATR", &[]).unwrap())
+    );
+    assert_eq!(19, lookback_by_name("BBANDS", &[20.0]).unwrap());
+
+    // Matches the `begin` of computing the function by name.
+    let close = (0..40).map(|close| close as f64).collect::<Vec<_>>();
+    assert_eq!(
+        compute_by_name("MACD", &[&close], &[]).unwrap().begin,
+        lookback_by_name("MACD", &[]).unwrap()
+    );
+
+    assert!(matches!(
+        lookback_by_name("SMA", &[1.0]),
+        Err(Error::BadParam(_))
+    ));
+    assert!(matches!(
+        lookback_by_name("SMA", &[3.0, 4.0]),
+        Err(Error::BadParam(_))
+    ));
+    for period in [10.5, f64::NAN, f64::INFINITY, 1e10] {
+        assert!(matches!(
+            lookback_by_name("SMA", &[period]),
+            Err(Error::BadParam(_))
+        ));
+    }
+    assert!(matches!(
+        lookback_by_name("NOT_A_FUNCTION", &[]),
+        Err(Error::Unsupported { name }) if name == "NOT_A_FUNCTION"
+    ));
+}
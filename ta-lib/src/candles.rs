@@ -0,0 +1,446 @@
+use crate::Error;
+#[cfg(not(feature = "pure_rust"))]
+use crate::{typical_price, Series};
+
+/// Open, high, low, close and volume columns of a price series.
+///
+/// All columns have the same length and each bar's high is at least its low.
+/// This is checked once on construction so malformed data is caught before it
+/// reaches the C library where it would produce silent nonsense.
+///
+/// The volume column may be empty if the data has no volume.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candles {
+    open: Vec<f64>,
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+    volume: Vec<f64>,
+}
+
+impl Candles {
+    /// Create a new set of candles from separate columns.
+    ///
+    /// Pass an empty `volume` if the data has no volume.
+    ///
+    /// Returns [`Error::LengthMismatch`] if the columns differ in length and
+    /// [`Error::InconsistentBar`] for the first bar whose high is below its
+    /// low.
+    pub fn try_new(
+        open: Vec<f64>,
+        high: Vec<f64>,
+        low: Vec<f64>,
+        close: Vec<f64>,
+        volume: Vec<f64>,
+    ) -> Result<Self, Error> {
+        let optional_volume = (!volume.is_empty()).then_some(&volume);
+
+        for column in [&high, &low, &close].into_iter().chain(optional_volume) {
+            if column.len() != open.len() {
+                return Err(Error::LengthMismatch {
+                    expected: open.len(),
+                    actual: column.len(),
+                });
+            }
+        }
+
+        if let Some(index) = high.iter().zip(&low).position(|(high, low)| high < low) {
+            return Err(Error::InconsistentBar { index });
+        }
+
+        Ok(Self {
+            open,
+            high,
+            low,
+            close,
+            volume,
+        })
+    }
+
+    pub fn open(&self) -> &[f64] {
+        &self.open
+    }
+
+    pub fn high(&self) -> &[f64] {
+        &self.high
+    }
+
+    pub fn low(&self) -> &[f64] {
+        &self.low
+    }
+
+    pub fn close(&self) -> &[f64] {
+        &self.close
+    }
+
+    pub fn volume(&self) -> &[f64] {
+        &self.volume
+    }
+
+    /// The number of bars.
+    pub fn len(&self) -> usize {
+        self.close.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.close.is_empty()
+    }
+
+    /// The [`typical_price()`] of each candle as a [`Series`] to chain
+    /// further computations onto.
+    #[cfg(not(feature = "pure_rust"))]
+    pub fn typical_price(&self) -> Result<Series, Error> {
+        typical_price(&self.high, &self.low, &self.close)
+    }
+
+    /// Aggregate every `factor` consecutive bars into one.
+    ///
+    /// Each resulting bar takes the first open, the highest high, the lowest
+    /// low, the last close and the summed volume of its group. E.g. a `factor`
+    /// of `5` turns 1-minute bars into 5-minute bars.
+    ///
+    /// If the number of bars is not a multiple of `factor` the trailing
+    /// partial group is kept as a last, shorter bar. This mirrors how an
+    /// in-progress bar of the higher timeframe looks in a live feed.
+    ///
+    /// Returns [`Error::BadParam`] if `factor` is zero.
+    pub fn resample(&self, factor: usize) -> Result<Candles, Error> {
+        if factor == 0 {
+            return Err(Error::BadParam(
+                "Resampling factor must be at least 1.".to_string(),
+            ));
+        }
+
+        let len = self.len().div_ceil(factor);
+        let mut candles = Candles {
+            open: Vec::with_capacity(len),
+            high: Vec::with_capacity(len),
+            low: Vec::with_capacity(len),
+            close: Vec::with_capacity(len),
+            volume: Vec::with_capacity(len),
+        };
+
+        for start in (0..self.len()).step_by(factor) {
+            let range = start..(start + factor).min(self.len());
+
+            candles.open.push(self.open[range.start]);
+            candles.high.push(
+                self.high[range.clone()]
+                    .iter()
+                    .copied()
+                    .fold(f64::NEG_INFINITY, f64::max),
+            );
+            candles.low.push(
+                self.low[range.clone()]
+                    .iter()
+                    .copied()
+                    .fold(f64::INFINITY, f64::min),
+            );
+            candles.close.push(self.close[range.end - 1]);
+            if !self.volume.is_empty() {
+                candles.volume.push(self.volume[range].iter().sum());
+            }
+        }
+
+        Ok(candles)
+    }
+}
+
+/// A column of CSV data, selected by its header name or its index.
+#[cfg(feature = "csv")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Column {
+    /// The column with this header. Matched ignoring case.
+    Name(String),
+    Index(usize),
+}
+
+/// Which CSV columns hold the open, high, low, close and volume values.
+///
+/// The default expects the headers `open`, `high`, `low`, `close` and
+/// `volume`.
+#[cfg(feature = "csv")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMapping {
+    /// Set to `false` if the first record does not contain headers. Columns
+    /// then must be selected by index.
+    pub has_headers: bool,
+    pub open: Column,
+    pub high: Column,
+    pub low: Column,
+    pub close: Column,
+    /// `None` if the data has no volume.
+    ///
+    /// A missing volume column is not an error, the candles' volume is left
+    /// empty then.
+    pub volume: Option<Column>,
+}
+
+#[cfg(feature = "csv")]
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self {
+            has_headers: true,
+            open: Column::Name("open".to_string()),
+            high: Column::Name("high".to_string()),
+            low: Column::Name("low".to_string()),
+            close: Column::Name("close".to_string()),
+            volume: Some(Column::Name("volume".to_string())),
+        }
+    }
+}
+
+#[cfg(feature = "csv")]
+impl Candles {
+    /// Read candles from CSV data.
+    ///
+    /// ```
+    /// # use ta_lib::*;
+    /// let data = "date,open,high,low,close\n\
+    ///             2021-10-25,1.0,1.5,0.5,1.2\n\
+    ///             2021-10-26,1.2,1.7,1.0,1.6\n";
+    ///
+    /// let candles = Candles::from_csv_reader(data.as_bytes(), ColumnMapping::default()).unwrap();
+    ///
+    /// assert_eq!(&[1.2, 1.6], candles.close());
+    /// assert!(candles.volume().is_empty());
+    /// ```
+    pub fn from_csv_reader<R: std::io::Read>(
+        reader: R,
+        mapping: ColumnMapping,
+    ) -> Result<Self, Error> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(mapping.has_headers)
+            .trim(csv::Trim::All)
+            .from_reader(reader);
+
+        let headers = if mapping.has_headers {
+            Some(
+                reader
+                    .headers()
+                    .map_err(|error| Error::Csv(error.to_string()))?
+                    .clone(),
+            )
+        } else {
+            None
+        };
+
+        let index_of = |column: &Column| -> Option<usize> {
+            match column {
+                Column::Index(index) => Some(*index),
+                Column::Name(name) => headers
+                    .as_ref()?
+                    .iter()
+                    .position(|header| header.eq_ignore_ascii_case(name)),
+            }
+        };
+        let required_index_of = |column: &Column| -> Result<usize, Error> {
+            index_of(column).ok_or_else(|| Error::Csv(format!("missing column {:?}", column)))
+        };
+
+        let open = required_index_of(&mapping.open)?;
+        let high = required_index_of(&mapping.high)?;
+        let low = required_index_of(&mapping.low)?;
+        let close = required_index_of(&mapping.close)?;
+        let volume = mapping.volume.as_ref().and_then(index_of);
+
+        let mut columns = [Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+
+        for (line, record) in reader.records().enumerate() {
+            let record = record.map_err(|error| Error::Csv(error.to_string()))?;
+
+            for (column, index) in
+                columns
+                    .iter_mut()
+                    .zip([Some(open), Some(high), Some(low), Some(close), volume])
+            {
+                if let Some(index) = index {
+                    let field = record.get(index).ok_or_else(|| {
+                        Error::Csv(format!("record {} has no column {}", line, index))
+                    })?;
+                    column.push(field.parse::<f64>().map_err(|error| {
+                        Error::Csv(format!(
+                            "record {}, column {}: {}: {:?}",
+                            line, index, error, field
+                        ))
+                    })?);
+                }
+            }
+        }
+
+        let [open, high, low, close, volume] = columns;
+
+        Candles::try_new(open, high, low, close, volume)
+    }
+}
+
+#[test]
+fn test_candles_try_new() {
+    let candles = Candles::try_new(
+        vec![1.0, 2.0, 3.0],
+        vec![1.5, 2.5, 3.5],
+        vec![0.5, 1.5, 2.5],
+        vec![1.2, 2.2, 3.2],
+        vec![10.0, 20.0, 30.0],
+    )
+    .unwrap();
+
+    assert_eq!(3, candles.len());
+    assert_eq!(&[1.2, 2.2, 3.2], candles.close());
+}
+
+#[test]
+fn test_candles_try_new_length_mismatch() {
+    assert!(matches!(
+        Candles::try_new(
+            vec![1.0, 2.0, 3.0],
+            vec![1.5, 2.5, 3.5],
+            vec![0.5, 1.5],
+            vec![1.2, 2.2, 3.2],
+            vec![10.0, 20.0, 30.0],
+        ),
+        Err(Error::LengthMismatch {
+            expected: 3,
+            actual: 2
+        })
+    ));
+}
+
+#[test]
+fn test_candles_try_new_empty_column() {
+    // Only the volume column may be empty.
+    assert!(matches!(
+        Candles::try_new(
+            vec![1.0, 2.0, 3.0],
+            vec![1.5, 2.5, 3.5],
+            Vec::new(),
+            vec![1.2, 2.2, 3.2],
+            Vec::new(),
+        ),
+        Err(Error::LengthMismatch {
+            expected: 3,
+            actual: 0
+        })
+    ));
+}
+
+#[test]
+fn test_candles_try_new_high_below_low() {
+    assert!(matches!(
+        Candles::try_new(
+            vec![1.0, 2.0, 3.0],
+            vec![1.5, 1.0, 3.5],
+            vec![0.5, 1.5, 2.5],
+            vec![1.2, 2.2, 3.2],
+            vec![10.0, 20.0, 30.0],
+        ),
+        Err(Error::InconsistentBar { index: 1 })
+    ));
+}
+
+#[test]
+fn test_candles_resample() {
+    let open = (0..20).map(|i| i as f64).collect::<Vec<_>>();
+    let high = open.iter().map(|open| open + 2.0).collect::<Vec<_>>();
+    let low = open.iter().map(|open| open - 1.0).collect::<Vec<_>>();
+    let close = open.iter().map(|open| open + 0.5).collect::<Vec<_>>();
+    let volume = vec![1.0; 20];
+
+    let candles = Candles::try_new(open, high, low, close, volume)
+        .unwrap()
+        .resample(5)
+        .unwrap();
+
+    assert_eq!(4, candles.len());
+    assert_eq!(&[0.0, 5.0, 10.0, 15.0], candles.open());
+    assert_eq!(&[6.0, 11.0, 16.0, 21.0], candles.high());
+    assert_eq!(&[-1.0, 4.0, 9.0, 14.0], candles.low());
+    assert_eq!(&[4.5, 9.5, 14.5, 19.5], candles.close());
+    assert_eq!(&[5.0, 5.0, 5.0, 5.0], candles.volume());
+}
+
+#[test]
+fn test_candles_resample_partial_group() {
+    let candles = Candles::try_new(
+        vec![1.0, 2.0, 3.0],
+        vec![1.5, 2.5, 3.5],
+        vec![0.5, 1.5, 2.5],
+        vec![1.2, 2.2, 3.2],
+        vec![10.0, 20.0, 30.0],
+    )
+    .unwrap()
+    .resample(2)
+    .unwrap();
+
+    assert_eq!(2, candles.len());
+    assert_eq!(&[1.0, 3.0], candles.open());
+    assert_eq!(&[2.5, 3.5], candles.high());
+    assert_eq!(&[0.5, 2.5], candles.low());
+    assert_eq!(&[2.2, 3.2], candles.close());
+    assert_eq!(&[30.0, 30.0], candles.volume());
+
+    assert!(matches!(candles.resample(0), Err(Error::BadParam(_))));
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_candles_from_csv_reader() {
+    let data = "\
+        Date, Open, High, Low, Close, Volume
+        1, 1.087130, 1.087130, 1.087010, 1.087130, 10
+        2, 1.087120, 1.087120, 1.087120, 1.087120, 20
+        3, 1.087120, 1.087220, 1.087080, 1.087220, 30
+        4, 1.087220, 1.087230, 1.087170, 1.087230, 40
+        5, 1.087230, 1.087180, 1.087110, 1.087110, 50
+        6, 1.087110, 1.087160, 1.087010, 1.087120, 60";
+
+    let candles = Candles::from_csv_reader(data.as_bytes(), ColumnMapping::default()).unwrap();
+
+    assert_eq!(6, candles.len());
+    assert_eq!(&[10.0, 20.0, 30.0, 40.0, 50.0, 60.0], candles.volume());
+
+    #[cfg(not(feature = "pure_rust"))]
+    {
+        let crate::Output {
+            values: atr_values,
+            begin,
+        } = crate::average_true_range(candles.high(), candles.low(), candles.close(), Some(3))
+            .unwrap();
+
+        assert_eq!(3, begin);
+        assert_eq!(3, atr_values.len());
+    }
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_candles_from_csv_reader_by_index() {
+    let data = "1.0,1.5,0.5,1.2\n1.2,1.7,1.0,1.6\n";
+
+    let candles = Candles::from_csv_reader(
+        data.as_bytes(),
+        ColumnMapping {
+            has_headers: false,
+            open: Column::Index(0),
+            high: Column::Index(1),
+            low: Column::Index(2),
+            close: Column::Index(3),
+            volume: None,
+        },
+    )
+    .unwrap();
+
+    assert_eq!(&[1.5, 1.7], candles.high());
+    assert!(candles.volume().is_empty());
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn test_candles_from_csv_reader_missing_column() {
+    let data = "open,high,low\n1.0,1.5,0.5\n";
+
+    assert!(matches!(
+        Candles::from_csv_reader(data.as_bytes(), ColumnMapping::default()),
+        Err(Error::Csv(_))
+    ));
+}
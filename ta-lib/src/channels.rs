@@ -0,0 +1,483 @@
+//! Price channels and bands composed from wrapped TA functions.
+use crate::{
+    average_true_range, check_aligned, exponential_moving_average, nan_padded, rolling_max,
+    rolling_min, Error, IndicatorOutput, Output,
+};
+
+/// The upper, middle and lower bands returned by [`keltner_channels()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeltnerChannels {
+    pub upper: Vec<f64>,
+    pub middle: Vec<f64>,
+    pub lower: Vec<f64>,
+    /// The index of the first candle to have an associated value.
+    pub begin: usize,
+}
+
+impl From<KeltnerChannels> for IndicatorOutput {
+    fn from(channels: KeltnerChannels) -> Self {
+        Self {
+            columns: vec![
+                ("upper".to_string(), channels.upper),
+                ("middle".to_string(), channels.middle),
+                ("lower".to_string(), channels.lower),
+            ],
+            begin: channels.begin,
+        }
+    }
+}
+
+/// Compute [Keltner Channels](https://en.wikipedia.org/wiki/Keltner_channel).
+///
+/// The middle band is the EMA of the closes over `ema_period`. The upper and
+/// lower bands are `multiplier` times the ATR over `atr_period` above and below
+/// it.
+///
+/// The EMA needs `ema_period - 1` candles to warm up while the ATR needs
+/// `atr_period`, as its first true range needs a previous close. The bands
+/// start at the later of the two `begin`s. The leading values of the component
+/// that starts earlier are dropped so that all three bands line up.
+///
+/// Returns [`Error::LengthMismatch`] if `high` or `low` differ in length from
+/// `close`.
+///
+/// Returns the bands and the index of the first candle to have an associated
+/// value.
+pub fn keltner_channels(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    ema_period: usize,
+    atr_period: usize,
+    multiplier: f64,
+) -> Result<KeltnerChannels, Error> {
+    check_aligned(&[close, high, low])?;
+
+    let Output {
+        values: ema,
+        begin: ema_begin,
+    } = exponential_moving_average(close, Some(ema_period))?;
+    let Output {
+        values: atr,
+        begin: atr_begin,
+    } = average_true_range(high, low, close, Some(atr_period))?;
+
+    let begin = ema_begin.max(atr_begin);
+    let middle = ema[begin - ema_begin..].to_vec();
+    let atr = &atr[begin - atr_begin..];
+
+    Ok(KeltnerChannels {
+        upper: middle
+            .iter()
+            .zip(atr)
+            .map(|(middle, atr)| middle + multiplier * atr)
+            .collect(),
+        lower: middle
+            .iter()
+            .zip(atr)
+            .map(|(middle, atr)| middle - multiplier * atr)
+            .collect(),
+        middle,
+        begin,
+    })
+}
+
+/// Compute [Keltner Channels](https://en.wikipedia.org/wiki/Keltner_channel).
+///
+/// Same as [`keltner_channels()`] but each band has as many values as there
+/// are candles.
+///
+/// Candles without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+pub fn keltner_channels_padded(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    ema_period: usize,
+    atr_period: usize,
+    multiplier: f64,
+) -> Result<KeltnerChannels, Error> {
+    let channels = keltner_channels(high, low, close, ema_period, atr_period, multiplier)?;
+
+    Ok(KeltnerChannels {
+        upper: nan_padded(channels.upper, channels.begin, close.len()),
+        middle: nan_padded(channels.middle, channels.begin, close.len()),
+        lower: nan_padded(channels.lower, channels.begin, close.len()),
+        begin: channels.begin,
+    })
+}
+
+/// Compute [Keltner Channels](https://en.wikipedia.org/wiki/Keltner_channel).
+///
+/// Same as [`keltner_channels()`] but returns the bands as the columns
+/// `"upper"`, `"middle"` and `"lower"`.
+pub fn keltner_channels_columns(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    ema_period: usize,
+    atr_period: usize,
+    multiplier: f64,
+) -> Result<IndicatorOutput, Error> {
+    keltner_channels(high, low, close, ema_period, atr_period, multiplier).map(Into::into)
+}
+
+/// The upper, middle and lower channels returned by [`donchian_channels()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DonchianChannels {
+    pub upper: Vec<f64>,
+    pub middle: Vec<f64>,
+    pub lower: Vec<f64>,
+    /// The index of the first candle to have an associated value.
+    pub begin: usize,
+}
+
+impl From<DonchianChannels> for IndicatorOutput {
+    fn from(channels: DonchianChannels) -> Self {
+        Self {
+            columns: vec![
+                ("upper".to_string(), channels.upper),
+                ("middle".to_string(), channels.middle),
+                ("lower".to_string(), channels.lower),
+            ],
+            begin: channels.begin,
+        }
+    }
+}
+
+/// Compute [Donchian Channels](https://en.wikipedia.org/wiki/Donchian_channel).
+///
+/// The upper channel is the highest high and the lower channel the lowest low
+/// of the trailing `period` candles. The middle channel is halfway between
+/// them.
+///
+/// Returns [`Error::LengthMismatch`] if `low` differs in length from `high`.
+///
+/// Returns the channels and the index of the first candle to have an
+/// associated value.
+pub fn donchian_channels(
+    high: &[f64],
+    low: &[f64],
+    period: usize,
+) -> Result<DonchianChannels, Error> {
+    check_aligned(&[high, low])?;
+
+    let Output {
+        values: upper,
+        begin,
+    } = rolling_max(high, Some(period))?;
+    let Output { values: lower, .. } = rolling_min(low, Some(period))?;
+
+    Ok(DonchianChannels {
+        middle: upper
+            .iter()
+            .zip(&lower)
+            .map(|(upper, lower)| (upper + lower) / 2.0)
+            .collect(),
+        upper,
+        lower,
+        begin,
+    })
+}
+
+/// Compute [Donchian Channels](https://en.wikipedia.org/wiki/Donchian_channel).
+///
+/// Same as [`donchian_channels()`] but each channel has as many values as
+/// there are candles.
+///
+/// Candles without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+pub fn donchian_channels_padded(
+    high: &[f64],
+    low: &[f64],
+    period: usize,
+) -> Result<DonchianChannels, Error> {
+    let channels = donchian_channels(high, low, period)?;
+
+    Ok(DonchianChannels {
+        upper: nan_padded(channels.upper, channels.begin, high.len()),
+        middle: nan_padded(channels.middle, channels.begin, high.len()),
+        lower: nan_padded(channels.lower, channels.begin, high.len()),
+        begin: channels.begin,
+    })
+}
+
+/// Compute [Donchian Channels](https://en.wikipedia.org/wiki/Donchian_channel).
+///
+/// Same as [`donchian_channels()`] but returns the channels as the columns
+/// `"upper"`, `"middle"` and `"lower"`.
+pub fn donchian_channels_columns(
+    high: &[f64],
+    low: &[f64],
+    period: usize,
+) -> Result<IndicatorOutput, Error> {
+    donchian_channels(high, low, period).map(Into::into)
+}
+
+/// The trend line and direction returned by [`super_trend()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuperTrend {
+    /// The lower band in an uptrend, the upper band in a downtrend.
+    pub line: Vec<f64>,
+    /// `1` in an uptrend, `-1` in a downtrend.
+    pub direction: Vec<i32>,
+    /// The index of the first candle to have an associated value.
+    pub begin: usize,
+}
+
+impl From<SuperTrend> for IndicatorOutput {
+    fn from(super_trend: SuperTrend) -> Self {
+        Self {
+            columns: vec![
+                ("line".to_string(), super_trend.line),
+                (
+                    "direction".to_string(),
+                    super_trend.direction.into_iter().map(f64::from).collect(),
+                ),
+            ],
+            begin: super_trend.begin,
+        }
+    }
+}
+
+/// Compute [SuperTrend](https://www.tradingview.com/support/solutions/43000634738-supertrend/).
+///
+/// The basic bands are `multiplier` times the ATR over `atr_period` above and
+/// below the midpoint of each candle's high and low. The final upper band only
+/// moves down and the final lower band only moves up, unless the previous
+/// close broke through them.
+///
+/// The trend turns up when a close is above the final upper band and down when
+/// it is below the final lower band. The line follows the lower band in an
+/// uptrend and the upper band in a downtrend. The trend starts out down.
+///
+/// Returns [`Error::LengthMismatch`] if `high` or `low` differ in length from
+/// `close`.
+///
+/// Returns the line, the direction and the index of the first candle to have
+/// an associated value, the `begin` of the ATR.
+pub fn super_trend(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    atr_period: usize,
+    multiplier: f64,
+) -> Result<SuperTrend, Error> {
+    check_aligned(&[close, high, low])?;
+
+    let Output { values: atr, begin } = average_true_range(high, low, close, Some(atr_period))?;
+
+    let mut line = Vec::with_capacity(atr.len());
+    let mut direction = Vec::with_capacity(atr.len());
+
+    let mut upper = f64::NAN;
+    let mut lower = f64::NAN;
+    let mut trend = -1;
+
+    for (index, atr) in (begin..).zip(atr) {
+        let midpoint = (high[index] + low[index]) / 2.0;
+        let basic_upper = midpoint + multiplier * atr;
+        let basic_lower = midpoint - multiplier * atr;
+
+        if index == begin {
+            upper = basic_upper;
+            lower = basic_lower;
+        } else {
+            // The bands only ever tighten, unless the previous close broke
+            // through them.
+            let previous_close = close[index - 1];
+
+            if basic_upper < upper || previous_close > upper {
+                upper = basic_upper;
+            }
+            if basic_lower > lower || previous_close < lower {
+                lower = basic_lower;
+            }
+
+            if -1 == trend && close[index] > upper {
+                trend = 1;
+            } else if 1 == trend && close[index] < lower {
+                trend = -1;
+            }
+        }
+
+        line.push(if 1 == trend { lower } else { upper });
+        direction.push(trend);
+    }
+
+    Ok(SuperTrend {
+        line,
+        direction,
+        begin,
+    })
+}
+
+/// Compute [SuperTrend](https://www.tradingview.com/support/solutions/43000634738-supertrend/).
+///
+/// Same as [`super_trend()`] but the line and direction have as many values as
+/// there are candles.
+///
+/// Candles without an associated value, i.e. those before `begin`, get
+/// `f64::NAN` and a direction of `0`.
+pub fn super_trend_padded(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    atr_period: usize,
+    multiplier: f64,
+) -> Result<SuperTrend, Error> {
+    let super_trend = super_trend(high, low, close, atr_period, multiplier)?;
+
+    let mut direction = vec![0; super_trend.begin];
+    direction.extend(super_trend.direction);
+
+    Ok(SuperTrend {
+        line: nan_padded(super_trend.line, super_trend.begin, close.len()),
+        direction,
+        begin: super_trend.begin,
+    })
+}
+
+/// Compute [SuperTrend](https://www.tradingview.com/support/solutions/43000634738-supertrend/).
+///
+/// Same as [`super_trend()`] but returns the line and direction as the
+/// columns `"line"` and `"direction"`.
+pub fn super_trend_columns(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    atr_period: usize,
+    multiplier: f64,
+) -> Result<IndicatorOutput, Error> {
+    super_trend(high, low, close, atr_period, multiplier).map(Into::into)
+}
+
+#[test]
+fn test_keltner_channels() {
+    let high = crate::SAMPLE_HIGH;
+    let low = crate::SAMPLE_LOW;
+    let close = crate::SAMPLE_CLOSE;
+
+    // EMA(5) starts at 4, ATR(7) at 7.
+    let channels = keltner_channels(&high, &low, &close, 5, 7, 2.0).unwrap();
+
+    assert_eq!(7, channels.begin);
+    assert_eq!(close.len() - channels.begin, channels.middle.len());
+
+    let Output {
+        values: ema,
+        begin: ema_begin,
+    } = exponential_moving_average(close, Some(5)).unwrap();
+    assert_eq!(ema[channels.begin - ema_begin..], channels.middle[..]);
+
+    let wide = keltner_channels(&high, &low, &close, 5, 7, 3.0).unwrap();
+
+    assert_eq!(channels.middle, wide.middle);
+
+    for index in 0..channels.middle.len() {
+        assert!(channels.lower[index] <= channels.middle[index]);
+        assert!(channels.middle[index] <= channels.upper[index]);
+        assert!(
+            wide.upper[index] - wide.lower[index] >= channels.upper[index] - channels.lower[index]
+        );
+    }
+
+    assert_eq!(
+        Err(Error::LengthMismatch {
+            expected: 19,
+            actual: 20
+        }),
+        keltner_channels(&high, &low, &close[1..], 5, 7, 2.0)
+    );
+}
+
+#[test]
+fn test_donchian_channels() {
+    let high = crate::SAMPLE_HIGH;
+    let low = crate::SAMPLE_LOW;
+
+    let channels = donchian_channels(&high, &low, 3).unwrap();
+
+    assert_eq!(2, channels.begin);
+
+    for index in channels.begin..high.len() {
+        let window = index + 1 - 3..=index;
+        let value = index - channels.begin;
+
+        assert_eq!(
+            high[window.clone()]
+                .iter()
+                .cloned()
+                .fold(f64::MIN, f64::max),
+            channels.upper[value]
+        );
+        assert_eq!(
+            low[window].iter().cloned().fold(f64::MAX, f64::min),
+            channels.lower[value]
+        );
+        assert!(channels.lower[value] <= channels.middle[value]);
+        assert!(channels.middle[value] <= channels.upper[value]);
+    }
+
+    // The highs are flat from index 15 to 18 so the upper channel does not
+    // decrease while the window moves through them.
+    for value in 15 - channels.begin..18 - channels.begin {
+        assert!(channels.upper[value] <= channels.upper[value + 1]);
+    }
+
+    assert_eq!(
+        Err(Error::LengthMismatch {
+            expected: 20,
+            actual: 19
+        }),
+        donchian_channels(&high, &low[1..], 3)
+    );
+}
+
+#[test]
+fn test_super_trend() {
+    let close = (0..40)
+        .map(|index| 100.0 + index as f64)
+        .collect::<Vec<_>>();
+    let high = close.iter().map(|close| close + 0.5).collect::<Vec<_>>();
+    let low = close.iter().map(|close| close - 0.5).collect::<Vec<_>>();
+
+    let super_trend = super_trend(&high, &low, &close, 10, 3.0).unwrap();
+
+    assert_eq!(10, super_trend.begin);
+    assert_eq!(close.len() - super_trend.begin, super_trend.line.len());
+    assert_eq!(super_trend.line.len(), super_trend.direction.len());
+
+    // The trend starts out down and turns up within a few candles.
+    let up = super_trend
+        .direction
+        .iter()
+        .position(|direction| 1 == *direction)
+        .unwrap();
+
+    assert!(up < 10);
+
+    for value in up..super_trend.line.len() {
+        assert_eq!(1, super_trend.direction[value]);
+        assert!(super_trend.line[value] < close[super_trend.begin + value]);
+    }
+
+    // The lower band never moves down in an uptrend.
+    for value in up + 1..super_trend.line.len() {
+        assert!(super_trend.line[value - 1] <= super_trend.line[value]);
+    }
+
+    let padded = super_trend_padded(&high, &low, &close, 10, 3.0).unwrap();
+
+    assert_eq!(close.len(), padded.line.len());
+    assert_eq!(vec![0; 10], padded.direction[..10]);
+    assert_eq!(super_trend.direction, padded.direction[10..]);
+
+    assert_eq!(
+        Err(Error::LengthMismatch {
+            expected: 40,
+            actual: 39
+        }),
+        crate::super_trend(&high[1..], &low, &close, 10, 3.0)
+    );
+}
@@ -0,0 +1,132 @@
+//! The wrapper a panic happened in, see [`with_panic_context()`].
+use std::{
+    cell::RefCell,
+    fmt::Debug,
+    panic::{self, AssertUnwindSafe},
+    thread,
+};
+
+thread_local! {
+    /// The innermost wrapper running when this thread last panicked.
+    static PANICKED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Records the wrapper it is dropped in should this thread be panicking.
+///
+/// Formatting is deferred until then so wrappers writing into a caller's
+/// buffer stay allocation free.
+pub(crate) struct PanicContext<'a> {
+    name: &'a str,
+    len: usize,
+    parameters: &'a [(&'a str, &'a dyn Debug)],
+}
+
+impl<'a> PanicContext<'a> {
+    pub(crate) fn enter(
+        name: &'a str,
+        len: usize,
+        parameters: &'a [(&'a str, &'a dyn Debug)],
+    ) -> Self {
+        Self {
+            name,
+            len,
+            parameters,
+        }
+    }
+}
+
+impl Drop for PanicContext<'_> {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            return;
+        }
+
+        // Unwinding drops the innermost context first; keep that one.
+        PANICKED.with(|panicked| {
+            panicked.borrow_mut().get_or_insert_with(|| {
+                let mut context = format!("{}() over {} inputs", self.name, self.len);
+
+                for (index, (name, value)) in self.parameters.iter().enumerate() {
+                    context += if 0 == index { " with " } else { ", " };
+                    context += &format!("{} = {:?}", name, value);
+                }
+
+                context
+            });
+        });
+    }
+}
+
+/// Run `f`, adding the function name, input length and parameters of the
+/// wrapper that panicked, if any, to the message of a panic in `f`.
+///
+/// The panic is resumed with the annotated message, a `String`. Without a
+/// wrapper involved the panic is resumed as is.
+///
+/// Malformed calls, e.g. with inputs of different lengths, and errors the C
+/// library reports are returned as [`Error`](crate::Error)s and need no
+/// context. This is for the panics left, e.g. of a closure passed to
+/// [`rolling_apply()`](crate::rolling_apply) or of a wrapper's internal
+/// consistency checks.
+///
+/// ```
+/// # use ta_lib::*;
+/// let panic = std::panic::catch_unwind(|| {
+///     with_panic_context(|| rolling_apply(&[1.0, 2.0], 2, |_| panic!("No window")))
+/// })
+/// .unwrap_err();
+///
+/// assert_eq!(
+///     "No window in rolling_apply() over 2 inputs with period = 2",
+///     panic.downcast_ref::<String>().unwrap()
+/// );
+/// ```
+pub fn with_panic_context<T>(f: impl FnOnce() -> T) -> T {
+    PANICKED.with(|panicked| panicked.borrow_mut().take());
+
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let Some(context) = PANICKED.with(|panicked| panicked.borrow_mut().take()) else {
+            panic::resume_unwind(payload)
+        };
+
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Panic".to_string());
+
+        panic::resume_unwind(Box::new(format!("{} in {}", message, context)))
+    })
+}
+
+#[test]
+fn test_with_panic_context() {
+    let close = vec![1.5; 20];
+
+    let panic = panic::catch_unwind(|| {
+        with_panic_context(|| {
+            crate::rolling_apply(&close, 14, |window| {
+                assert!(window.len() < 14, "Window too long");
+                0.0
+            })
+        })
+    })
+    .unwrap_err();
+
+    assert_eq!(
+        Some(&"Window too long in rolling_apply() over 20 inputs with period = 14".to_string()),
+        panic.downcast_ref::<String>()
+    );
+
+    // The context of an earlier panic does not leak into unrelated ones.
+    let panic = panic::catch_unwind(|| with_panic_context(|| panic!("Unrelated"))).unwrap_err();
+    assert_eq!(Some(&"Unrelated"), panic.downcast_ref::<&str>());
+
+    assert_eq!(
+        5,
+        with_panic_context(|| crate::simple_moving_average(&close, Some(16)))
+            .unwrap()
+            .values
+            .len()
+    );
+}
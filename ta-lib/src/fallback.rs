@@ -0,0 +1,233 @@
+//! Rust implementations of SMA, EMA, WMA and RSI that the wrappers use
+//! instead of the C library with the `pure_rust` feature.
+//!
+//! They follow the arithmetic of the C functions and agree with them to within
+//! rounding. Like the streaming states they assume the global TA-Lib settings
+//! are at their defaults, i.e. no unstable period and the default
+//! compatibility mode.
+use crate::{ta_period, EmaState, Error, RsiState, DEFAULT_MA_PERIOD, DEFAULT_RSI_PERIOD};
+
+/// The signature of the fallbacks: compute the values of `input` into `out`
+/// and return `begin`.
+pub(crate) type Compute = fn(&[f64], Option<usize>, &mut Vec<f64>) -> Result<usize, Error>;
+
+/// Clear `out` and make room for the values of an input of length `have`
+/// with the given `lookback`.
+fn prepare(out: &mut Vec<f64>, have: usize, lookback: usize) -> Result<(), Error> {
+    if have <= lookback {
+        return Err(Error::InsufficientData {
+            have,
+            need: lookback + 1,
+        });
+    }
+
+    out.clear();
+    out.reserve_exact(have - lookback);
+
+    Ok(())
+}
+
+pub(crate) fn simple_moving_average_lookback(period: Option<usize>) -> Result<usize, Error> {
+    Ok(ta_period("SMA", period, DEFAULT_MA_PERIOD)? - 1)
+}
+
+pub(crate) fn simple_moving_average(
+    input: &[f64],
+    period: Option<usize>,
+    out: &mut Vec<f64>,
+) -> Result<usize, Error> {
+    let period = ta_period("SMA", period, DEFAULT_MA_PERIOD)?;
+    prepare(out, input.len(), period - 1)?;
+
+    let mut total = input[..period - 1].iter().sum::<f64>();
+
+    for (value, trailing) in input[period - 1..].iter().zip(input) {
+        total += value;
+        out.push(total / period as f64);
+        total -= trailing;
+    }
+
+    Ok(period - 1)
+}
+
+pub(crate) fn exponential_moving_average_lookback(period: Option<usize>) -> Result<usize, Error> {
+    Ok(ta_period("EMA", period, DEFAULT_MA_PERIOD)? - 1)
+}
+
+pub(crate) fn exponential_moving_average(
+    input: &[f64],
+    period: Option<usize>,
+    out: &mut Vec<f64>,
+) -> Result<usize, Error> {
+    let period = ta_period("EMA", period, DEFAULT_MA_PERIOD)?;
+    prepare(out, input.len(), period - 1)?;
+
+    let mut ema = EmaState::new(period)?;
+    out.extend(input.iter().filter_map(|value| ema.next(*value)));
+
+    Ok(period - 1)
+}
+
+pub(crate) fn weighted_moving_average_lookback(period: Option<usize>) -> Result<usize, Error> {
+    Ok(ta_period("WMA", period, DEFAULT_MA_PERIOD)? - 1)
+}
+
+pub(crate) fn weighted_moving_average(
+    input: &[f64],
+    period: Option<usize>,
+    out: &mut Vec<f64>,
+) -> Result<usize, Error> {
+    let period = ta_period("WMA", period, DEFAULT_MA_PERIOD)?;
+    prepare(out, input.len(), period - 1)?;
+
+    let divider = (period * (period + 1) / 2) as f64;
+
+    // The weighted sum and the plain sum of the window. Each step adds the
+    // new value with the full weight and lowers all others by one, i.e.
+    // subtracts the plain sum.
+    let (mut weighted, mut total) = input[..period - 1]
+        .iter()
+        .zip(1..)
+        .fold((0.0, 0.0), |(weighted, total), (value, weight)| {
+            (weighted + value * weight as f64, total + value)
+        });
+    let mut trailing = 0.0;
+
+    for (index, value) in input.iter().enumerate().skip(period - 1) {
+        total += value - trailing;
+        weighted += value * period as f64;
+        trailing = input[index + 1 - period];
+
+        out.push(weighted / divider);
+        weighted -= total;
+    }
+
+    Ok(period - 1)
+}
+
+pub(crate) fn relative_strength_index_lookback(period: Option<usize>) -> Result<usize, Error> {
+    ta_period("RSI", period, DEFAULT_RSI_PERIOD)
+}
+
+pub(crate) fn relative_strength_index(
+    input: &[f64],
+    period: Option<usize>,
+    out: &mut Vec<f64>,
+) -> Result<usize, Error> {
+    let period = ta_period("RSI", period, DEFAULT_RSI_PERIOD)?;
+    prepare(out, input.len(), period)?;
+
+    let mut rsi = RsiState::new(period)?;
+    out.extend(input.iter().filter_map(|value| rsi.next(*value)));
+
+    Ok(period)
+}
+
+/// The value of the last input computed from the `lookback + 1` inputs ending
+/// with it, or `None` if there are fewer.
+pub(crate) fn latest(
+    input: &[f64],
+    period: Option<usize>,
+    compute: Compute,
+    lookback: fn(Option<usize>) -> Result<usize, Error>,
+) -> Result<Option<f64>, Error> {
+    let lookback = lookback(period)?;
+
+    if input.len() <= lookback {
+        return Ok(None);
+    }
+
+    let mut out = Vec::with_capacity(1);
+    compute(&input[input.len() - lookback - 1..], period, &mut out)?;
+
+    Ok(out.last().copied())
+}
+
+#[cfg(all(test, not(feature = "pure_rust")))]
+/// Assert that `fallback` and its `lookback` agree with the wrappers
+/// `wrapper` and `wrapper_latest`, which call the C library.
+///
+/// `wrapper_latest` is `None` for functions depending on all inputs, whose
+/// latest value is not computed over the last window.
+#[allow(clippy::type_complexity)]
+fn assert_matches_wrapper(
+    fallback: Compute,
+    lookback: fn(Option<usize>) -> Result<usize, Error>,
+    wrapper: fn(&[f64], Option<usize>) -> Result<crate::Output, Error>,
+    wrapper_latest: Option<fn(&[f64], Option<usize>) -> Result<Option<f64>, Error>>,
+) {
+    let input = (0..200)
+        .map(|index| 100.0 + (index as f64 * 0.3).sin() * 5.0 + (index as f64 * 0.07).cos())
+        .collect::<Vec<_>>();
+
+    for period in [None, Some(2), Some(7), Some(14), Some(50)] {
+        let crate::Output {
+            values: expected,
+            begin: expected_begin,
+        } = wrapper(&input, period).unwrap();
+
+        let mut values = Vec::new();
+        let begin = fallback(&input, period, &mut values).unwrap();
+
+        assert_eq!(expected_begin, begin);
+        assert_eq!(begin, lookback(period).unwrap());
+        assert_eq!(expected.len(), values.len());
+
+        for (expected, value) in expected.iter().zip(&values) {
+            assert!((expected - value).abs() < 1e-9, "{} != {}", value, expected);
+        }
+
+        if let Some(wrapper_latest) = wrapper_latest {
+            let expected = wrapper_latest(&input, period).unwrap().unwrap();
+            let value = latest(&input, period, fallback, lookback).unwrap().unwrap();
+
+            assert!((expected - value).abs() < 1e-9, "{} != {}", value, expected);
+            assert_eq!(
+                None,
+                latest(&input[..begin], period, fallback, lookback).unwrap()
+            );
+        }
+    }
+
+    let mut values = Vec::new();
+    assert_eq!(
+        wrapper(&input[..10], Some(20)).unwrap_err(),
+        fallback(&input[..10], Some(20), &mut values).unwrap_err()
+    );
+    assert!(matches!(
+        fallback(&input, Some(1), &mut values),
+        Err(Error::BadParam(_))
+    ));
+    assert!(matches!(lookback(Some(1)), Err(Error::BadParam(_))));
+}
+
+// With the `pure_rust` feature the wrappers call the fallbacks. Their tests
+// compare them with values computed by the C library then.
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_fallback() {
+    assert_matches_wrapper(
+        simple_moving_average,
+        simple_moving_average_lookback,
+        |input, period| crate::simple_moving_average(input, period),
+        Some(|input, period| crate::simple_moving_average_latest(input, period)),
+    );
+    assert_matches_wrapper(
+        exponential_moving_average,
+        exponential_moving_average_lookback,
+        |input, period| crate::exponential_moving_average(input, period),
+        None,
+    );
+    assert_matches_wrapper(
+        weighted_moving_average,
+        weighted_moving_average_lookback,
+        |input, period| crate::weighted_moving_average(input, period),
+        Some(|input, period| crate::weighted_moving_average_latest(input, period)),
+    );
+    assert_matches_wrapper(
+        relative_strength_index,
+        relative_strength_index_lookback,
+        |input, period| crate::relative_strength_index(input, period),
+        None,
+    );
+}
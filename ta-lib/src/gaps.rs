@@ -0,0 +1,127 @@
+//! Computing over series with missing values.
+use crate::{Error, Output};
+
+/// How [`with_gaps()`] treats missing values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GapPolicy {
+    /// Replace a missing value with the last value before it.
+    ///
+    /// Leading missing values have nothing to fill them with and are skipped.
+    ForwardFill,
+    /// Fail with [`Error::MissingValue`] on the first missing value.
+    Error,
+}
+
+/// Compute `f` over `input` with missing values handled as `policy` says.
+///
+/// `f` gets the dense series and is usually a closure around one of the
+/// functions in this crate, returning an [`Output`] or a `(values, begin)`
+/// tuple. The returned `begin` is an index into `input`,
+/// i.e. it accounts for any skipped leading missing values.
+///
+/// ```
+/// # use ta_lib::*;
+/// let close = [None, Some(1.0), Some(2.0), None, Some(4.0)];
+///
+/// let sma = with_gaps(&close, GapPolicy::ForwardFill, |close| {
+///     simple_moving_average(close, Some(2))
+/// })
+/// .unwrap();
+///
+/// assert_eq!(2, sma.begin);
+/// assert_eq!(vec![1.5, 2.0, 3.0], sma.values);
+/// ```
+pub fn with_gaps<F, T>(input: &[Option<f64>], policy: GapPolicy, f: F) -> Result<Output, Error>
+where
+    F: FnOnce(&[f64]) -> Result<T, Error>,
+    T: Into<Output>,
+{
+    let (dense, offset) = fill_gaps(input, policy)?;
+    let Output { values, begin } = f(&dense)?.into();
+
+    Ok(Output {
+        values,
+        begin: offset + begin,
+    })
+}
+
+/// Turn `input` into a dense series as `policy` says.
+///
+/// Returns the dense series and the index of its first element in `input`.
+fn fill_gaps(input: &[Option<f64>], policy: GapPolicy) -> Result<(Vec<f64>, usize), Error> {
+    let offset = match policy {
+        GapPolicy::ForwardFill => input
+            .iter()
+            .position(Option::is_some)
+            .ok_or(Error::InsufficientData { have: 0, need: 1 })?,
+        GapPolicy::Error => 0,
+    };
+
+    let mut dense = Vec::with_capacity(input.len() - offset);
+    for (index, value) in input.iter().enumerate().skip(offset) {
+        match (value, policy) {
+            (Some(value), _) => dense.push(*value),
+            (None, GapPolicy::ForwardFill) => dense.push(*dense.last().unwrap()),
+            (None, GapPolicy::Error) => return Err(Error::MissingValue { index }),
+        }
+    }
+
+    Ok((dense, offset))
+}
+
+#[test]
+fn test_with_gaps() {
+    let close = [
+        Some(1.0),
+        Some(2.0),
+        None,
+        Some(3.0),
+        Some(4.0),
+        None,
+        Some(6.0),
+    ];
+
+    let Output { values, begin } = with_gaps(&close, GapPolicy::ForwardFill, |close| {
+        crate::simple_moving_average(close, Some(3))
+    })
+    .unwrap();
+
+    assert_eq!(2, begin);
+    assert_eq!(close.len() - begin, values.len());
+    // The window ending at index 2 is 1, 2 and the filled 2.
+    assert_eq!(5.0 / 3.0, values[0]);
+    // The window ending at index 6 is 4, the filled 4 and 6.
+    assert_eq!(14.0 / 3.0, values[4]);
+
+    assert!(matches!(
+        with_gaps(&close, GapPolicy::Error, |close| {
+            crate::simple_moving_average(close, Some(3))
+        }),
+        Err(Error::MissingValue { index: 2 })
+    ));
+
+    // Leading gaps are skipped but still count towards `begin`.
+    let Output { values, begin } = with_gaps(
+        &[None, None, Some(1.0), Some(2.0)],
+        GapPolicy::ForwardFill,
+        |close| crate::simple_moving_average(close, Some(2)),
+    )
+    .unwrap();
+
+    assert_eq!(3, begin);
+    assert_eq!(vec![1.5], values);
+
+    // Functions returning a `(values, begin)` tuple work as well.
+    assert_eq!(
+        Output {
+            values: vec![1.5],
+            begin: 3
+        },
+        with_gaps(
+            &[None, None, Some(1.0), Some(2.0)],
+            GapPolicy::ForwardFill,
+            |close| crate::wilders_smoothing(close, 2),
+        )
+        .unwrap()
+    );
+}
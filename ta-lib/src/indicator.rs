@@ -0,0 +1,532 @@
+//! A uniform interface over indicators and their parameters.
+use crate::{
+    average_directional_movement_index, average_directional_movement_index_lookback,
+    average_true_range, average_true_range_lookback, bollinger_bands, exponential_moving_average,
+    exponential_moving_average_lookback, moving_average_convergence_divergence, nan_padded,
+    normalized_average_true_range, normalized_average_true_range_lookback, relative_strength_index,
+    relative_strength_index_lookback, simple_moving_average, simple_moving_average_lookback,
+    stochastic, true_range, true_range_lookback, Candles, Error, IndicatorOutput,
+    MovingAverageType, Output,
+};
+
+/// An indicator together with its parameters.
+///
+/// This allows frameworks to treat indicators uniformly, e.g. to pre-trim
+/// inputs or to align outputs of different indicators.
+pub trait Indicator {
+    /// The number of candles consumed before the first output value, i.e. the
+    /// `begin` of the output.
+    ///
+    /// Returns [`Error::BadParam`] if the parameters are out of range for the
+    /// indicator.
+    fn lookback(&self) -> Result<usize, Error>;
+
+    /// Compute the indicator over `candles`.
+    fn compute(&self, candles: &Candles) -> Result<IndicatorOutput, Error>;
+}
+
+/// The lookback of `indicator` from its `*_lookback()` function, which
+/// returns `None` for parameters out of range.
+fn checked_lookback(
+    indicator: &impl std::fmt::Debug,
+    lookback: Option<usize>,
+) -> Result<usize, Error> {
+    lookback
+        .ok_or_else(|| Error::BadParam(format!("The period of {:?} is out of range.", indicator)))
+}
+
+/// Output of the single valued indicators as a column named `name`.
+fn single_column(name: &str, Output { values, begin }: Output) -> IndicatorOutput {
+    IndicatorOutput {
+        columns: vec![(name.to_string(), values)],
+        begin,
+    }
+}
+
+macro_rules! define_close_period_indicator {
+    ($(#[$attr:meta])* $name:ident, $column:literal, $fn_name:ident, $lookback_fn_name:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct $name(pub usize);
+
+        impl Indicator for $name {
+            fn lookback(&self) -> Result<usize, Error> {
+                checked_lookback(self, $lookback_fn_name(Some(self.0)))
+            }
+
+            fn compute(&self, candles: &Candles) -> Result<IndicatorOutput, Error> {
+                $fn_name(candles.close(), Some(self.0)).map(|output| single_column($column, output))
+            }
+        }
+    };
+}
+
+macro_rules! define_high_low_close_period_indicator {
+    ($(#[$attr:meta])* $name:ident, $column:literal, $fn_name:ident, $lookback_fn_name:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct $name(pub usize);
+
+        impl Indicator for $name {
+            fn lookback(&self) -> Result<usize, Error> {
+                checked_lookback(self, $lookback_fn_name(Some(self.0)))
+            }
+
+            fn compute(&self, candles: &Candles) -> Result<IndicatorOutput, Error> {
+                $fn_name(candles.high(), candles.low(), candles.close(), Some(self.0))
+                    .map(|output| single_column($column, output))
+            }
+        }
+    };
+}
+
+define_close_period_indicator!(
+    /// [`simple_moving_average()`] of the closes over a period.
+    Sma,
+    "sma",
+    simple_moving_average,
+    simple_moving_average_lookback
+);
+
+define_close_period_indicator!(
+    /// [`exponential_moving_average()`] of the closes over a period.
+    Ema,
+    "ema",
+    exponential_moving_average,
+    exponential_moving_average_lookback
+);
+
+define_close_period_indicator!(
+    /// [`relative_strength_index()`] of the closes over a period.
+    Rsi,
+    "rsi",
+    relative_strength_index,
+    relative_strength_index_lookback
+);
+
+define_high_low_close_period_indicator!(
+    /// [`average_true_range()`] over a period.
+    Atr,
+    "atr",
+    average_true_range,
+    average_true_range_lookback
+);
+
+define_high_low_close_period_indicator!(
+    /// [`normalized_average_true_range()`] over a period.
+    Natr,
+    "natr",
+    normalized_average_true_range,
+    normalized_average_true_range_lookback
+);
+
+define_high_low_close_period_indicator!(
+    /// [`average_directional_movement_index()`] over a period.
+    Adx,
+    "adx",
+    average_directional_movement_index,
+    average_directional_movement_index_lookback
+);
+
+/// [`true_range()`] of each candle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TrueRange;
+
+impl Indicator for TrueRange {
+    fn lookback(&self) -> Result<usize, Error> {
+        Ok(true_range_lookback())
+    }
+
+    fn compute(&self, candles: &Candles) -> Result<IndicatorOutput, Error> {
+        true_range(candles.high(), candles.low(), candles.close())
+            .map(|output| single_column("trange", output))
+    }
+}
+
+/// An indicator with its parameters for [`Candles::indicator()`] and
+/// [`panel()`].
+///
+/// The indicators over high, low and close, e.g. [`IndicatorSpec::Atr`], need
+/// candles. The others are computed over a single series, the closes in case
+/// of candles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IndicatorSpec {
+    /// [`simple_moving_average()`] as the column `"sma"`.
+    Sma(usize),
+    /// [`exponential_moving_average()`] as the column `"ema"`.
+    Ema(usize),
+    /// [`relative_strength_index()`] as the column `"rsi"`.
+    Rsi(usize),
+    /// [`moving_average_convergence_divergence()`].
+    Macd {
+        fast_period: usize,
+        slow_period: usize,
+        signal_period: usize,
+    },
+    /// [`bollinger_bands()`].
+    BollingerBands {
+        period: usize,
+        num_std_deviations_up: f64,
+        num_std_deviations_down: f64,
+        moving_average_type: MovingAverageType,
+    },
+    /// [`average_true_range()`] as the column `"atr"`.
+    Atr(usize),
+    /// [`average_directional_movement_index()`] as the column `"adx"`.
+    Adx(usize),
+    /// [`stochastic()`] with simple moving averages for both lines.
+    Stochastic {
+        fast_k_period: usize,
+        slow_k_period: usize,
+        slow_d_period: usize,
+    },
+}
+
+impl Candles {
+    /// Compute the indicator described by `spec`.
+    ///
+    /// Indicators over high, low and close get the respective columns, all
+    /// others the closes.
+    pub fn indicator(&self, spec: IndicatorSpec) -> Result<IndicatorOutput, Error> {
+        match spec {
+            IndicatorSpec::Atr(period) => {
+                average_true_range(self.high(), self.low(), self.close(), Some(period))
+                    .map(|output| single_column("atr", output))
+            }
+            IndicatorSpec::Adx(period) => average_directional_movement_index(
+                self.high(),
+                self.low(),
+                self.close(),
+                Some(period),
+            )
+            .map(|output| single_column("adx", output)),
+            IndicatorSpec::Stochastic {
+                fast_k_period,
+                slow_k_period,
+                slow_d_period,
+            } => stochastic(
+                self.high(),
+                self.low(),
+                self.close(),
+                Some(fast_k_period),
+                Some(slow_k_period),
+                Some(MovingAverageType::SimpleMovingAverage),
+                Some(slow_d_period),
+                Some(MovingAverageType::SimpleMovingAverage),
+            )
+            .map(Into::into),
+            _ => series_indicator(self.close(), spec),
+        }
+    }
+}
+
+/// Named columns with one value per candle, e.g. for charting.
+///
+/// Returned by [`Candles::standard_panel()`]. Candles without an associated
+/// value get `f64::NAN`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Panel {
+    /// The name and values of each column.
+    pub columns: Vec<(String, Vec<f64>)>,
+}
+
+impl Panel {
+    /// The values of the column with the given `name`.
+    pub fn column(&self, name: &str) -> Option<&[f64]> {
+        self.columns
+            .iter()
+            .find(|(column_name, _)| column_name == name)
+            .map(|(_, values)| values.as_slice())
+    }
+}
+
+impl Candles {
+    /// Compute the usual overlays and oscillators of a chart at once.
+    ///
+    /// | Indicator                  | Columns                                         |
+    /// |----------------------------|-------------------------------------------------|
+    /// | SMA(20), SMA(50), SMA(200) | `sma_20`, `sma_50`, `sma_200`                   |
+    /// | EMA(12), EMA(26)           | `ema_12`, `ema_26`                              |
+    /// | RSI(14)                    | `rsi_14`                                        |
+    /// | MACD(12, 26, 9)            | `macd`, `macd_signal`, `macd_histogram`         |
+    /// | BBANDS(20) at 2 std. dev.  | `bbands_upper`, `bbands_middle`, `bbands_lower` |
+    /// | ATR(14)                    | `atr_14`                                        |
+    ///
+    /// The columns are NaN padded to the number of candles. The columns of
+    /// indicators needing more candles than there are, e.g. SMA(200) of a
+    /// short history, are all `f64::NAN`.
+    pub fn standard_panel(&self) -> Result<Panel, Error> {
+        let specs: [(IndicatorSpec, &[&str]); 9] = [
+            (IndicatorSpec::Sma(20), &["sma_20"]),
+            (IndicatorSpec::Sma(50), &["sma_50"]),
+            (IndicatorSpec::Sma(200), &["sma_200"]),
+            (IndicatorSpec::Ema(12), &["ema_12"]),
+            (IndicatorSpec::Ema(26), &["ema_26"]),
+            (IndicatorSpec::Rsi(14), &["rsi_14"]),
+            (
+                IndicatorSpec::Macd {
+                    fast_period: 12,
+                    slow_period: 26,
+                    signal_period: 9,
+                },
+                &["macd", "macd_signal", "macd_histogram"],
+            ),
+            (
+                IndicatorSpec::BollingerBands {
+                    period: 20,
+                    num_std_deviations_up: 2.0,
+                    num_std_deviations_down: 2.0,
+                    moving_average_type: MovingAverageType::SimpleMovingAverage,
+                },
+                &["bbands_upper", "bbands_middle", "bbands_lower"],
+            ),
+            (IndicatorSpec::Atr(14), &["atr_14"]),
+        ];
+
+        let mut columns = Vec::new();
+
+        for (spec, names) in specs {
+            match self.indicator(spec) {
+                Ok(output) => {
+                    debug_assert_eq!(names.len(), output.columns.len());
+
+                    columns.extend(names.iter().zip(output.columns).map(|(name, (_, values))| {
+                        (
+                            name.to_string(),
+                            nan_padded(values, output.begin, self.len()),
+                        )
+                    }));
+                }
+                Err(Error::InsufficientData { .. }) => columns.extend(
+                    names
+                        .iter()
+                        .map(|name| (name.to_string(), vec![f64::NAN; self.len()])),
+                ),
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(Panel { columns })
+    }
+}
+
+/// Compute an indicator over a single series.
+///
+/// Returns [`Error::BadParam`] for the indicators that need candles.
+fn series_indicator(input: &[f64], spec: IndicatorSpec) -> Result<IndicatorOutput, Error> {
+    match spec {
+        IndicatorSpec::Sma(period) => {
+            simple_moving_average(input, Some(period)).map(|output| single_column("sma", output))
+        }
+        IndicatorSpec::Ema(period) => exponential_moving_average(input, Some(period))
+            .map(|output| single_column("ema", output)),
+        IndicatorSpec::Rsi(period) => {
+            relative_strength_index(input, Some(period)).map(|output| single_column("rsi", output))
+        }
+        IndicatorSpec::Macd {
+            fast_period,
+            slow_period,
+            signal_period,
+        } => moving_average_convergence_divergence(
+            input,
+            Some(fast_period),
+            Some(slow_period),
+            Some(signal_period),
+        )
+        .map(Into::into),
+        IndicatorSpec::BollingerBands {
+            period,
+            num_std_deviations_up,
+            num_std_deviations_down,
+            moving_average_type,
+        } => bollinger_bands(
+            input,
+            Some(period),
+            Some(num_std_deviations_up),
+            Some(num_std_deviations_down),
+            Some(moving_average_type),
+        )
+        .map(Into::into),
+        IndicatorSpec::Atr(_) | IndicatorSpec::Adx(_) | IndicatorSpec::Stochastic { .. } => Err(
+            Error::BadParam(format!("{:?} needs candles, not a single series.", spec)),
+        ),
+    }
+}
+
+/// Compute several indicators over the same `input` at once.
+///
+/// The outputs are in the order of `specs`. Fails on the first indicator that
+/// can not be computed, e.g. one that needs [`Candles`].
+pub fn panel(input: &[f64], specs: &[IndicatorSpec]) -> Result<Vec<IndicatorOutput>, Error> {
+    specs
+        .iter()
+        .map(|spec| series_indicator(input, *spec))
+        .collect()
+}
+
+/// Candles with a close of `1, 2, 3, …` for the tests.
+#[cfg(test)]
+fn rising_candles(len: usize) -> Candles {
+    let close = (1..=len).map(|close| close as f64).collect::<Vec<_>>();
+
+    Candles::try_new(
+        close.clone(),
+        close.iter().map(|close| close + 0.5).collect(),
+        close.iter().map(|close| close - 0.5).collect(),
+        close,
+        Vec::new(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_indicator_lookback() {
+    assert_eq!(Ok(9), Sma(10).lookback());
+    assert!(matches!(Sma(0).lookback(), Err(Error::BadParam(_))));
+    assert!(matches!(
+        Atr(usize::MAX).lookback(),
+        Err(Error::BadParam(_))
+    ));
+
+    let indicators: [&dyn Indicator; 7] = [
+        &Sma(10),
+        &Ema(10),
+        &Rsi(14),
+        &Atr(14),
+        &Natr(14),
+        &Adx(14),
+        &TrueRange,
+    ];
+
+    for indicator in indicators {
+        let lookback = indicator.lookback().unwrap();
+
+        // Just enough candles for a single value.
+        let output = indicator.compute(&rising_candles(lookback + 1)).unwrap();
+        assert_eq!(lookback, output.begin);
+        assert_eq!(1, output.columns[0].1.len());
+
+        assert!(matches!(
+            indicator.compute(&rising_candles(lookback)),
+            Err(Error::InsufficientData { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_panel() {
+    let close = rising_candles(60).close().to_vec();
+
+    let panel = panel(
+        &close,
+        &[
+            IndicatorSpec::Sma(20),
+            IndicatorSpec::Rsi(14),
+            IndicatorSpec::Macd {
+                fast_period: 12,
+                slow_period: 26,
+                signal_period: 9,
+            },
+        ],
+    )
+    .unwrap();
+
+    assert_eq!(
+        vec![
+            vec!["sma"],
+            vec!["rsi"],
+            vec!["macd", "signal", "histogram"]
+        ],
+        panel
+            .iter()
+            .map(|output| output
+                .columns
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![19, 14, 33],
+        panel.iter().map(|output| output.begin).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        simple_moving_average(&close, Some(20)).unwrap().values,
+        panel[0].columns[0].1
+    );
+}
+
+#[test]
+fn test_candles_indicator() {
+    let candles = rising_candles(30);
+
+    let atr = candles.indicator(IndicatorSpec::Atr(14)).unwrap();
+    assert_eq!("atr", atr.columns[0].0);
+    assert_eq!(average_true_range_lookback(Some(14)).unwrap(), atr.begin);
+    assert_eq!(30 - atr.begin, atr.columns[0].1.len());
+
+    let sma = candles.indicator(IndicatorSpec::Sma(20)).unwrap();
+    assert_eq!("sma", sma.columns[0].0);
+    assert_eq!(19, sma.begin);
+    assert_eq!(
+        simple_moving_average(candles.close(), Some(20))
+            .unwrap()
+            .values,
+        sma.columns[0].1
+    );
+
+    assert!(matches!(
+        panel(candles.close(), &[IndicatorSpec::Atr(14)]),
+        Err(Error::BadParam(_))
+    ));
+}
+
+#[test]
+fn test_standard_panel() {
+    let candles = rising_candles(100);
+
+    let panel = candles.standard_panel().unwrap();
+
+    assert_eq!(
+        vec![
+            "sma_20",
+            "sma_50",
+            "sma_200",
+            "ema_12",
+            "ema_26",
+            "rsi_14",
+            "macd",
+            "macd_signal",
+            "macd_histogram",
+            "bbands_upper",
+            "bbands_middle",
+            "bbands_lower",
+            "atr_14"
+        ],
+        panel
+            .columns
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert!(panel
+        .columns
+        .iter()
+        .all(|(_, values)| candles.close().len() == values.len()));
+
+    let sma = panel.column("sma_50").unwrap();
+    assert!(sma[..49].iter().all(|value| value.is_nan()));
+    assert_eq!(
+        simple_moving_average(candles.close(), Some(50))
+            .unwrap()
+            .values,
+        sma[49..]
+    );
+
+    // 100 candles are too few for SMA(200).
+    assert!(panel
+        .column("sma_200")
+        .unwrap()
+        .iter()
+        .all(|value| value.is_nan()));
+}
@@ -3,46 +3,964 @@
 //! [`ADX`](average_directional_movement_index), MACD, RSI, Stochastic,
 //! Bollinger Bands, as well as Candlestick pattern recognition.
 //!
+//! ## Results
+//! Functions return the computed values together with `begin`, the index of
+//! the first input to have an associated value. The value at position `i`
+//! belongs to the input at `begin + i`.
+//!
+//! A successful result always has at least one value. If the input is too
+//! short to compute a single value for the given parameters, functions return
+//! [`Error::InsufficientData`] instead of an empty list with a meaningless
+//! `begin`. Empty inputs give [`Error::InvalidRange`].
+//!
+//! Functions over several series, e.g. highs, lows and closes, return
+//! [`Error::LengthMismatch`] unless all of them have the same length.
+//!
 //! ## Cargo Features
 //! * `use_system_lib` – Use the system's installed C TA lib instead of building
 //!   from source.
 //!
-//!   By deafult the C TA lib is built from source included with the
-//! `ta-lib-sys`   crate.
+//!   By default the C TA lib is built from source included with the
+//!   `ta-lib-sys` crate.
+//!
+//! * `csv` – Read [`Candles`] from CSV data.
+//!
+//! * `f32` – Add `*_f32` variants of the wrappers that compute in `f64` but
+//!   return `f32` values, e.g. for storing large precomputed indicator tables.
+//!
+//! * `tracing` – Emit a [`tracing`](https://docs.rs/tracing/) debug span,
+//!   tagged with the input length, around each call into the C library and log
+//!   the `RetCode` of failed calls.
+//!
+//! * `panic_context` – Add [`with_panic_context()`] which annotates panics,
+//!   e.g. of a closure passed to [`rolling_apply()`], with the name, input
+//!   length and parameters of the wrapper they happened in. A debugging aid.
+//!
+//! * `ffi` – Wrap the C TA lib of the `ta-lib-sys` crate. Enabled by default.
+//!
+//! * `pure_rust` – Compute [`simple_moving_average()`],
+//!   [`exponential_moving_average()`], [`weighted_moving_average()`] and
+//!   [`relative_strength_index()`], as well as their `_into`, `_padded` etc.
+//!   variants, with Rust implementations instead of the C library. These ignore
+//!   the unstable period and compatibility settings. All functions calling the
+//!   C library, the settings and the abstract interface are left out.
+//!
+//!   Together with `default-features = false` this builds without
+//!   `ta-lib-sys`, e.g. for `wasm32-unknown-unknown`.
+//!
+//! * `serde` – Derive `Serialize` and `Deserialize` for the incremental
+//!   indicator states, e.g. [`RsiState`], so a stream can be saved and resumed
+//!   without replaying its history.
 use concat_idents::concat_idents;
-use std::mem::MaybeUninit;
+use std::collections::BTreeMap;
+#[cfg(not(feature = "pure_rust"))]
 use ta_lib_sys as ta;
 
+#[cfg(not(any(feature = "ffi", feature = "pure_rust")))]
+compile_error!("Enable either the `ffi` or the `pure_rust` feature.");
+
 #[macro_use]
 mod macros;
 
-#[derive(Debug, Clone)]
-pub struct Error(String);
+#[cfg(not(feature = "pure_rust"))]
+mod abstract_interface;
+#[cfg(not(feature = "pure_rust"))]
+pub use abstract_interface::{
+    available_functions, compute_by_name, function_info, lookback_by_name, FuncInfo, ParameterInfo,
+    ParameterRange,
+};
+
+mod candles;
+pub use candles::Candles;
+#[cfg(feature = "csv")]
+pub use candles::{Column, ColumnMapping};
+
+#[cfg(feature = "panic_context")]
+mod context;
+#[cfg(feature = "panic_context")]
+pub use context::with_panic_context;
+
+#[cfg(not(feature = "pure_rust"))]
+mod channels;
+#[cfg(not(feature = "pure_rust"))]
+pub use channels::{
+    donchian_channels, donchian_channels_columns, donchian_channels_padded, keltner_channels,
+    keltner_channels_columns, keltner_channels_padded, super_trend, super_trend_columns,
+    super_trend_padded, DonchianChannels, KeltnerChannels, SuperTrend,
+};
+
+#[cfg(any(feature = "pure_rust", test))]
+mod fallback;
+
+mod gaps;
+pub use gaps::{with_gaps, GapPolicy};
+
+#[cfg(not(feature = "pure_rust"))]
+mod indicator;
+#[cfg(not(feature = "pure_rust"))]
+pub use indicator::{
+    panel, Adx, Atr, Ema, Indicator, IndicatorSpec, Natr, Panel, Rsi, Sma, TrueRange,
+};
+
+#[cfg(not(feature = "pure_rust"))]
+mod oscillators;
+#[cfg(not(feature = "pure_rust"))]
+pub use oscillators::{
+    adaptive_rsi, chaikin_volatility, coppock_curve, elder_impulse, elder_ray, elder_ray_columns,
+    elder_ray_padded, fisher_transform, fisher_transform_columns, fisher_transform_padded,
+    percentage_volume_oscillator, percentage_volume_oscillator_columns,
+    percentage_volume_oscillator_padded, vortex_indicator, vortex_indicator_columns,
+    vortex_indicator_padded, williams_ad, ElderRay, FisherTransform, Pvo, Vortex,
+};
+
+#[cfg(not(feature = "pure_rust"))]
+mod patterns;
+#[cfg(not(feature = "pure_rust"))]
+pub use patterns::{
+    candlestick_pattern, candlestick_pattern_normalized, scan_candlesticks, CandleSignal,
+    CANDLESTICK_PATTERNS,
+};
+
+pub mod prelude;
+
+mod rolling;
+#[cfg(not(feature = "pure_rust"))]
+pub use rolling::{
+    correlation_matrix, rolling_alpha_beta, rolling_alpha_beta_columns, rolling_alpha_beta_padded,
+    rolling_zscore, vwap, AlphaBeta,
+};
+pub use rolling::{
+    rolling_apply, rolling_kurtosis, rolling_percent_rank, rolling_quantile, rolling_sharpe,
+    rolling_skewness,
+};
+
+mod series;
+pub use series::{Output, Pipeline, Series, Step};
+
+#[cfg(not(feature = "pure_rust"))]
+mod settings;
+#[cfg(not(feature = "pure_rust"))]
+pub use settings::{
+    cmo_unstable_period, compatibility, reset_global_settings, rsi_unstable_period,
+    set_compatibility, set_unstable_period, unstable_period, with_compatibility,
+    with_unstable_period, Compatibility, UnstablePeriodFunction, DEFAULT_UNSTABLE_PERIOD,
+};
+
+mod signal;
+pub use signal::{crossovers, Cross};
+
+mod streaming;
+pub use streaming::{EmaState, RsiState, SmaState};
+
+mod window;
+pub use window::Window;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The C library failed to compute the function.
+    Computation(String),
+    /// The input is too short to compute even a single value.
+    ///
+    /// `need` is the minimum number of input elements for the given
+    /// parameters. This is one more than the function's lookback.
+    InsufficientData { have: usize, need: usize },
+    /// Two inputs that must have the same length do not.
+    LengthMismatch { expected: usize, actual: usize },
+    /// The bar at `index` is malformed, e.g. its high is below its low.
+    InconsistentBar { index: usize },
+    /// The denominator at `index` is zero.
+    DivisionByZero { index: usize },
+    /// A parameter is out of range.
+    BadParam(String),
+    /// The input value at `index` is missing.
+    MissingValue { index: usize },
+    /// The input value at `index` is out of its domain, e.g. a negative
+    /// volume.
+    InvalidInput { index: usize },
+    /// The input has more elements than the C library can index.
+    InputTooLarge { len: usize },
+    /// The range of inputs to compute, `start..end`, is empty or inverted,
+    /// e.g. because the input is empty.
+    InvalidRange { start: usize, end: usize },
+    /// The linked C library does not provide the function `name`.
+    Unsupported { name: String },
+    /// CSV data could not be read.
+    #[cfg(feature = "csv")]
+    Csv(String),
+}
+
+/// Fail early if the `lookback` the C library reports for our parameters
+/// leaves no room for a single output value in an input of length `have`.
+///
+/// Returns the number of values the C function will output, the capacity to
+/// allocate for each output.
+///
+/// A negative `lookback` means the parameters are invalid. The C function will
+/// report this itself so we let it through.
+fn check_lookback(have: usize, lookback: i32) -> Result<usize, Error> {
+    match usize::try_from(lookback) {
+        Ok(lookback) if have <= lookback => Err(Error::InsufficientData {
+            have,
+            need: lookback + 1,
+        }),
+        Ok(lookback) => Ok(have - lookback),
+        Err(_) => Ok(have),
+    }
+}
+
+/// Fail if the range of inputs to compute, `start..end`, is empty or
+/// inverted.
+///
+/// The C library takes the index of the last input, `end - 1`, which would
+/// underflow for an empty input.
+fn check_range(start: usize, end: usize) -> Result<(), Error> {
+    if start < end {
+        Ok(())
+    } else {
+        Err(Error::InvalidRange { start, end })
+    }
+}
+
+/// Convert an optional period to what the C library expects, substituting
+/// `TA_INTEGER_DEFAULT` for `None`.
+///
+/// Returns [`Error::BadParam`] for a period of `0` or one beyond `i32::MAX`,
+/// rather than wrapping around to some arbitrary, possibly valid, period.
+/// Any narrower range a function has is checked by the C library.
+fn period_or_default(period: Option<usize>) -> Result<i32, Error> {
+    match period {
+        Some(0) => Err(Error::BadParam("Period must be at least 1.".to_string())),
+        Some(period) => i32::try_from(period).map_err(|_| {
+            Error::BadParam(format!(
+                "Period {} is too large; it must be at most {}.",
+                period,
+                i32::MAX
+            ))
+        }),
+        // ta::TA_INTEGER_DEFAULT
+        None => Ok(i32::MIN),
+    }
+}
+
+/// Check the `period` of a function implemented in Rust the same way
+/// [`period_or_default()`] does for the wrapped ones, and that it is at least
+/// the `min` the function needs.
+///
+/// Returns [`Error::BadParam`] otherwise.
+fn checked_period(period: usize, min: usize) -> Result<usize, Error> {
+    period_or_default(Some(period))?;
+
+    if period < min {
+        return Err(Error::BadParam(format!(
+            "Period must be at least {} but is {}.",
+            min, period
+        )));
+    }
+
+    Ok(period)
+}
+
+/// TA-Lib's default period of SMA, EMA and WMA.
+const DEFAULT_MA_PERIOD: usize = 30;
+
+/// TA-Lib's default period of RSI.
+#[cfg(any(feature = "pure_rust", test))]
+const DEFAULT_RSI_PERIOD: usize = 14;
+
+/// The periods TA-Lib accepts for SMA, EMA, WMA and RSI.
+const TA_PERIOD_RANGE: std::ops::RangeInclusive<usize> = 2..=100_000;
+
+/// The `period`, or `default` for `None`, of the function `name` if it is in
+/// [`TA_PERIOD_RANGE`].
+///
+/// For the Rust implementations of functions TA-Lib has too, so they accept
+/// the same periods as the C library.
+fn ta_period(name: &str, period: Option<usize>, default: usize) -> Result<usize, Error> {
+    let period = period.unwrap_or(default);
+
+    if TA_PERIOD_RANGE.contains(&period) {
+        Ok(period)
+    } else {
+        Err(Error::BadParam(format!(
+            "{} period must be between {} and {} but is {}.",
+            name,
+            TA_PERIOD_RANGE.start(),
+            TA_PERIOD_RANGE.end(),
+            period
+        )))
+    }
+}
+
+/// Report an [`Error::InsufficientData`] of a computation over intermediate
+/// values that start at `offset` of the input relative to the input.
+///
+/// Other errors are returned as they are.
+pub(crate) fn rebase_too_short(error: Error, offset: usize) -> Error {
+    match error {
+        Error::InsufficientData { have, need } => Error::InsufficientData {
+            have: have + offset,
+            need: need + offset,
+        },
+        error => error,
+    }
+}
+
+/// Fail if an input of length `len` can not be indexed with the C library's
+/// `int` indices.
+fn check_input_len(len: usize) -> Result<(), Error> {
+    if len > i32::MAX as usize {
+        Err(Error::InputTooLarge { len })
+    } else {
+        Ok(())
+    }
+}
+
+/// Fail with [`Error::LengthMismatch`] for the first of `series` that differs
+/// in length from the first one.
+fn check_aligned(series: &[&[f64]]) -> Result<(), Error> {
+    if let Some(first) = series.first() {
+        if let Some(other) = series.iter().find(|other| other.len() != first.len()) {
+            return Err(Error::LengthMismatch {
+                expected: first.len(),
+                actual: other.len(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Check a `series` against the rules of the wrappers and beyond, e.g. to
+/// screen user data before computing anything.
+///
+/// Returns [`Error::InvalidRange`] if it is empty, [`Error::InputTooLarge`] if
+/// the C library can not index it and [`Error::InvalidInput`] for the first
+/// value that is not finite.
+///
+/// The wrappers do not check for finite values. The C library propagates
+/// `f64::NAN`s into all values computed from them.
+///
+/// ```
+/// # use ta_lib::*;
+/// assert!(validate_series(&[1.0, 2.0]).is_ok());
+/// assert!(matches!(
+///     validate_series(&[1.0, f64::NAN]),
+///     Err(Error::InvalidInput { index: 1 })
+/// ));
+/// ```
+pub fn validate_series(series: &[f64]) -> Result<(), Error> {
+    check_range(0, series.len())?;
+    check_input_len(series.len())?;
+
+    match series.iter().position(|value| !value.is_finite()) {
+        Some(index) => Err(Error::InvalidInput { index }),
+        None => Ok(()),
+    }
+}
+
+/// Check `series` that go into the same indicator, e.g. highs, lows and
+/// closes.
+///
+/// Returns [`Error::LengthMismatch`] for the first series that differs in
+/// length from the first one, like the wrappers taking several series do.
+/// Then checks each with
+/// [`validate_series()`]. No series at all give [`Error::InvalidRange`].
+pub fn validate_aligned(series: &[&[f64]]) -> Result<(), Error> {
+    check_aligned(series)?;
+
+    if series.is_empty() {
+        return Err(Error::InvalidRange { start: 0, end: 0 });
+    }
+
+    series.iter().try_for_each(|series| validate_series(series))
+}
+
+#[test]
+fn test_validate_series() {
+    assert!(validate_series(&[1.0, -2.0, 0.0]).is_ok());
+
+    assert!(matches!(
+        validate_series(&[]),
+        Err(Error::InvalidRange { start: 0, end: 0 })
+    ));
+
+    for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+        assert!(matches!(
+            validate_series(&[1.0, 2.0, value, 4.0]),
+            Err(Error::InvalidInput { index: 2 })
+        ));
+    }
+}
+
+#[test]
+fn test_validate_aligned() {
+    let high = [2.0, 3.0, 4.0];
+    let low = [1.0, 2.0, 3.0];
+
+    assert!(validate_aligned(&[&high, &low]).is_ok());
+
+    assert!(matches!(
+        validate_aligned(&[]),
+        Err(Error::InvalidRange { start: 0, end: 0 })
+    ));
+    assert!(matches!(
+        validate_aligned(&[&[], &[]]),
+        Err(Error::InvalidRange { start: 0, end: 0 })
+    ));
+    assert!(matches!(
+        validate_aligned(&[&high, &low[1..]]),
+        Err(Error::LengthMismatch {
+            expected: 3,
+            actual: 2
+        })
+    ));
+    assert!(matches!(
+        validate_aligned(&[&high, &[1.0, f64::NAN, 3.0]]),
+        Err(Error::InvalidInput { index: 1 })
+    ));
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_length_mismatch() {
+    let long = [1.0; 20];
+    let short = [1.0; 19];
+    let mismatch = Error::LengthMismatch {
+        expected: 19,
+        actual: 20,
+    };
+
+    assert_eq!(
+        Err(mismatch.clone()),
+        average_true_range(long, long, short, None)
+    );
+    assert_eq!(Err(mismatch.clone()), true_range(short, long, short));
+    assert_eq!(
+        Err(mismatch.clone()),
+        stochastic(&long, &short, &short, None, None, None, None, None)
+    );
+    assert_eq!(
+        Err(mismatch.clone()),
+        fast_stochastic(&long, &long, &short, None, None, None)
+    );
+    assert_eq!(
+        Err(mismatch.clone()),
+        pearson_correlation(&short, &long, None)
+    );
+    assert_eq!(Err(mismatch.clone()), on_balance_volume(&short, &long));
+    assert_eq!(Err(mismatch.clone()), divide(&short, &long));
+    assert_eq!(Err(mismatch), multiply(&short, &long));
+}
+
+/// Pair each value of an indicator result with the index of the input element
+/// it belongs to.
+///
+/// The `begin` offset returned by every function in this crate is the index of
+/// the input element the first output value is associated with.
+///
+/// ```
+/// # use ta_lib::*;
+/// let close = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let Output { values: sma, begin } = simple_moving_average(&close, Some(3)).unwrap();
+///
+/// for (index, value) in indexed(&sma, begin) {
+///     println!("SMA at close {} is {}", close[index], value);
+/// }
+/// ```
+pub fn indexed(values: &[f64], begin: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+    values
+        .iter()
+        .enumerate()
+        .map(move |(index, value)| (begin + index, *value))
+}
+
+/// Pad `values` with leading `f64::NAN`s so that there is one value for each
+/// of the `len` inputs.
+///
+/// This turns the `(values, begin)` returned by the functions in this crate
+/// into the shape e.g. `pandas` or `numpy` use. Each function also has a
+/// `*_padded` variant that does this.
+pub fn nan_padded(values: Vec<f64>, begin: usize, len: usize) -> Vec<f64> {
+    let mut padded = Vec::with_capacity(len);
+    padded.resize(begin, f64::NAN);
+    padded.extend(values);
+    padded.resize(len, f64::NAN);
+
+    padded
+}
+
+/// The order of the values of an input series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TimeOrder {
+    /// Oldest first. This is what TA-Lib expects.
+    #[default]
+    Ascending,
+    /// Newest first, as delivered by some data sources.
+    Descending,
+}
+
+/// Compute `f` over an `input` in the given time `order`.
+///
+/// TA-Lib assumes [`TimeOrder::Ascending`] inputs. Feeding it a newest first
+/// series gives wrong values, not an error. With [`TimeOrder::Descending`] the
+/// input is reversed before `f` is called and the values after. They are then
+/// newest first, like `input`: `values[i]` belongs to `input[i]` and the last
+/// `begin` inputs, the oldest, have no value.
+///
+/// `f` returns an [`Output`] or a `(values, begin)` tuple. The result is a
+/// tuple either way as newest first values do not line up with the inputs the
+/// way [`Output::aligned()`] and [`Output::indexed()`] assume.
+///
+/// ```
+/// # use ta_lib::*;
+/// let newest_first = [5.0, 4.0, 3.0, 2.0, 1.0];
+///
+/// let (sma, begin) = in_time_order(&newest_first, TimeOrder::Descending, |input| {
+///     simple_moving_average(input, Some(2))
+/// })?;
+///
+/// assert_eq!(vec![4.5, 3.5, 2.5, 1.5], sma);
+/// assert_eq!(1, begin);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn in_time_order<T: Into<Output>>(
+    input: &[f64],
+    order: TimeOrder,
+    f: impl FnOnce(&[f64]) -> Result<T, Error>,
+) -> Result<(Vec<f64>, usize), Error> {
+    match order {
+        TimeOrder::Ascending => f(input).map(|output| output.into().into()),
+        TimeOrder::Descending => {
+            let ascending = input.iter().rev().copied().collect::<Vec<_>>();
+            let Output { mut values, begin } = f(&ascending)?.into();
+            values.reverse();
+
+            Ok((values, begin))
+        }
+    }
+}
+
+/// Round each of `values` to the nearest `f32`.
+#[cfg(feature = "f32")]
+fn to_f32(values: Vec<f64>) -> Vec<f32> {
+    values.into_iter().map(|value| value as f32).collect()
+}
+
+/// The number of valid elements in an output buffer of `capacity` the C
+/// library reported as `out_size`.
+///
+/// The C library never reports more elements than the buffer, sized by
+/// [`check_lookback()`], can hold. Should it ever do so this panics instead of
+/// exposing uninitialized memory.
+#[cfg(not(feature = "pure_rust"))]
+fn output_len(out_size: i32, capacity: usize) -> usize {
+    let out_size = usize::try_from(out_size).expect("Negative output size.");
+    assert!(
+        out_size <= capacity,
+        "Output size {} exceeds output buffer capacity {}.",
+        out_size,
+        capacity
+    );
+
+    out_size
+}
+
+/// The `begin` the C library reported as `out_begin` for an input of `len`
+/// elements and an output buffer of the `capacity` [`check_lookback()`]
+/// returned.
+///
+/// Computing from the first input, TA-Lib starts outputting at the lookback.
+/// This includes any unstable period, e.g. of MAMA or the Hilbert Transform
+/// functions, which therefore begin later than their formula needs. No
+/// wrapped function is known to differ from its lookback otherwise. A debug
+/// build asserts this to catch a wrapper passing its parameters to the
+/// lookback function inconsistently. The reported `out_begin` is always
+/// returned.
+#[cfg(not(feature = "pure_rust"))]
+#[track_caller]
+fn checked_begin(out_begin: i32, len: usize, capacity: usize) -> usize {
+    debug_assert_eq!(
+        len - capacity,
+        out_begin as usize,
+        "Begin differs from the lookback."
+    );
+
+    out_begin as _
+}
+
+/// Key each value of an indicator result by the index of the input element it
+/// belongs to.
+///
+/// This makes joining the results of indicators with different `begin`s
+/// trivial, e.g. by looking up the same index in each map.
+pub fn to_index_map(values: Vec<f64>, begin: usize) -> BTreeMap<usize, f64> {
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(index, value)| (begin + index, value))
+        .collect()
+}
+
+/// Merge the `(values, begin)` results of several indicators into one row per
+/// input element.
+///
+/// Each row holds the index of the input element and, for each of `inputs` in
+/// order, its value for that element or `None` if it has none, e.g. during
+/// its warm-up.
+///
+/// ```
+/// # use ta_lib::*;
+/// let close = [1.0, 2.0, 3.0, 4.0];
+/// let Output {
+///     values: sma_2,
+///     begin: begin_2,
+/// } = simple_moving_average(&close, Some(2))?;
+/// let Output {
+///     values: sma_3,
+///     begin: begin_3,
+/// } = simple_moving_average(&close, Some(3))?;
+///
+/// let rows = align_many(&[(&sma_2, begin_2), (&sma_3, begin_3)], close.len());
+///
+/// assert_eq!((1, vec![Some(1.5), None]), rows[1]);
+/// assert_eq!((2, vec![Some(2.5), Some(2.0)]), rows[2]);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn align_many(inputs: &[(&[f64], usize)], input_len: usize) -> Vec<(usize, Vec<Option<f64>>)> {
+    (0..input_len)
+        .map(|index| {
+            (
+                index,
+                inputs
+                    .iter()
+                    .map(|(values, begin)| {
+                        index
+                            .checked_sub(*begin)
+                            .and_then(|index| values.get(index))
+                            .copied()
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Trim the `(values, begin)` results of two indicators to the input elements
+/// both have values for.
+///
+/// Returns the index of the first of these input elements and the values of
+/// `a` and `b` for them. Both slices have the same length and `a[i]` and
+/// `b[i]` belong to the same input element, e.g. to compare them with
+/// [`crossovers()`]. They are empty if the results do not overlap.
+///
+/// ```
+/// # use ta_lib::*;
+/// let close = [1.0, 2.0, 3.0, 4.0];
+/// let Output {
+///     values: sma_2,
+///     begin: begin_2,
+/// } = simple_moving_average(&close, Some(2))?;
+/// let Output {
+///     values: sma_3,
+///     begin: begin_3,
+/// } = simple_moving_average(&close, Some(3))?;
+///
+/// let (begin, sma_2, sma_3) = common_range((&sma_2, begin_2), (&sma_3, begin_3));
+///
+/// assert_eq!(2, begin);
+/// assert_eq!(&[2.5, 3.5], sma_2);
+/// assert_eq!(&[2.0, 3.0], sma_3);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn common_range<'a>(
+    (a, a_begin): (&'a [f64], usize),
+    (b, b_begin): (&'a [f64], usize),
+) -> (usize, &'a [f64], &'a [f64]) {
+    let begin = a_begin.max(b_begin);
+    let end = (a_begin + a.len()).min(b_begin + b.len());
+
+    if end <= begin {
+        return (begin, &[], &[]);
+    }
+
+    (
+        begin,
+        &a[begin - a_begin..end - a_begin],
+        &b[begin - b_begin..end - b_begin],
+    )
+}
+
+/// Append the results of a computation over newly arrived input to those of a
+/// previous computation, dropping the values both have in common.
+///
+/// When an indicator is recomputed over a window that overlaps the previous
+/// input, the values for the shared bars are computed again. `overlap` is the
+/// number of leading `new_values` that are already in `prev`. For a new input
+/// window that starts `shared` bars before the end of the previous input this
+/// is `shared - lookback`, where `lookback` is the number of bars the indicator
+/// needs before it outputs its first value, i.e. the `begin` it returns.
+///
+/// ```
+/// # use ta_lib::*;
+/// let close = (0..20).map(|x| x as f64).collect::<Vec<_>>();
+///
+/// // First computation over bars 0..15.
+/// let Output {
+///     values: mut sma, ..
+/// } = simple_moving_average(&close[..15], Some(5)).unwrap();
+/// // Bars 15..20 arrive. Recompute over 10..20 so the SMA has enough data.
+/// let Output {
+///     values: new_sma,
+///     begin,
+/// } = simple_moving_average(&close[10..], Some(5)).unwrap();
+/// // The two windows share 5 bars. The first 4 of these are the warm-up.
+/// append_incremental(&mut sma, new_sma, 5 - begin);
+///
+/// assert_eq!(simple_moving_average(&close, Some(5)).unwrap().values, sma);
+/// ```
+pub fn append_incremental(prev: &mut Vec<f64>, new_values: Vec<f64>, overlap: usize) {
+    prev.extend(new_values.into_iter().skip(overlap));
+}
+
+/// The `begin` index [`simple_moving_average()`] returns for `period`.
+///
+/// Returns [`Error::BadParam`] for a `period` the wrappers reject, e.g. zero.
+pub fn expected_begin_sma(period: usize) -> Result<usize, Error> {
+    period_or_default(Some(period))?;
+
+    Ok(period - 1)
+}
+
+/// The `begin` index [`exponential_moving_average()`] returns for `period`.
+///
+/// This assumes the default unstable period of zero.
+pub fn expected_begin_ema(period: usize) -> Result<usize, Error> {
+    period_or_default(Some(period))?;
+
+    Ok(period - 1)
+}
+
+/// The `begin` index [`average_true_range()`] and
+/// [`normalized_average_true_range()`] return for `period`.
+///
+/// The first true range needs a previous close, hence the extra candle.
+///
+/// This assumes the default unstable period of zero.
+pub fn expected_begin_atr(period: usize) -> Result<usize, Error> {
+    period_or_default(Some(period))?;
+
+    Ok(period)
+}
+
+/// The `begin` index [`positive_directional_indicator()`] and
+/// [`negative_directional_indicator()`] return for `period`.
+///
+/// This assumes the default unstable period of zero.
+pub fn expected_begin_directional_indicator(period: usize) -> Result<usize, Error> {
+    period_or_default(Some(period))?;
+
+    Ok(period.max(1))
+}
+
+/// The `begin` index [`average_directional_movement_index()`] returns for
+/// `period`.
+///
+/// ADX smoothes the directional movement index over another `period`, so its
+/// warm-up is almost twice as long as that of the directional indicators.
+///
+/// This assumes the default unstable period of zero.
+pub fn expected_begin_adx(period: usize) -> Result<usize, Error> {
+    period_or_default(Some(period))?;
+
+    Ok(2 * period - 1)
+}
+
+/// The `begin` index [`true_range()`] returns.
+pub fn expected_begin_true_range() -> usize {
+    1
+}
+
+/// The highs of the sample candles used by the tests.
+#[cfg(all(test, not(feature = "pure_rust")))]
+const SAMPLE_HIGH: [f64; 20] = [
+    1.087130, 1.087120, 1.087220, 1.087230, 1.087180, 1.087160, 1.087210, 1.087150, 1.087200,
+    1.087230, 1.087070, 1.087000, 1.086630, 1.086650, 1.086680, 1.086690, 1.086690, 1.086690,
+    1.086690, 1.086650,
+];
+
+/// The lows of the sample candles used by the tests.
+#[cfg(test)]
+const SAMPLE_LOW: [f64; 20] = [
+    1.087010, 1.087120, 1.087080, 1.087170, 1.087110, 1.087010, 1.087100, 1.087120, 1.087110,
+    1.087080, 1.087000, 1.086630, 1.086630, 1.086610, 1.086630, 1.086640, 1.086650, 1.086650,
+    1.086670, 1.086630,
+];
+
+/// The closes of the sample candles used by the tests.
+#[cfg(test)]
+const SAMPLE_CLOSE: [f64; 20] = [
+    1.087130, 1.087120, 1.087220, 1.087230, 1.087110, 1.087120, 1.087100, 1.087120, 1.087130,
+    1.087080, 1.087000, 1.086630, 1.086630, 1.086650, 1.086640, 1.086690, 1.086650, 1.086690,
+    1.086670, 1.086640,
+];
+
+/// The single series of the tests of functions over one series, e.g. the
+/// generated ones. These are the lows of the sample candles.
+#[cfg(test)]
+const SAMPLE_PRICES: [f64; 20] = SAMPLE_LOW;
+
+/// ATR(7) of the sample candles.
+#[cfg(all(test, not(feature = "pure_rust")))]
+const SAMPLE_ATR: [f64; 13] = [
+    0.00009142857142853598,
+    0.00009122448979587705,
+    0.0000996209912535907,
+    0.00009681799250308917,
+    0.00013584399357407378,
+    0.00011643770877777753,
+    0.00010551803609521188,
+    0.00009758688808162526,
+    0.000090788761212805,
+    0.00008353322389669571,
+    0.00007731419191145918,
+    0.00006912645020980915,
+    0.0000649655287512707,
+];
+
+/// EMA(10) of [`SAMPLE_PRICES`].
+#[cfg(test)]
+const SAMPLE_EMA: [f64; 11] = [
+    1.0870910000000003,
+    1.0870744545454547,
+    1.0869936446280992,
+    1.0869275274229904,
+    1.086869795164265,
+    1.0868261960434895,
+    1.0867923422174006,
+    1.0867664618142368,
+    1.086745286938921,
+    1.0867315984045716,
+    1.0867131259673768,
+];
+
+/// A sine wave around `100.0` with an amplitude of `5.0` and a period of
+/// about 21 inputs, used by the tests needing more inputs than the sample
+/// candles.
+#[cfg(all(test, not(feature = "pure_rust")))]
+fn sample_wave(len: usize) -> Vec<f64> {
+    (0..len)
+        .map(|index| 100.0 + (index as f64 * 0.3).sin() * 5.0)
+        .collect()
+}
+
+/// SMA(10) of [`SAMPLE_PRICES`].
+#[cfg(test)]
+const SAMPLE_SMA: [f64; 11] = [
+    1.0870910000000003,
+    1.0870900000000003,
+    1.0870410000000001,
+    1.086996,
+    1.08694,
+    1.086892,
+    1.086855,
+    1.08681,
+    1.086763,
+    1.086719,
+    1.086674,
+];
+
+/// WMA(10) of [`SAMPLE_PRICES`].
+#[cfg(test)]
+const SAMPLE_WMA: [f64; 11] = [
+    1.087095090909091,
+    1.0870785454545455,
+    1.086994909090909,
+    1.0869201818181817,
+    1.08685,
+    1.0867936363636364,
+    1.0867478181818182,
+    1.0867105454545456,
+    1.0866814545454546,
+    1.0866645454545456,
+    1.0866483636363637,
+];
+
+/// RSI(10) of [`SAMPLE_PRICES`].
+#[cfg(test)]
+const SAMPLE_RSI: [f64; 10] = [
+    49.20634920634417,
+    29.77588046958376,
+    29.77588046958376,
+    29.011386831331997,
+    30.980358438044632,
+    32.02775197555972,
+    33.154857387561094,
+    33.154857387561094,
+    35.78404061898681,
+    32.90772618416411,
+];
+
+/// Assert that `values` match the `expected` golden values within `1e-9`.
+#[cfg(test)]
+fn assert_expected(expected: &[f64], values: &[f64]) {
+    assert_eq!(expected.len(), values.len());
+
+    for (index, (expected, value)) in expected.iter().zip(values).enumerate() {
+        assert!(
+            (expected - value).abs() < 1e-9,
+            "Value {} is {} but {} was expected.",
+            index,
+            value,
+            expected
+        );
+    }
+}
 
 define_high_low_close_period_fn!(
     /// Compute [Average Directional (Movement) Index](https://www.tadoc.org/indicator/ADX.htm) over a period.
     ///
-    /// Returns a tuple containing the list of ADX values and the
+    /// Returns an [`Output`] containing the list of ADX values and the
     /// index of the first candle to have an associated ADX value.
     =>
     average_directional_movement_index,
     TA_ADX
 );
 
+define_high_low_close_period_fn!(
+    /// Compute [Average Directional Movement Index Rating](https://www.tadoc.org/indicator/ADXR.htm) over a period.
+    ///
+    /// Returns an [`Output`] containing the list of ADXR values and the
+    /// index of the first candle to have an associated ADXR value.
+    =>
+    average_directional_movement_index_rating,
+    TA_ADXR
+);
+
 define_high_low_close_period_fn!(
     /// Compute [Average True Range](https://www.tadoc.org/indicator/ATR.htm) over a period.
     ///
-    /// Returns a tuple containing the list of ATR values and the
+    /// Returns an [`Output`] containing the list of ATR values and the
     /// index of the first candle to have an associated ATR value.
     =>
     average_true_range,
-    TA_ATR
+    TA_ATR,
+    expected: SAMPLE_ATR
+);
+
+define_high_low_close_period_fn!(
+    /// Compute [Directional Movement Index](https://www.tadoc.org/indicator/DX.htm) over a period.
+    ///
+    /// Returns an [`Output`] containing the list of DX values and the
+    /// index of the first candle to have an associated DX value.
+    =>
+    directional_movement_index,
+    TA_DX
 );
 
 define_high_low_close_period_fn!(
     /// Compute [Normalized Average True Range](https://www.tadoc.org/indicator/NATR.htm) over a period.
     ///
-    /// Returns a tuple containing the list of NATR values and the
+    /// Returns an [`Output`] containing the list of NATR values and the
     /// index of the first candle to have an associated NATR value.
     =>
     normalized_average_true_range,
@@ -52,7 +970,7 @@ define_high_low_close_period_fn!(
 define_high_low_close_period_fn!(
     /// Compute [Negative Directional Indicator](https://www.tadoc.org/indicator/MINUS_DI.htm) over a period.
     ///
-    /// Returns a tuple containing the list of -DI values and the
+    /// Returns an [`Output`] containing the list of -DI values and the
     /// index of the first candle to have an associated -DI value.
     =>
     negative_directional_indicator,
@@ -62,7 +980,7 @@ define_high_low_close_period_fn!(
 define_high_low_close_period_fn!(
     /// Compute [Positive Directional Indicator](https://www.tadoc.org/indicator/PLUS_DI.htm) over a period.
     ///
-    /// Returns a tuple containing the list of +DI values and the
+    /// Returns an [`Output`] containing the list of +DI values and the
     /// index of the first candle to have an associated +DI value.
     =>
     positive_directional_indicator,
@@ -70,178 +988,3455 @@ define_high_low_close_period_fn!(
 );
 
 define_high_low_close_fn!(
-    /// Compute [True Range](https://www.tadoc.org/indicator/TRANGE.htm) over a period.
+    /// Compute [True Range](https://www.tadoc.org/indicator/TRANGE.htm).
     ///
-    /// Returns a tuple containing the list of TRANGE values and the
+    /// Returns an [`Output`] containing the list of TRANGE values and the
     /// index of the first candle to have an associated TRANGE value.
+    ///
+    /// The true range of a candle needs the previous close so `begin` is
+    /// always `1`.
     =>
     true_range,
     TA_TRANGE
 );
 
+define_high_low_close_fn!(
+    /// Compute the [Typical Price](https://www.tadoc.org/indicator/TYPPRICE.htm).
+    ///
+    /// This is the average of each candle's high, low and close.
+    ///
+    /// Returns an [`Output`] containing the list of TYPPRICE values and the
+    /// index of the first candle to have an associated TYPPRICE value.
+    =>
+    typical_price,
+    TA_TYPPRICE
+);
+
 define_values_period_fn!(
     /// Compute [Exponential Moving Average](https://www.tadoc.org/indicator/EMA.htm) over a period.
     ///
-    /// Returns a tuple containing the list of EMA values and the
+    /// Returns an [`Output`] containing the list of EMA values and the
     /// index of the first candle to have an associated EMA value.
     =>
     exponential_moving_average,
-    TA_EMA
+    TA_EMA,
+    memory: true,
+    fallback: exponential_moving_average,
+    expected: SAMPLE_EMA
 );
 
 define_values_period_fn!(
     /// Compute [Simple Moving Average](https://www.tadoc.org/indicator/SMA.htm) over a period.
     ///
-    /// Returns a tuple containing the list of SMA values and the
+    /// Returns an [`Output`] containing the list of SMA values and the
     /// index of the first close to have an associated SMA value.
     =>
     simple_moving_average,
-    TA_SMA
+    TA_SMA,
+    fallback: simple_moving_average,
+    expected: SAMPLE_SMA
 );
 
-pub enum MovingAverageType {
-    SimpleMovingAverage = ta::TA_MAType_TA_MAType_SMA as _,
-    ExponentialMovingAverage = ta::TA_MAType_TA_MAType_EMA as _,
-    WeightedMovingAverage = ta::TA_MAType_TA_MAType_WMA as _,
-    DoubleExponentialMovingAverage = ta::TA_MAType_TA_MAType_DEMA as _,
-    TripleExponentialMovingAverage = ta::TA_MAType_TA_MAType_TEMA as _,
-    TriangularMovingAverage = ta::TA_MAType_TA_MAType_TRIMA as _,
-    KaufmanAdaptiveMovingAverage = ta::TA_MAType_TA_MAType_KAMA as _,
-    MESAAdaptiveMovingAverage = ta::TA_MAType_TA_MAType_MAMA as _,
-    TripleGeneralizedDoubleExponentialMovingAverage = ta::TA_MAType_TA_MAType_T3 as _,
-}
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_latest() {
+    let close_prices = (0..1000)
+        .map(|index| 100.0 + (index as f64 * 0.3).sin() * 5.0 + (index as f64 * 0.07).cos())
+        .collect::<Vec<_>>();
 
-/// Compute [Bollinger Bands](https://www.tadoc.org/indicator/BBANDS.htm).
-///
-/// Returns a tuple containing the upper, middle and lower BBANDS values and the
-/// index of the first candle to have an associated BBANS value.
-pub fn bollinger_bands(
-    input: &[f64],
-    period: Option<usize>,
-    num_std_deviations_up: Option<f64>,
-    num_std_deviations_down: Option<f64>,
-    moving_average_type: Option<MovingAverageType>,
-) -> Result<(Vec<f64>, Vec<f64>, Vec<f64>, usize), Error> {
-    assert!(!input.is_empty());
+    // The latest value is the last of all values. Functions over a window
+    // keeping running totals, e.g. SMA, differ by their rounding.
+    #[allow(clippy::type_complexity)]
+    let functions: [(
+        &str,
+        fn(&[f64], Option<usize>) -> Result<Output, Error>,
+        fn(&[f64], Option<usize>) -> Result<Option<f64>, Error>,
+    ); 8] = [
+        (
+            "SMA",
+            |input, period| simple_moving_average(input, period),
+            |input, period| simple_moving_average_latest(input, period),
+        ),
+        (
+            "WMA",
+            |input, period| weighted_moving_average(input, period),
+            |input, period| weighted_moving_average_latest(input, period),
+        ),
+        (
+            "MAX",
+            |input, period| rolling_max(input, period),
+            |input, period| rolling_max_latest(input, period),
+        ),
+        (
+            "MIN",
+            |input, period| rolling_min(input, period),
+            |input, period| rolling_min_latest(input, period),
+        ),
+        (
+            "SUM",
+            |input, period| rolling_sum(input, period),
+            |input, period| rolling_sum_latest(input, period),
+        ),
+        (
+            "ROC",
+            |input, period| rate_of_change(input, period),
+            |input, period| rate_of_change_latest(input, period),
+        ),
+        (
+            "EMA",
+            |input, period| exponential_moving_average(input, period),
+            |input, period| exponential_moving_average_latest(input, period),
+        ),
+        (
+            "RSI",
+            |input, period| relative_strength_index(input, period),
+            |input, period| relative_strength_index_latest(input, period),
+        ),
+    ];
 
-    let mut out_begin = MaybeUninit::<i32>::uninit();
-    let mut out_size = MaybeUninit::<i32>::uninit();
-    let mut out_upper_band: Vec<f64> = Vec::with_capacity(input.len());
-    let mut out_middle_band: Vec<f64> = Vec::with_capacity(input.len());
-    let mut out_lower_band: Vec<f64> = Vec::with_capacity(input.len());
+    for (name, all, latest) in functions {
+        let last = *all(&close_prices, Some(20)).unwrap().values.last().unwrap();
+        let latest = latest(&close_prices, Some(20)).unwrap().unwrap();
 
-    unsafe {
-        let ret_code = ta::TA_BBANDS(
-            0,
-            (input.len() - 1) as _,
-            input.as_ptr(),
-            if let Some(period) = period {
-                period as _
-            } else {
-                // ta::TA_INTEGER_DEFAULT
-                i32::MIN
-            },
-            num_std_deviations_up.unwrap_or(ta::TA_REAL_DEFAULT),
-            num_std_deviations_down.unwrap_or(ta::TA_REAL_DEFAULT),
-            moving_average_type.unwrap_or(MovingAverageType::ExponentialMovingAverage) as _,
-            out_begin.as_mut_ptr(),
-            out_size.as_mut_ptr(),
-            out_upper_band.as_mut_ptr(),
-            out_middle_band.as_mut_ptr(),
-            out_lower_band.as_mut_ptr(),
+        assert!(
+            (last - latest).abs() < 1e-9,
+            "{}: {} != {}",
+            name,
+            latest,
+            last
         );
+    }
 
-        match ret_code {
-            ta::TA_RetCode_TA_SUCCESS => {
-                out_upper_band.set_len(out_size.assume_init() as _);
-                out_middle_band.set_len(out_size.assume_init() as _);
-                out_lower_band.set_len(out_size.assume_init() as _);
+    let lookback = relative_strength_index_lookback(Some(14)).unwrap();
+    assert_eq!(
+        None,
+        relative_strength_index_latest(&close_prices[..lookback], Some(14)).unwrap()
+    );
+}
 
-                Ok((
-                    out_upper_band,
-                    out_middle_band,
-                    out_lower_band,
-                    out_begin.assume_init() as _,
-                ))
-            }
-            _ => Err(Error(format!(
-                "Could not compute OBV; error: {:?}",
-                ret_code
-            ))),
-        }
-    }
+/// How [`exponential_moving_average_seeded()`] starts the EMA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EmaSeed {
+    /// The SMA of the first `period` values, as TA-Lib does. The first EMA
+    /// value is at `period - 1`.
+    #[default]
+    Sma,
+    /// The first value, as e.g. pandas' `ewm(adjust=False)` and many charting
+    /// platforms do. The first EMA value is at `0`.
+    FirstValue,
 }
 
-/*
+/// Compute [Exponential Moving Average](https://www.tadoc.org/indicator/EMA.htm)
+/// over a period, starting from the given `seed`.
+///
+/// With [`EmaSeed::Sma`] this is [`exponential_moving_average()`]. Use
+/// [`EmaSeed::FirstValue`] to reproduce the EMAs of libraries seeding with
+/// the first value. Both use `alpha = 2 / (period + 1)` and converge as the
+/// seed's weight decays.
+///
+/// Returns a tuple containing the list of EMA values and the
+/// index of the first candle to have an associated EMA value.
+pub fn exponential_moving_average_seeded(
+    input: impl AsRef<[f64]>,
+    period: Option<usize>,
+    seed: EmaSeed,
+) -> Result<(Vec<f64>, usize), Error> {
+    let input = input.as_ref();
+
+    match seed {
+        EmaSeed::Sma => exponential_moving_average(input, period).map(Into::into),
+        EmaSeed::FirstValue => {
+            check_range(0, input.len())?;
+
+            let period = ta_period("EMA", period, DEFAULT_MA_PERIOD)?;
+
+            let alpha = 2.0 / (period + 1) as f64;
+            let mut ema = input[0];
+
+            Ok((
+                input
+                    .iter()
+                    .map(|value| {
+                        ema += alpha * (value - ema);
+                        ema
+                    })
+                    .collect(),
+                0,
+            ))
+        }
+    }
+}
+
+#[test]
+fn test_exponential_moving_average_seeded() {
+    let close_prices = SAMPLE_CLOSE;
+
+    assert_eq!(
+        exponential_moving_average(close_prices, Some(5)).unwrap(),
+        Output::from(
+            exponential_moving_average_seeded(close_prices, Some(5), EmaSeed::Sma).unwrap()
+        )
+    );
+
+    let (sma_seeded, sma_begin) =
+        exponential_moving_average_seeded(close_prices, Some(5), EmaSeed::Sma).unwrap();
+    let (first_value_seeded, first_value_begin) =
+        exponential_moving_average_seeded(close_prices, Some(5), EmaSeed::FirstValue).unwrap();
+
+    assert_eq!(4, sma_begin);
+    assert_eq!(0, first_value_begin);
+    assert_eq!(close_prices.len(), first_value_seeded.len());
+    assert_eq!(close_prices[0], first_value_seeded[0]);
+
+    let difference = |index: usize| (sma_seeded[index] - first_value_seeded[index + 4]).abs();
+
+    // The difference of the seeds decays by `1 - alpha` with each value.
+    assert!(1e-6 < difference(0));
+    assert!(difference(sma_seeded.len() - 1) < 1e-8);
+
+    for index in 1..sma_seeded.len() {
+        assert!((difference(index) - difference(index - 1) * 2.0 / 3.0).abs() < 1e-12);
+    }
+
+    // The range of TA-Lib's EMA.
+    for period in [1, 100_001] {
+        assert!(matches!(
+            exponential_moving_average_seeded(close_prices, Some(period), EmaSeed::FirstValue),
+            Err(Error::BadParam(_))
+        ));
+    }
+}
+
+/// Compute [Simple Moving Average](https://www.tadoc.org/indicator/SMA.htm) over a period.
+///
+/// Same as [`simple_moving_average()`] but takes the values from any iterator,
+/// e.g. a database cursor or a channel. They are collected into a buffer for
+/// the C library.
+///
+/// ```
+/// # use ta_lib::*;
+/// let sma = simple_moving_average_iter((0..20).map(|x| x as f64), Some(5))?;
+///
+/// assert_eq!(4, sma.begin);
+/// assert_eq!(2.0, sma[0]);
+/// # Ok::<(), Error>(())
+/// ```
+pub fn simple_moving_average_iter(
+    input: impl IntoIterator<Item = f64>,
+    period: Option<usize>,
+) -> Result<Output, Error> {
+    simple_moving_average(input.into_iter().collect::<Vec<_>>(), period)
+}
+
+/// Compute [Simple Moving Average](https://www.tadoc.org/indicator/SMA.htm)
+/// over each of `periods`, e.g. for a moving average ribbon.
+///
+/// Returns the period, values and `begin` of each SMA in the order of
+/// `periods`. Fails if any of them can not be computed, e.g. because the input
+/// is too short for the longest period.
+#[allow(clippy::type_complexity)]
+pub fn simple_moving_average_multi(
+    input: &[f64],
+    periods: &[usize],
+) -> Result<Vec<(usize, Vec<f64>, usize)>, Error> {
+    periods
+        .iter()
+        .map(|period| {
+            simple_moving_average(input, Some(*period))
+                .map(|Output { values, begin }| (*period, values, begin))
+        })
+        .collect()
+}
+
+define_values_period_fn!(
+    /// Compute [Weighted Moving Average](https://www.tadoc.org/indicator/WMA.htm) over a period.
+    ///
+    /// Returns an [`Output`] containing the list of WMA values and the
+    /// index of the first input to have an associated WMA value.
+    =>
+    weighted_moving_average,
+    TA_WMA,
+    fallback: weighted_moving_average,
+    expected: SAMPLE_WMA
+);
+
+define_values_period_fn!(
+    /// Compute the highest value over a period.
+    ///
+    /// Returns an [`Output`] containing the list of MAX values and the
+    /// index of the first input to have an associated MAX value.
+    =>
+    rolling_max,
+    TA_MAX
+);
+
+define_values_period_fn!(
+    /// Compute the lowest value over a period.
+    ///
+    /// Returns an [`Output`] containing the list of MIN values and the
+    /// index of the first input to have an associated MIN value.
+    =>
+    rolling_min,
+    TA_MIN
+);
+
+define_values_period_fn!(
+    /// Compute the sum over a period.
+    ///
+    /// Returns an [`Output`] containing the list of SUM values and the
+    /// index of the first input to have an associated SUM value.
+    =>
+    rolling_sum,
+    TA_SUM
+);
+
+define_values_period_fn!(
+    /// Compute the [Rate of Change](https://www.tadoc.org/indicator/ROC.htm) over a period.
+    ///
+    /// This is `(value / previous_value - 1) * 100` where `previous_value` is
+    /// `period` inputs back.
+    ///
+    /// Returns an [`Output`] containing the list of ROC values and the
+    /// index of the first input to have an associated ROC value.
+    =>
+    rate_of_change,
+    TA_ROC
+);
+
+define_values_period_fn!(
+    /// Compute [Relative Strength Index](https://www.tadoc.org/indicator/RSI.htm) over a period.
+    ///
+    /// Returns an [`Output`] containing the list of RSI values and the
+    /// index of the first close to have an associated RSI value.
+    =>
+    relative_strength_index,
+    TA_RSI,
+    memory: true,
+    fallback: relative_strength_index,
+    expected: SAMPLE_RSI
+);
+
+define_values_two_period_fn!(
+    /// Compute the [Absolute Price Oscillator](https://www.tadoc.org/indicator/APO.htm).
+    ///
+    /// This is the difference of the moving averages over `fast_period` and
+    /// `slow_period`. The moving average type defaults to
+    /// [`MovingAverageType::SimpleMovingAverage`].
+    ///
+    /// Returns an [`Output`] containing the list of APO values and the
+    /// index of the first input to have an associated APO value.
+    =>
+    absolute_price_oscillator,
+    TA_APO
+);
+
+define_values_two_period_fn!(
+    /// Compute the [Percentage Price Oscillator](https://www.tadoc.org/indicator/PPO.htm).
+    ///
+    /// This is the difference of the moving averages over `fast_period` and
+    /// `slow_period` in percent of the slow one. The moving average type
+    /// defaults to [`MovingAverageType::SimpleMovingAverage`].
+    ///
+    /// Returns an [`Output`] containing the list of PPO values and the
+    /// index of the first input to have an associated PPO value.
+    =>
+    percentage_price_oscillator,
+    TA_PPO
+);
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_price_oscillators_reference() {
+    let close_prices = sample_wave(60);
+
+    let Output {
+        values: fast,
+        begin: fast_begin,
+    } = simple_moving_average(&close_prices, Some(5)).unwrap();
+    let Output {
+        values: slow,
+        begin: slow_begin,
+    } = simple_moving_average(&close_prices, Some(20)).unwrap();
+    let fast = &fast[slow_begin - fast_begin..];
+
+    let Output {
+        values: apo,
+        begin: apo_begin,
+    } = absolute_price_oscillator(&close_prices, Some(5), Some(20), None).unwrap();
+    let Output {
+        values: ppo,
+        begin: ppo_begin,
+    } = percentage_price_oscillator(&close_prices, Some(5), Some(20), None).unwrap();
+
+    assert_eq!(slow_begin, apo_begin);
+    assert_eq!(slow_begin, ppo_begin);
+
+    for (((fast, slow), apo), ppo) in fast.iter().zip(&slow).zip(&apo).zip(&ppo) {
+        assert!((fast - slow - apo).abs() < 1e-9);
+        assert!(((fast - slow) / slow * 100.0 - ppo).abs() < 1e-9);
+    }
+}
+
+/// Compute Welles Wilder's smoothing over a period.
+///
+/// This is the moving average TA-Lib uses internally for e.g. ATR and RSI. It
+/// is an EMA with `alpha = 1 / period`, seeded with the SMA of the first
+/// `period` values. TA-Lib does not expose it as a function of its own.
+///
+/// Returns a tuple containing the list of smoothed values and the index of
+/// the first input to have an associated value.
+pub fn wilders_smoothing(input: &[f64], period: usize) -> Result<(Vec<f64>, usize), Error> {
+    check_range(0, input.len())?;
+
+    let period = checked_period(period, 1)?;
+
+    check_lookback(input.len(), (period - 1) as _)?;
+
+    let mut out = Vec::with_capacity(input.len() - period + 1);
+    let mut smoothed = input[..period].iter().sum::<f64>() / period as f64;
+    out.push(smoothed);
+
+    for value in &input[period..] {
+        smoothed += (value - smoothed) / period as f64;
+        out.push(smoothed);
+    }
+
+    Ok((out, period - 1))
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_wilders_smoothing() {
+    let high = SAMPLE_HIGH;
+    let low = SAMPLE_LOW;
+    let close = SAMPLE_CLOSE;
+
+    let Output {
+        values: true_range_values,
+        begin: true_range_begin,
+    } = true_range(high, low, close).unwrap();
+    let (smoothed, begin) = wilders_smoothing(&true_range_values, 7).unwrap();
+    let Output {
+        values: atr_values,
+        begin: atr_begin,
+    } = average_true_range(high, low, close, Some(7)).unwrap();
+
+    assert_eq!(atr_begin, true_range_begin + begin);
+    assert_eq!(atr_values.len(), smoothed.len());
+
+    for (atr, smoothed) in atr_values.iter().zip(&smoothed) {
+        assert!((atr - smoothed).abs() < 1e-12);
+    }
+}
+
+/// Compute the [Hull Moving Average](https://alanhull.com/hull-moving-average)
+/// over a period.
+///
+/// This is the WMA over `sqrt(period)`, rounded to the nearest integer, of
+/// twice the WMA over `period / 2` minus the WMA over `period`. It follows
+/// trends with much less lag than a WMA over the same period.
+///
+/// The difference starts at the `begin` of the WMA over `period`. The outer
+/// WMA needs another `sqrt(period) - 1` differences to warm up.
+///
+/// Returns [`Error::BadParam`] if `period` is less than `4`. Below that
+/// `period / 2` or `sqrt(period)` is less than the WMA's minimum of `2`.
+///
+/// Returns a tuple containing the list of Hull MA values and the index of the
+/// first input to have an associated value.
+pub fn hull_moving_average(
+    input: impl AsRef<[f64]>,
+    period: usize,
+) -> Result<(Vec<f64>, usize), Error> {
+    let input = input.as_ref();
+
+    if period < 4 {
+        return Err(Error::BadParam(format!(
+            "The period of the Hull MA must be at least 4, not {}.",
+            period
+        )));
+    }
+
+    let Output {
+        values: half,
+        begin: half_begin,
+    } = weighted_moving_average(input, Some(period / 2))?;
+    let Output {
+        values: full,
+        begin,
+    } = weighted_moving_average(input, Some(period))?;
+
+    let difference = half[begin - half_begin..]
+        .iter()
+        .zip(&full)
+        .map(|(half, full)| 2.0 * half - full)
+        .collect::<Vec<_>>();
+
+    let sqrt_period = (period as f64).sqrt().round() as usize;
+
+    let Output {
+        values,
+        begin: hull_begin,
+    } = weighted_moving_average(&difference, Some(sqrt_period))
+        .map_err(|error| rebase_too_short(error, begin))?;
+
+    Ok((values, begin + hull_begin))
+}
+
+#[test]
+fn test_hull_moving_average() {
+    let input = (0..50).map(|index| 2.0 * index as f64).collect::<Vec<_>>();
+
+    let (hull, begin) = hull_moving_average(&input, 16).unwrap();
+    assert_eq!(
+        (hull.clone(), begin),
+        hull_moving_average(input.clone(), 16).unwrap()
+    );
+
+    // WMA(16) needs 15 inputs to warm up, the WMA(4) of the differences 3.
+    assert_eq!(15 + 3, begin);
+    assert_eq!(input.len() - begin, hull.len());
+
+    let Output {
+        values: wma,
+        begin: wma_begin,
+    } = weighted_moving_average(&input, Some(16)).unwrap();
+
+    for (index, hull) in indexed(&hull, begin) {
+        let wma = wma[index - wma_begin];
+
+        assert!((input[index] - hull).abs() < (input[index] - wma).abs());
+    }
+
+    // On a ramp the lag is constant: WMA(n) lags (n - 1) / 3 inputs.
+    assert!((input[begin] - 2.0 * 2.0 / 3.0 - hull[0]).abs() < 1e-9);
+
+    assert!(matches!(
+        hull_moving_average(&input[..17], 16),
+        Err(Error::InsufficientData { have: 17, need: 19 })
+    ));
+
+    // The smallest period has inner WMAs over 2 and 4 and an outer one over 2.
+    let (hull, begin) = hull_moving_average(&input, 4).unwrap();
+    assert_eq!(3 + 1, begin);
+    assert_eq!(input.len() - begin, hull.len());
+
+    for period in 0..4 {
+        assert!(matches!(
+            hull_moving_average(&input, period),
+            Err(Error::BadParam(message)) if message.contains("Hull MA")
+        ));
+    }
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MovingAverageType {
+    SimpleMovingAverage = ta::TA_MAType_TA_MAType_SMA as _,
+    ExponentialMovingAverage = ta::TA_MAType_TA_MAType_EMA as _,
+    WeightedMovingAverage = ta::TA_MAType_TA_MAType_WMA as _,
+    DoubleExponentialMovingAverage = ta::TA_MAType_TA_MAType_DEMA as _,
+    TripleExponentialMovingAverage = ta::TA_MAType_TA_MAType_TEMA as _,
+    TriangularMovingAverage = ta::TA_MAType_TA_MAType_TRIMA as _,
+    KaufmanAdaptiveMovingAverage = ta::TA_MAType_TA_MAType_KAMA as _,
+    MESAAdaptiveMovingAverage = ta::TA_MAType_TA_MAType_MAMA as _,
+    TripleGeneralizedDoubleExponentialMovingAverage = ta::TA_MAType_TA_MAType_T3 as _,
+}
+
+#[cfg(not(feature = "pure_rust"))]
+impl MovingAverageType {
+    /// Every moving average type, in the order of their TA-Lib codes.
+    pub const ALL: [MovingAverageType; 9] = [
+        MovingAverageType::SimpleMovingAverage,
+        MovingAverageType::ExponentialMovingAverage,
+        MovingAverageType::WeightedMovingAverage,
+        MovingAverageType::DoubleExponentialMovingAverage,
+        MovingAverageType::TripleExponentialMovingAverage,
+        MovingAverageType::TriangularMovingAverage,
+        MovingAverageType::KaufmanAdaptiveMovingAverage,
+        MovingAverageType::MESAAdaptiveMovingAverage,
+        MovingAverageType::TripleGeneralizedDoubleExponentialMovingAverage,
+    ];
+}
+
+#[cfg(not(feature = "pure_rust"))]
+impl From<MovingAverageType> for i32 {
+    /// The TA-Lib code of the moving average type.
+    fn from(moving_average_type: MovingAverageType) -> Self {
+        moving_average_type as _
+    }
+}
+
+#[cfg(not(feature = "pure_rust"))]
+impl TryFrom<i32> for MovingAverageType {
+    type Error = Error;
+
+    /// The moving average type with the TA-Lib `code`, e.g. from a config
+    /// file.
+    ///
+    /// Returns [`Error::BadParam`] for unknown codes.
+    fn try_from(code: i32) -> Result<Self, Error> {
+        MovingAverageType::ALL
+            .into_iter()
+            .find(|moving_average_type| code == *moving_average_type as i32)
+            .ok_or_else(|| Error::BadParam(format!("Unknown moving average type {}.", code)))
+    }
+}
+
+/// Compute the [Moving Average](https://www.tadoc.org/indicator/MA.htm) of the
+/// given type over a period.
+///
+/// The moving average type defaults to
+/// [`MovingAverageType::SimpleMovingAverage`]. The MESA adaptive moving
+/// average ignores `period` and uses its default limits, see
+/// [`mesa_adaptive_moving_average()`] to set them.
+///
+/// Returns a tuple containing the list of MA values and the index of the first
+/// input to have an associated MA value.
+#[cfg(not(feature = "pure_rust"))]
+pub fn moving_average(
+    input: &[f64],
+    period: Option<usize>,
+    moving_average_type: Option<MovingAverageType>,
+) -> Result<(Vec<f64>, usize), Error> {
+    check_range(0, input.len())?;
+
+    trace_span!("moving_average", input.len(), period, moving_average_type);
+
+    check_input_len(input.len())?;
+
+    let period = period_or_default(period)?;
+    let moving_average_type =
+        moving_average_type.unwrap_or(MovingAverageType::SimpleMovingAverage) as _;
+
+    let capacity = check_lookback(input.len(), unsafe {
+        ta::TA_MA_Lookback(period, moving_average_type)
+    })?;
+
+    let mut out: Vec<f64> = Vec::with_capacity(capacity);
+    let mut out_begin: i32 = 0;
+    let mut out_size: i32 = 0;
+
+    unsafe {
+        let ret_code = ta::TA_MA(
+            0,
+            (input.len() - 1) as _,
+            input.as_ptr(),
+            period,
+            moving_average_type,
+            &mut out_begin,
+            &mut out_size,
+            out.as_mut_ptr(),
+        );
+
+        match ret_code {
+            ta::TA_RetCode_TA_SUCCESS => {
+                out.set_len(output_len(out_size, out.capacity()));
+                Ok((out, checked_begin(out_begin, input.len(), capacity)))
+            }
+            _ => {
+                trace_error!(ret_code);
+
+                Err(Error::Computation(format!(
+                    "Could not compute MA; error: {:?}",
+                    ret_code
+                )))
+            }
+        }
+    }
+}
+
+/// Compute the [Moving Average](https://www.tadoc.org/indicator/MA.htm) of the
+/// given type over a period.
+///
+/// Same as [`moving_average()`] but returns as many values as there are
+/// inputs.
+///
+/// Inputs without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn moving_average_padded(
+    input: &[f64],
+    period: Option<usize>,
+    moving_average_type: Option<MovingAverageType>,
+) -> Result<Vec<f64>, Error> {
+    let (values, begin) = moving_average(input, period, moving_average_type)?;
+    Ok(nan_padded(values, begin, input.len()))
+}
+
+/// Compute every [moving average type](MovingAverageType) over a period, e.g.
+/// to display them together in a chart.
+///
+/// Returns the type, values and `begin` of each moving average in the order of
+/// [`MovingAverageType::ALL`]. Fails if any of them can not be computed, e.g.
+/// because the input is too short for the one with the longest lookback.
+#[cfg(not(feature = "pure_rust"))]
+#[allow(clippy::type_complexity)]
+pub fn all_moving_averages(
+    input: &[f64],
+    period: usize,
+) -> Result<Vec<(MovingAverageType, Vec<f64>, usize)>, Error> {
+    MovingAverageType::ALL
+        .iter()
+        .map(|moving_average_type| {
+            moving_average(input, Some(period), Some(*moving_average_type))
+                .map(|(values, begin)| (*moving_average_type, values, begin))
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_all_moving_averages() {
+    let close_prices = sample_wave(100);
+
+    let moving_averages = all_moving_averages(&close_prices, 10).unwrap();
+
+    assert_eq!(
+        MovingAverageType::ALL.to_vec(),
+        moving_averages
+            .iter()
+            .map(|(moving_average_type, _, _)| *moving_average_type)
+            .collect::<Vec<_>>()
+    );
+
+    for (_, values, begin) in &moving_averages {
+        assert_eq!(close_prices.len() - begin, values.len());
+    }
+
+    // The generic MA matches the dedicated functions.
+    assert_eq!(
+        simple_moving_average(&close_prices, Some(10))
+            .unwrap()
+            .values,
+        moving_averages[0].1
+    );
+    assert_eq!(9, moving_averages[0].2);
+    assert_eq!(
+        exponential_moving_average(&close_prices, Some(10))
+            .unwrap()
+            .values,
+        moving_averages[1].1
+    );
+}
+
+/// The upper, middle and lower bands returned by [`bollinger_bands()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BollingerBands {
+    pub upper: Vec<f64>,
+    pub middle: Vec<f64>,
+    pub lower: Vec<f64>,
+    /// The index of the first candle to have an associated BBANDS value.
+    pub begin: usize,
+}
+
+/// The outputs of an indicator as named columns.
+///
+/// This is the uniform shape of every indicator, regardless of how many series
+/// it outputs, e.g. for feeding results into a data frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndicatorOutput {
+    /// The name and values of each output series, in the order TA-Lib outputs
+    /// them.
+    pub columns: Vec<(String, Vec<f64>)>,
+    /// The index of the first input to have associated output values.
+    pub begin: usize,
+}
+
+impl IndicatorOutput {
+    /// The values of the column with the given `name`.
+    pub fn column(&self, name: &str) -> Option<&[f64]> {
+        self.columns
+            .iter()
+            .find(|(column_name, _)| column_name == name)
+            .map(|(_, values)| values.as_slice())
+    }
+}
+
+impl From<BollingerBands> for IndicatorOutput {
+    fn from(bands: BollingerBands) -> Self {
+        Self {
+            columns: vec![
+                ("upper".to_string(), bands.upper),
+                ("middle".to_string(), bands.middle),
+                ("lower".to_string(), bands.lower),
+            ],
+            begin: bands.begin,
+        }
+    }
+}
+
+/// Compute [Bollinger Bands](https://www.tadoc.org/indicator/BBANDS.htm).
+///
+/// `num_std_deviations_up` and `num_std_deviations_down` default
+/// independently. Passing only one of them gives asymmetric bands. Use
+/// [`bollinger_bands_symmetric()`] for the usual case of both being the same.
+///
+/// `moving_average_type` defaults to an exponential moving average, unlike in
+/// TA-Lib itself, which defaults to a simple one. Use
+/// [`bollinger_bands_strict()`] to get TA-Lib's default.
+///
+/// Returns the upper, middle and lower BBANDS values and the index of the
+/// first candle to have an associated BBANDS value.
+#[cfg(not(feature = "pure_rust"))]
+pub fn bollinger_bands(
+    input: &[f64],
+    period: Option<usize>,
+    num_std_deviations_up: Option<f64>,
+    num_std_deviations_down: Option<f64>,
+    moving_average_type: Option<MovingAverageType>,
+) -> Result<BollingerBands, Error> {
+    check_range(0, input.len())?;
+
+    trace_span!(
+        "bollinger_bands",
+        input.len(),
+        period,
+        num_std_deviations_up,
+        num_std_deviations_down,
+        moving_average_type
+    );
+
+    check_input_len(input.len())?;
+
+    let period = period_or_default(period)?;
+    let num_std_deviations_up = num_std_deviations_up.unwrap_or(ta::TA_REAL_DEFAULT);
+    let num_std_deviations_down = num_std_deviations_down.unwrap_or(ta::TA_REAL_DEFAULT);
+    let moving_average_type =
+        moving_average_type.unwrap_or(MovingAverageType::ExponentialMovingAverage) as _;
+
+    let capacity = check_lookback(input.len(), unsafe {
+        ta::TA_BBANDS_Lookback(
+            period,
+            num_std_deviations_up,
+            num_std_deviations_down,
+            moving_average_type,
+        )
+    })?;
+
+    let mut out_begin: i32 = 0;
+    let mut out_size: i32 = 0;
+    let mut out_upper_band: Vec<f64> = Vec::with_capacity(capacity);
+    let mut out_middle_band: Vec<f64> = Vec::with_capacity(capacity);
+    let mut out_lower_band: Vec<f64> = Vec::with_capacity(capacity);
+
+    unsafe {
+        let ret_code = ta::TA_BBANDS(
+            0,
+            (input.len() - 1) as _,
+            input.as_ptr(),
+            period,
+            num_std_deviations_up,
+            num_std_deviations_down,
+            moving_average_type,
+            &mut out_begin,
+            &mut out_size,
+            out_upper_band.as_mut_ptr(),
+            out_middle_band.as_mut_ptr(),
+            out_lower_band.as_mut_ptr(),
+        );
+
+        match ret_code {
+            ta::TA_RetCode_TA_SUCCESS => {
+                out_upper_band.set_len(output_len(out_size, out_upper_band.capacity()));
+                out_middle_band.set_len(output_len(out_size, out_middle_band.capacity()));
+                out_lower_band.set_len(output_len(out_size, out_lower_band.capacity()));
+
+                Ok(BollingerBands {
+                    upper: out_upper_band,
+                    middle: out_middle_band,
+                    lower: out_lower_band,
+                    begin: checked_begin(out_begin, input.len(), capacity),
+                })
+            }
+            _ => {
+                trace_error!(ret_code);
+
+                Err(Error::Computation(format!(
+                    "Could not compute BBANDS; error: {:?}",
+                    ret_code
+                )))
+            }
+        }
+    }
+}
+
+/// Compute [Bollinger Bands](https://www.tadoc.org/indicator/BBANDS.htm).
+///
+/// Same as [`bollinger_bands()`] but the upper and lower bands are both
+/// `num_std_deviations` away from the middle one.
+#[cfg(not(feature = "pure_rust"))]
+pub fn bollinger_bands_symmetric(
+    input: &[f64],
+    period: Option<usize>,
+    num_std_deviations: Option<f64>,
+    moving_average_type: Option<MovingAverageType>,
+) -> Result<BollingerBands, Error> {
+    bollinger_bands(
+        input,
+        period,
+        num_std_deviations,
+        num_std_deviations,
+        moving_average_type,
+    )
+}
+
+/// Compute [Bollinger Bands](https://www.tadoc.org/indicator/BBANDS.htm).
+///
+/// Same as [`bollinger_bands()`] but `moving_average_type` defaults to a
+/// simple moving average, like in TA-Lib itself and other tools built on it.
+#[cfg(not(feature = "pure_rust"))]
+pub fn bollinger_bands_strict(
+    input: &[f64],
+    period: Option<usize>,
+    num_std_deviations_up: Option<f64>,
+    num_std_deviations_down: Option<f64>,
+    moving_average_type: Option<MovingAverageType>,
+) -> Result<BollingerBands, Error> {
+    bollinger_bands(
+        input,
+        period,
+        num_std_deviations_up,
+        num_std_deviations_down,
+        Some(moving_average_type.unwrap_or(MovingAverageType::SimpleMovingAverage)),
+    )
+}
+
+/// Compute [Bollinger Bands](https://www.tadoc.org/indicator/BBANDS.htm).
+///
+/// Returns a tuple containing the upper, middle and lower BBANDS values and the
+/// index of the first candle to have an associated BBANDS value.
+#[cfg(not(feature = "pure_rust"))]
+#[deprecated(
+    since = "0.1.2",
+    note = "use `bollinger_bands()` which returns a `BollingerBands` struct"
+)]
+#[allow(clippy::type_complexity)]
+pub fn bollinger_bands_tuple(
+    input: &[f64],
+    period: Option<usize>,
+    num_std_deviations_up: Option<f64>,
+    num_std_deviations_down: Option<f64>,
+    moving_average_type: Option<MovingAverageType>,
+) -> Result<(Vec<f64>, Vec<f64>, Vec<f64>, usize), Error> {
+    bollinger_bands(
+        input,
+        period,
+        num_std_deviations_up,
+        num_std_deviations_down,
+        moving_average_type,
+    )
+    .map(|bands| (bands.upper, bands.middle, bands.lower, bands.begin))
+}
+
+/// Compute [Bollinger Bands](https://www.tadoc.org/indicator/BBANDS.htm).
+///
+/// Same as [`bollinger_bands()`] but each band has as many values as there are
+/// inputs.
+///
+/// Inputs without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn bollinger_bands_padded(
+    input: &[f64],
+    period: Option<usize>,
+    num_std_deviations_up: Option<f64>,
+    num_std_deviations_down: Option<f64>,
+    moving_average_type: Option<MovingAverageType>,
+) -> Result<BollingerBands, Error> {
+    let bands = bollinger_bands(
+        input,
+        period,
+        num_std_deviations_up,
+        num_std_deviations_down,
+        moving_average_type,
+    )?;
+
+    Ok(BollingerBands {
+        upper: nan_padded(bands.upper, bands.begin, input.len()),
+        middle: nan_padded(bands.middle, bands.begin, input.len()),
+        lower: nan_padded(bands.lower, bands.begin, input.len()),
+        begin: bands.begin,
+    })
+}
+
+/// Compute [Bollinger Bands](https://www.tadoc.org/indicator/BBANDS.htm).
+///
+/// Same as [`bollinger_bands()`] but returns the bands as the columns
+/// `"upper"`, `"middle"` and `"lower"`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn bollinger_bands_columns(
+    input: &[f64],
+    period: Option<usize>,
+    num_std_deviations_up: Option<f64>,
+    num_std_deviations_down: Option<f64>,
+    moving_average_type: Option<MovingAverageType>,
+) -> Result<IndicatorOutput, Error> {
+    bollinger_bands(
+        input,
+        period,
+        num_std_deviations_up,
+        num_std_deviations_down,
+        moving_average_type,
+    )
+    .map(Into::into)
+}
+
+/// The parameters of [`bollinger_bands_with()`].
+///
+/// The [`Default`] is what [`bollinger_bands_strict()`] uses for `None`, like
+/// TA-Lib itself: 5 inputs, 2 standard deviations up and down and a simple
+/// moving average.
+#[cfg(not(feature = "pure_rust"))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BollingerBandsParameters {
+    pub period: usize,
+    pub num_std_deviations_up: f64,
+    pub num_std_deviations_down: f64,
+    pub moving_average_type: MovingAverageType,
+}
+
+#[cfg(not(feature = "pure_rust"))]
+impl Default for BollingerBandsParameters {
+    fn default() -> Self {
+        Self {
+            period: 5,
+            num_std_deviations_up: 2.0,
+            num_std_deviations_down: 2.0,
+            moving_average_type: MovingAverageType::SimpleMovingAverage,
+        }
+    }
+}
+
+/// Compute [Bollinger Bands](https://www.tadoc.org/indicator/BBANDS.htm).
+///
+/// Same as [`bollinger_bands()`] but takes the parameters as a struct.
+///
+/// ```
+/// # use ta_lib::*;
+/// let close = (0..30).map(|close| close as f64).collect::<Vec<_>>();
+///
+/// let bands = bollinger_bands_with(
+///     &close,
+///     BollingerBandsParameters {
+///         period: 20,
+///         ..Default::default()
+///     },
+/// )?;
+///
+/// assert_eq!(19, bands.begin);
+/// # Ok::<(), Error>(())
+/// ```
+#[cfg(not(feature = "pure_rust"))]
+pub fn bollinger_bands_with(
+    input: &[f64],
+    parameters: BollingerBandsParameters,
+) -> Result<BollingerBands, Error> {
+    bollinger_bands(
+        input,
+        Some(parameters.period),
+        Some(parameters.num_std_deviations_up),
+        Some(parameters.num_std_deviations_down),
+        Some(parameters.moving_average_type),
+    )
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_bollinger_bands_with() {
+    let close_prices = sample_wave(40);
+
+    let defaults = bollinger_bands_with(&close_prices, Default::default()).unwrap();
+    let expected = bollinger_bands_strict(&close_prices, None, None, None, None).unwrap();
+
+    assert_eq!(expected.begin, defaults.begin);
+    assert_eq!(expected.upper, defaults.upper);
+    assert_eq!(expected.lower, defaults.lower);
+
+    // Like TA-Lib, the middle band defaults to the SMA.
+    let sma = simple_moving_average(&close_prices, Some(5)).unwrap();
+    assert_eq!(sma.begin, defaults.begin);
+
+    for (sma, middle) in sma.iter().zip(&defaults.middle) {
+        assert!((sma - middle).abs() < 1e-9);
+    }
+
+    let wide = bollinger_bands_with(
+        &close_prices,
+        BollingerBandsParameters {
+            num_std_deviations_up: 3.0,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let expected = bollinger_bands_strict(&close_prices, None, Some(3.0), None, None).unwrap();
+
+    assert_eq!(expected.upper, wide.upper);
+    assert_eq!(defaults.lower, wide.lower);
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_bollinger_bands() {
+    let close_prices = SAMPLE_PRICES;
+
+    let bands = bollinger_bands(&close_prices, Some(5), Some(2.0), Some(2.0), None).unwrap();
+
+    assert_eq!(4, bands.begin);
+    assert_eq!(close_prices.len() - bands.begin, bands.middle.len());
+    assert_eq!(bands.upper.len(), bands.middle.len());
+    assert_eq!(bands.lower.len(), bands.middle.len());
+
+    for ((upper, middle), lower) in bands.upper.iter().zip(&bands.middle).zip(&bands.lower) {
+        assert!(lower <= middle && middle <= upper);
+    }
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_bollinger_bands_strict() {
+    let close_prices = (0..30)
+        .map(|index| 100.0 + (index as f64 * 0.5).sin() * 5.0)
+        .collect::<Vec<_>>();
+
+    let strict = bollinger_bands_strict(&close_prices, Some(10), None, None, None).unwrap();
+    let Output {
+        values: sma,
+        begin: sma_begin,
+    } = simple_moving_average(&close_prices, Some(10)).unwrap();
+
+    assert_eq!(sma_begin, strict.begin);
+    assert_expected(&sma, &strict.middle);
+
+    // Without strict mode the middle band is the EMA.
+    let legacy = bollinger_bands(&close_prices, Some(10), None, None, None).unwrap();
+    let Output { values: ema, .. } = exponential_moving_average(&close_prices, Some(10)).unwrap();
+
+    assert_expected(&ema, &legacy.middle);
+    assert_ne!(strict.middle, legacy.middle);
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_bollinger_bands_symmetric() {
+    let close_prices = SAMPLE_PRICES;
+
+    let bands = bollinger_bands_symmetric(&close_prices, Some(5), Some(2.5), None).unwrap();
+
+    assert_eq!(4, bands.begin);
+    for ((upper, middle), lower) in bands.upper.iter().zip(&bands.middle).zip(&bands.lower) {
+        assert!((upper - middle - (middle - lower)).abs() < 1e-12);
+    }
+
+    let asymmetric = bollinger_bands(&close_prices, Some(5), Some(2.5), None, None).unwrap();
+    assert_eq!(bands.upper, asymmetric.upper);
+    assert_ne!(bands.lower, asymmetric.lower);
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_bollinger_bands_padded() {
+    let close_prices = SAMPLE_PRICES;
+
+    let bands = bollinger_bands(&close_prices, Some(5), None, None, None).unwrap();
+    let padded = bollinger_bands_padded(&close_prices, Some(5), None, None, None).unwrap();
+
+    assert_eq!(bands.begin, padded.begin);
+    for (values, padded) in [
+        (&bands.upper, &padded.upper),
+        (&bands.middle, &padded.middle),
+        (&bands.lower, &padded.lower),
+    ] {
+        assert_eq!(close_prices.len(), padded.len());
+        assert!(padded[..bands.begin].iter().all(|value| value.is_nan()));
+        assert_eq!(values[..], padded[bands.begin..]);
+    }
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_bollinger_bands_columns() {
+    let close_prices = SAMPLE_PRICES;
+
+    let bands = bollinger_bands(&close_prices, Some(5), None, None, None).unwrap();
+    let output = bollinger_bands_columns(&close_prices, Some(5), None, None, None).unwrap();
+
+    assert_eq!(
+        vec!["upper", "middle", "lower"],
+        output
+            .columns
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(bands.begin, output.begin);
+    assert_eq!(Some(bands.middle.as_slice()), output.column("middle"));
+}
+
+/// The slow %K and %D lines returned by [`stochastic()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stochastic {
+    pub slow_k: Vec<f64>,
+    pub slow_d: Vec<f64>,
+    /// The index of the first candle to have an associated STOCH value.
+    pub begin: usize,
+}
+
+impl From<Stochastic> for IndicatorOutput {
+    fn from(stochastic: Stochastic) -> Self {
+        Self {
+            columns: vec![
+                ("slow_k".to_string(), stochastic.slow_k),
+                ("slow_d".to_string(), stochastic.slow_d),
+            ],
+            begin: stochastic.begin,
+        }
+    }
+}
+
+/// The fast %K and %D lines returned by [`fast_stochastic()`] and
+/// [`stochastic_relative_strength_index()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FastStochastic {
+    pub fast_k: Vec<f64>,
+    pub fast_d: Vec<f64>,
+    /// The index of the first input to have an associated value.
+    pub begin: usize,
+}
+
+impl From<FastStochastic> for IndicatorOutput {
+    fn from(stochastic: FastStochastic) -> Self {
+        Self {
+            columns: vec![
+                ("fast_k".to_string(), stochastic.fast_k),
+                ("fast_d".to_string(), stochastic.fast_d),
+            ],
+            begin: stochastic.begin,
+        }
+    }
+}
+
+/// Compute the [Stochastic](https://www.tadoc.org/indicator/STOCH.htm)
+/// oscillator.
+///
+/// Returns the slow %K and %D lines and the index of the first candle to have
+/// an associated STOCH value.
+///
+/// The moving average types default to
+/// [`MovingAverageType::SimpleMovingAverage`].
+#[cfg(not(feature = "pure_rust"))]
+#[allow(clippy::too_many_arguments)]
+pub fn stochastic(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    fast_k_period: Option<usize>,
+    slow_k_period: Option<usize>,
+    slow_k_moving_average_type: Option<MovingAverageType>,
+    slow_d_period: Option<usize>,
+    slow_d_moving_average_type: Option<MovingAverageType>,
+) -> Result<Stochastic, Error> {
+    check_range(0, close.len())?;
+
+    trace_span!(
+        "stochastic",
+        close.len(),
+        fast_k_period,
+        slow_k_period,
+        slow_k_moving_average_type,
+        slow_d_period,
+        slow_d_moving_average_type
+    );
+
+    check_aligned(&[close, high, low])?;
+
+    check_input_len(close.len())?;
+
+    let fast_k_period = period_or_default(fast_k_period)?;
+    let slow_k_period = period_or_default(slow_k_period)?;
+    let slow_k_moving_average_type =
+        slow_k_moving_average_type.unwrap_or(MovingAverageType::SimpleMovingAverage) as _;
+    let slow_d_period = period_or_default(slow_d_period)?;
+    let slow_d_moving_average_type =
+        slow_d_moving_average_type.unwrap_or(MovingAverageType::SimpleMovingAverage) as _;
+
+    let capacity = check_lookback(close.len(), unsafe {
+        ta::TA_STOCH_Lookback(
+            fast_k_period,
+            slow_k_period,
+            slow_k_moving_average_type,
+            slow_d_period,
+            slow_d_moving_average_type,
+        )
+    })?;
+
+    let mut out_begin: i32 = 0;
+    let mut out_size: i32 = 0;
+    let mut out_slow_k: Vec<f64> = Vec::with_capacity(capacity);
+    let mut out_slow_d: Vec<f64> = Vec::with_capacity(capacity);
+
+    unsafe {
+        let ret_code = ta::TA_STOCH(
+            0,
+            (close.len() - 1) as _,
+            high.as_ptr(),
+            low.as_ptr(),
+            close.as_ptr(),
+            fast_k_period,
+            slow_k_period,
+            slow_k_moving_average_type,
+            slow_d_period,
+            slow_d_moving_average_type,
+            &mut out_begin,
+            &mut out_size,
+            out_slow_k.as_mut_ptr(),
+            out_slow_d.as_mut_ptr(),
+        );
+
+        match ret_code {
+            ta::TA_RetCode_TA_SUCCESS => {
+                out_slow_k.set_len(output_len(out_size, out_slow_k.capacity()));
+                out_slow_d.set_len(output_len(out_size, out_slow_d.capacity()));
+
+                Ok(Stochastic {
+                    slow_k: out_slow_k,
+                    slow_d: out_slow_d,
+                    begin: checked_begin(out_begin, close.len(), capacity),
+                })
+            }
+            _ => {
+                trace_error!(ret_code);
+
+                Err(Error::Computation(format!(
+                    "Could not compute STOCH; error: {:?}",
+                    ret_code
+                )))
+            }
+        }
+    }
+}
+
+/// Compute the [Stochastic](https://www.tadoc.org/indicator/STOCH.htm)
+/// oscillator.
+///
+/// Same as [`stochastic()`] but each line has as many values as there are
+/// candles.
+///
+/// Candles without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+#[cfg(not(feature = "pure_rust"))]
+#[allow(clippy::too_many_arguments)]
+pub fn stochastic_padded(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    fast_k_period: Option<usize>,
+    slow_k_period: Option<usize>,
+    slow_k_moving_average_type: Option<MovingAverageType>,
+    slow_d_period: Option<usize>,
+    slow_d_moving_average_type: Option<MovingAverageType>,
+) -> Result<Stochastic, Error> {
+    let stochastic = stochastic(
+        high,
+        low,
+        close,
+        fast_k_period,
+        slow_k_period,
+        slow_k_moving_average_type,
+        slow_d_period,
+        slow_d_moving_average_type,
+    )?;
+
+    Ok(Stochastic {
+        slow_k: nan_padded(stochastic.slow_k, stochastic.begin, close.len()),
+        slow_d: nan_padded(stochastic.slow_d, stochastic.begin, close.len()),
+        begin: stochastic.begin,
+    })
+}
+
+/// Compute the [Stochastic](https://www.tadoc.org/indicator/STOCH.htm)
+/// oscillator.
+///
+/// Same as [`stochastic()`] but returns the lines as the columns `"slow_k"`
+/// and `"slow_d"`.
+#[cfg(not(feature = "pure_rust"))]
+#[allow(clippy::too_many_arguments)]
+pub fn stochastic_columns(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    fast_k_period: Option<usize>,
+    slow_k_period: Option<usize>,
+    slow_k_moving_average_type: Option<MovingAverageType>,
+    slow_d_period: Option<usize>,
+    slow_d_moving_average_type: Option<MovingAverageType>,
+) -> Result<IndicatorOutput, Error> {
+    stochastic(
+        high,
+        low,
+        close,
+        fast_k_period,
+        slow_k_period,
+        slow_k_moving_average_type,
+        slow_d_period,
+        slow_d_moving_average_type,
+    )
+    .map(Into::into)
+}
+
+/// The parameters of [`stochastic_with()`].
+///
+/// The [`Default`] is TA-Lib's: a fast %K over 5 candles, smoothed into the
+/// slow %K and that into the slow %D with simple moving averages over 3.
+#[cfg(not(feature = "pure_rust"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StochasticParameters {
+    pub fast_k_period: usize,
+    pub slow_k_period: usize,
+    pub slow_k_moving_average_type: MovingAverageType,
+    pub slow_d_period: usize,
+    pub slow_d_moving_average_type: MovingAverageType,
+}
+
+#[cfg(not(feature = "pure_rust"))]
+impl Default for StochasticParameters {
+    fn default() -> Self {
+        Self {
+            fast_k_period: 5,
+            slow_k_period: 3,
+            slow_k_moving_average_type: MovingAverageType::SimpleMovingAverage,
+            slow_d_period: 3,
+            slow_d_moving_average_type: MovingAverageType::SimpleMovingAverage,
+        }
+    }
+}
+
+/// Compute the [Stochastic](https://www.tadoc.org/indicator/STOCH.htm)
+/// oscillator.
+///
+/// Same as [`stochastic()`] but takes the parameters as a struct.
+#[cfg(not(feature = "pure_rust"))]
+pub fn stochastic_with(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    parameters: StochasticParameters,
+) -> Result<Stochastic, Error> {
+    stochastic(
+        high,
+        low,
+        close,
+        Some(parameters.fast_k_period),
+        Some(parameters.slow_k_period),
+        Some(parameters.slow_k_moving_average_type),
+        Some(parameters.slow_d_period),
+        Some(parameters.slow_d_moving_average_type),
+    )
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_stochastic_with() {
+    let close = sample_wave(40);
+    let high = close.iter().map(|close| close + 1.0).collect::<Vec<_>>();
+    let low = close.iter().map(|close| close - 1.0).collect::<Vec<_>>();
+
+    let defaults = stochastic_with(&high, &low, &close, Default::default()).unwrap();
+    let expected = stochastic(&high, &low, &close, None, None, None, None, None).unwrap();
+
+    assert_eq!(expected.begin, defaults.begin);
+    assert_eq!(expected.slow_k, defaults.slow_k);
+    assert_eq!(expected.slow_d, defaults.slow_d);
+
+    let slower = stochastic_with(
+        &high,
+        &low,
+        &close,
+        StochasticParameters {
+            fast_k_period: 14,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let expected = stochastic(&high, &low, &close, Some(14), None, None, None, None).unwrap();
+
+    assert_eq!(expected.begin, slower.begin);
+    assert_eq!(expected.slow_k, slower.slow_k);
+    assert_ne!(defaults.begin, slower.begin);
+}
+
+/// Compute the [Stochastic Fast](https://www.tadoc.org/indicator/STOCHF.htm)
+/// oscillator.
+///
+/// Returns the fast %K and %D lines and the index of the first candle to have
+/// an associated STOCHF value.
+///
+/// The moving average type defaults to
+/// [`MovingAverageType::SimpleMovingAverage`].
+#[cfg(not(feature = "pure_rust"))]
+pub fn fast_stochastic(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    fast_k_period: Option<usize>,
+    fast_d_period: Option<usize>,
+    fast_d_moving_average_type: Option<MovingAverageType>,
+) -> Result<FastStochastic, Error> {
+    check_range(0, close.len())?;
+
+    trace_span!(
+        "fast_stochastic",
+        close.len(),
+        fast_k_period,
+        fast_d_period,
+        fast_d_moving_average_type
+    );
+
+    check_aligned(&[close, high, low])?;
+
+    check_input_len(close.len())?;
+
+    let fast_k_period = period_or_default(fast_k_period)?;
+    let fast_d_period = period_or_default(fast_d_period)?;
+    let fast_d_moving_average_type =
+        fast_d_moving_average_type.unwrap_or(MovingAverageType::SimpleMovingAverage) as _;
+
+    let capacity = check_lookback(close.len(), unsafe {
+        ta::TA_STOCHF_Lookback(fast_k_period, fast_d_period, fast_d_moving_average_type)
+    })?;
+
+    let mut out_begin: i32 = 0;
+    let mut out_size: i32 = 0;
+    let mut out_fast_k: Vec<f64> = Vec::with_capacity(capacity);
+    let mut out_fast_d: Vec<f64> = Vec::with_capacity(capacity);
+
+    unsafe {
+        let ret_code = ta::TA_STOCHF(
+            0,
+            (close.len() - 1) as _,
+            high.as_ptr(),
+            low.as_ptr(),
+            close.as_ptr(),
+            fast_k_period,
+            fast_d_period,
+            fast_d_moving_average_type,
+            &mut out_begin,
+            &mut out_size,
+            out_fast_k.as_mut_ptr(),
+            out_fast_d.as_mut_ptr(),
+        );
+
+        match ret_code {
+            ta::TA_RetCode_TA_SUCCESS => {
+                out_fast_k.set_len(output_len(out_size, out_fast_k.capacity()));
+                out_fast_d.set_len(output_len(out_size, out_fast_d.capacity()));
+
+                Ok(FastStochastic {
+                    fast_k: out_fast_k,
+                    fast_d: out_fast_d,
+                    begin: checked_begin(out_begin, close.len(), capacity),
+                })
+            }
+            _ => {
+                trace_error!(ret_code);
+
+                Err(Error::Computation(format!(
+                    "Could not compute STOCHF; error: {:?}",
+                    ret_code
+                )))
+            }
+        }
+    }
+}
+
+/// Compute the [Stochastic Fast](https://www.tadoc.org/indicator/STOCHF.htm)
+/// oscillator.
+///
+/// Same as [`fast_stochastic()`] but each line has as many values as there are
+/// candles.
+///
+/// Candles without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn fast_stochastic_padded(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    fast_k_period: Option<usize>,
+    fast_d_period: Option<usize>,
+    fast_d_moving_average_type: Option<MovingAverageType>,
+) -> Result<FastStochastic, Error> {
+    let stochastic = fast_stochastic(
+        high,
+        low,
+        close,
+        fast_k_period,
+        fast_d_period,
+        fast_d_moving_average_type,
+    )?;
+
+    Ok(FastStochastic {
+        fast_k: nan_padded(stochastic.fast_k, stochastic.begin, close.len()),
+        fast_d: nan_padded(stochastic.fast_d, stochastic.begin, close.len()),
+        begin: stochastic.begin,
+    })
+}
+
+/// Compute the [Stochastic Fast](https://www.tadoc.org/indicator/STOCHF.htm)
+/// oscillator.
+///
+/// Same as [`fast_stochastic()`] but returns the lines as the columns
+/// `"fast_k"` and `"fast_d"`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn fast_stochastic_columns(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    fast_k_period: Option<usize>,
+    fast_d_period: Option<usize>,
+    fast_d_moving_average_type: Option<MovingAverageType>,
+) -> Result<IndicatorOutput, Error> {
+    fast_stochastic(
+        high,
+        low,
+        close,
+        fast_k_period,
+        fast_d_period,
+        fast_d_moving_average_type,
+    )
+    .map(Into::into)
+}
+
+/// Compute the [Stochastic Relative Strength
+/// Index](https://www.tadoc.org/indicator/STOCHRSI.htm).
+///
+/// Returns the fast %K and %D lines of the stochastic of the RSI and the index
+/// of the first input to have an associated STOCHRSI value.
+///
+/// The moving average type defaults to
+/// [`MovingAverageType::SimpleMovingAverage`].
+#[cfg(not(feature = "pure_rust"))]
+pub fn stochastic_relative_strength_index(
+    input: &[f64],
+    period: Option<usize>,
+    fast_k_period: Option<usize>,
+    fast_d_period: Option<usize>,
+    fast_d_moving_average_type: Option<MovingAverageType>,
+) -> Result<FastStochastic, Error> {
+    check_range(0, input.len())?;
+
+    trace_span!(
+        "stochastic_relative_strength_index",
+        input.len(),
+        period,
+        fast_k_period,
+        fast_d_period,
+        fast_d_moving_average_type
+    );
+
+    check_input_len(input.len())?;
+
+    let period = period_or_default(period)?;
+    let fast_k_period = period_or_default(fast_k_period)?;
+    let fast_d_period = period_or_default(fast_d_period)?;
+    let fast_d_moving_average_type =
+        fast_d_moving_average_type.unwrap_or(MovingAverageType::SimpleMovingAverage) as _;
+
+    let capacity = check_lookback(input.len(), unsafe {
+        ta::TA_STOCHRSI_Lookback(
+            period,
+            fast_k_period,
+            fast_d_period,
+            fast_d_moving_average_type,
+        )
+    })?;
+
+    let mut out_begin: i32 = 0;
+    let mut out_size: i32 = 0;
+    let mut out_fast_k: Vec<f64> = Vec::with_capacity(capacity);
+    let mut out_fast_d: Vec<f64> = Vec::with_capacity(capacity);
+
+    unsafe {
+        let ret_code = ta::TA_STOCHRSI(
+            0,
+            (input.len() - 1) as _,
+            input.as_ptr(),
+            period,
+            fast_k_period,
+            fast_d_period,
+            fast_d_moving_average_type,
+            &mut out_begin,
+            &mut out_size,
+            out_fast_k.as_mut_ptr(),
+            out_fast_d.as_mut_ptr(),
+        );
+
+        match ret_code {
+            ta::TA_RetCode_TA_SUCCESS => {
+                out_fast_k.set_len(output_len(out_size, out_fast_k.capacity()));
+                out_fast_d.set_len(output_len(out_size, out_fast_d.capacity()));
+
+                Ok(FastStochastic {
+                    fast_k: out_fast_k,
+                    fast_d: out_fast_d,
+                    begin: checked_begin(out_begin, input.len(), capacity),
+                })
+            }
+            _ => {
+                trace_error!(ret_code);
+
+                Err(Error::Computation(format!(
+                    "Could not compute STOCHRSI; error: {:?}",
+                    ret_code
+                )))
+            }
+        }
+    }
+}
+
+/// Compute the [Stochastic Relative Strength
+/// Index](https://www.tadoc.org/indicator/STOCHRSI.htm).
+///
+/// Same as [`stochastic_relative_strength_index()`] but each line has as many
+/// values as there are inputs.
+///
+/// Inputs without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn stochastic_relative_strength_index_padded(
+    input: &[f64],
+    period: Option<usize>,
+    fast_k_period: Option<usize>,
+    fast_d_period: Option<usize>,
+    fast_d_moving_average_type: Option<MovingAverageType>,
+) -> Result<FastStochastic, Error> {
+    let stochastic = stochastic_relative_strength_index(
+        input,
+        period,
+        fast_k_period,
+        fast_d_period,
+        fast_d_moving_average_type,
+    )?;
+
+    Ok(FastStochastic {
+        fast_k: nan_padded(stochastic.fast_k, stochastic.begin, input.len()),
+        fast_d: nan_padded(stochastic.fast_d, stochastic.begin, input.len()),
+        begin: stochastic.begin,
+    })
+}
+
+/// Compute the [Stochastic Relative Strength
+/// Index](https://www.tadoc.org/indicator/STOCHRSI.htm).
+///
+/// Same as [`stochastic_relative_strength_index()`] but returns the lines as
+/// the columns `"fast_k"` and `"fast_d"`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn stochastic_relative_strength_index_columns(
+    input: &[f64],
+    period: Option<usize>,
+    fast_k_period: Option<usize>,
+    fast_d_period: Option<usize>,
+    fast_d_moving_average_type: Option<MovingAverageType>,
+) -> Result<IndicatorOutput, Error> {
+    stochastic_relative_strength_index(
+        input,
+        period,
+        fast_k_period,
+        fast_d_period,
+        fast_d_moving_average_type,
+    )
+    .map(Into::into)
+}
+
+/// The MACD, signal and histogram lines returned by
+/// [`moving_average_convergence_divergence()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Macd {
+    pub macd: Vec<f64>,
+    pub signal: Vec<f64>,
+    pub histogram: Vec<f64>,
+    /// The index of the first input to have an associated MACD value.
+    pub begin: usize,
+}
+
+impl From<Macd> for IndicatorOutput {
+    fn from(macd: Macd) -> Self {
+        Self {
+            columns: vec![
+                ("macd".to_string(), macd.macd),
+                ("signal".to_string(), macd.signal),
+                ("histogram".to_string(), macd.histogram),
+            ],
+            begin: macd.begin,
+        }
+    }
+}
+
+impl Macd {
+    /// Find the indices where the histogram changes sign, i.e. where the MACD
+    /// line crosses its signal, a shift in momentum.
+    ///
+    /// [`Cross::Up`] means the histogram turned positive. The indices are
+    /// those of the inputs, not of the histogram. See [`crossovers()`] for
+    /// how touching and `f64::NAN` values are handled.
+    pub fn histogram_crossings(&self) -> Vec<(usize, Cross)> {
+        signal::crossings(self.histogram.iter().map(|histogram| (*histogram, 0.0)))
+            .into_iter()
+            .map(|(index, cross)| (self.begin + index, cross))
+            .collect()
+    }
+}
+
+/// Compute [Moving Average Convergence/Divergence](https://www.tadoc.org/indicator/MACD.htm).
+///
+/// Returns the MACD, signal and histogram lines and the index of the first
+/// input to have an associated MACD value.
+#[cfg(not(feature = "pure_rust"))]
+pub fn moving_average_convergence_divergence(
+    input: &[f64],
+    fast_period: Option<usize>,
+    slow_period: Option<usize>,
+    signal_period: Option<usize>,
+) -> Result<Macd, Error> {
+    check_range(0, input.len())?;
+
+    trace_span!(
+        "moving_average_convergence_divergence",
+        input.len(),
+        fast_period,
+        slow_period,
+        signal_period
+    );
+
+    check_input_len(input.len())?;
+
+    let fast_period = period_or_default(fast_period)?;
+    let slow_period = period_or_default(slow_period)?;
+    let signal_period = period_or_default(signal_period)?;
+
+    let capacity = check_lookback(input.len(), unsafe {
+        ta::TA_MACD_Lookback(fast_period, slow_period, signal_period)
+    })?;
+
+    let mut out_begin: i32 = 0;
+    let mut out_size: i32 = 0;
+    let mut out_macd: Vec<f64> = Vec::with_capacity(capacity);
+    let mut out_signal: Vec<f64> = Vec::with_capacity(capacity);
+    let mut out_histogram: Vec<f64> = Vec::with_capacity(capacity);
+
+    unsafe {
+        let ret_code = ta::TA_MACD(
+            0,
+            (input.len() - 1) as _,
+            input.as_ptr(),
+            fast_period,
+            slow_period,
+            signal_period,
+            &mut out_begin,
+            &mut out_size,
+            out_macd.as_mut_ptr(),
+            out_signal.as_mut_ptr(),
+            out_histogram.as_mut_ptr(),
+        );
+
+        match ret_code {
+            ta::TA_RetCode_TA_SUCCESS => {
+                out_macd.set_len(output_len(out_size, out_macd.capacity()));
+                out_signal.set_len(output_len(out_size, out_signal.capacity()));
+                out_histogram.set_len(output_len(out_size, out_histogram.capacity()));
+
+                Ok(Macd {
+                    macd: out_macd,
+                    signal: out_signal,
+                    histogram: out_histogram,
+                    begin: checked_begin(out_begin, input.len(), capacity),
+                })
+            }
+            _ => {
+                trace_error!(ret_code);
+
+                Err(Error::Computation(format!(
+                    "Could not compute MACD; error: {:?}",
+                    ret_code
+                )))
+            }
+        }
+    }
+}
+
+/// Compute [Moving Average Convergence/Divergence](https://www.tadoc.org/indicator/MACD.htm).
+///
+/// Same as [`moving_average_convergence_divergence()`] but each line has as
+/// many values as there are inputs.
+///
+/// Inputs without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn moving_average_convergence_divergence_padded(
+    input: &[f64],
+    fast_period: Option<usize>,
+    slow_period: Option<usize>,
+    signal_period: Option<usize>,
+) -> Result<Macd, Error> {
+    let macd =
+        moving_average_convergence_divergence(input, fast_period, slow_period, signal_period)?;
+
+    Ok(Macd {
+        macd: nan_padded(macd.macd, macd.begin, input.len()),
+        signal: nan_padded(macd.signal, macd.begin, input.len()),
+        histogram: nan_padded(macd.histogram, macd.begin, input.len()),
+        begin: macd.begin,
+    })
+}
+
+/// Compute [Moving Average Convergence/Divergence](https://www.tadoc.org/indicator/MACD.htm).
+///
+/// Same as [`moving_average_convergence_divergence()`] but returns the lines
+/// as the columns `"macd"`, `"signal"` and `"histogram"`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn moving_average_convergence_divergence_columns(
+    input: &[f64],
+    fast_period: Option<usize>,
+    slow_period: Option<usize>,
+    signal_period: Option<usize>,
+) -> Result<IndicatorOutput, Error> {
+    moving_average_convergence_divergence(input, fast_period, slow_period, signal_period)
+        .map(Into::into)
+}
+
+/// The parameters of [`moving_average_convergence_divergence_with()`].
+///
+/// The [`Default`] is TA-Lib's: EMAs over 12 and 26 inputs and a signal EMA
+/// over 9.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MacdParameters {
+    pub fast_period: usize,
+    pub slow_period: usize,
+    pub signal_period: usize,
+}
+
+impl Default for MacdParameters {
+    fn default() -> Self {
+        Self {
+            fast_period: 12,
+            slow_period: 26,
+            signal_period: 9,
+        }
+    }
+}
+
+/// Compute [Moving Average Convergence/Divergence](https://www.tadoc.org/indicator/MACD.htm).
+///
+/// Same as [`moving_average_convergence_divergence()`] but takes the
+/// parameters as a struct.
+#[cfg(not(feature = "pure_rust"))]
+pub fn moving_average_convergence_divergence_with(
+    input: &[f64],
+    parameters: MacdParameters,
+) -> Result<Macd, Error> {
+    moving_average_convergence_divergence(
+        input,
+        Some(parameters.fast_period),
+        Some(parameters.slow_period),
+        Some(parameters.signal_period),
+    )
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_moving_average_convergence_divergence_with() {
+    let close_prices = sample_wave(60);
+
+    let defaults =
+        moving_average_convergence_divergence_with(&close_prices, Default::default()).unwrap();
+    let expected = moving_average_convergence_divergence(&close_prices, None, None, None).unwrap();
+
+    assert_eq!(expected.begin, defaults.begin);
+    assert_eq!(expected.macd, defaults.macd);
+    assert_eq!(expected.histogram, defaults.histogram);
+
+    let faster = moving_average_convergence_divergence_with(
+        &close_prices,
+        MacdParameters {
+            signal_period: 5,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let expected =
+        moving_average_convergence_divergence(&close_prices, None, None, Some(5)).unwrap();
+
+    assert_eq!(expected.begin, faster.begin);
+    assert_eq!(expected.signal, faster.signal);
+    assert_eq!(defaults.begin - 4, faster.begin);
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_macd_histogram_crossings() {
+    let close_prices = (0..120)
+        .map(|index| 100.0 + (index as f64 * std::f64::consts::TAU / 60.0).sin() * 5.0)
+        .collect::<Vec<_>>();
+
+    let macd = moving_average_convergence_divergence(&close_prices, None, None, None).unwrap();
+
+    let expected = macd
+        .histogram
+        .windows(2)
+        .enumerate()
+        .filter_map(|(index, pair)| {
+            if pair[0] < 0.0 && 0.0 < pair[1] {
+                Some((macd.begin + index + 1, Cross::Up))
+            } else if 0.0 < pair[0] && pair[1] < 0.0 {
+                Some((macd.begin + index + 1, Cross::Down))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // A sine with a period of 60 has its histogram change sign every 30 or so
+    // inputs.
+    assert!(2 <= expected.len());
+    assert_eq!(expected, macd.histogram_crossings());
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_moving_average_convergence_divergence() {
+    let close_prices = sample_wave(60);
+
+    let macd = moving_average_convergence_divergence(&close_prices, None, None, None).unwrap();
+
+    // 26 - 1 for the slow EMA and 9 - 1 for the signal.
+    assert_eq!(33, macd.begin);
+    assert_eq!(close_prices.len() - macd.begin, macd.macd.len());
+
+    for ((macd, signal), histogram) in macd.macd.iter().zip(&macd.signal).zip(&macd.histogram) {
+        assert!((macd - signal - histogram).abs() < 1e-9);
+    }
+}
+
+/// The MAMA and FAMA lines returned by [`mesa_adaptive_moving_average()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mama {
+    pub mama: Vec<f64>,
+    /// The Following Adaptive Moving Average.
+    pub fama: Vec<f64>,
+    /// The index of the first input to have an associated MAMA value.
+    pub begin: usize,
+}
+
+impl From<Mama> for IndicatorOutput {
+    fn from(mama: Mama) -> Self {
+        Self {
+            columns: vec![
+                ("mama".to_string(), mama.mama),
+                ("fama".to_string(), mama.fama),
+            ],
+            begin: mama.begin,
+        }
+    }
+}
+
+/// Compute the [MESA Adaptive Moving
+/// Average](https://www.tadoc.org/indicator/MAMA.htm).
+///
+/// Returns the MAMA and FAMA lines and the index of the first input to have an
+/// associated MAMA value.
+///
+/// `fast_limit` defaults to `0.5` and `slow_limit` to `0.05`. Returns
+/// [`Error::BadParam`] unless `0 < slow_limit <= fast_limit <= 1`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn mesa_adaptive_moving_average(
+    input: &[f64],
+    fast_limit: Option<f64>,
+    slow_limit: Option<f64>,
+) -> Result<Mama, Error> {
+    check_range(0, input.len())?;
+
+    trace_span!(
+        "mesa_adaptive_moving_average",
+        input.len(),
+        fast_limit,
+        slow_limit
+    );
+
+    check_input_len(input.len())?;
+
+    let fast_limit = fast_limit.unwrap_or(0.5);
+    let slow_limit = slow_limit.unwrap_or(0.05);
+
+    for (name, limit) in [("fast_limit", fast_limit), ("slow_limit", slow_limit)] {
+        if !(0.0 < limit && limit <= 1.0) {
+            return Err(Error::BadParam(format!(
+                "`{}` must be in (0, 1] but is {}",
+                name, limit
+            )));
+        }
+    }
+
+    if fast_limit < slow_limit {
+        return Err(Error::BadParam(format!(
+            "`slow_limit` ({}) must not exceed `fast_limit` ({})",
+            slow_limit, fast_limit
+        )));
+    }
+
+    let capacity = check_lookback(input.len(), unsafe {
+        ta::TA_MAMA_Lookback(fast_limit, slow_limit)
+    })?;
+
+    let mut out_begin: i32 = 0;
+    let mut out_size: i32 = 0;
+    let mut out_mama: Vec<f64> = Vec::with_capacity(capacity);
+    let mut out_fama: Vec<f64> = Vec::with_capacity(capacity);
+
+    unsafe {
+        let ret_code = ta::TA_MAMA(
+            0,
+            (input.len() - 1) as _,
+            input.as_ptr(),
+            fast_limit,
+            slow_limit,
+            &mut out_begin,
+            &mut out_size,
+            out_mama.as_mut_ptr(),
+            out_fama.as_mut_ptr(),
+        );
+
+        match ret_code {
+            ta::TA_RetCode_TA_SUCCESS => {
+                out_mama.set_len(output_len(out_size, out_mama.capacity()));
+                out_fama.set_len(output_len(out_size, out_fama.capacity()));
+
+                Ok(Mama {
+                    mama: out_mama,
+                    fama: out_fama,
+                    begin: checked_begin(out_begin, input.len(), capacity),
+                })
+            }
+            _ => {
+                trace_error!(ret_code);
+
+                Err(Error::Computation(format!(
+                    "Could not compute MAMA; error: {:?}",
+                    ret_code
+                )))
+            }
+        }
+    }
+}
+
+/// Compute the [MESA Adaptive Moving
+/// Average](https://www.tadoc.org/indicator/MAMA.htm).
+///
+/// Same as [`mesa_adaptive_moving_average()`] but each line has as many values
+/// as there are inputs.
+///
+/// Inputs without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn mesa_adaptive_moving_average_padded(
+    input: &[f64],
+    fast_limit: Option<f64>,
+    slow_limit: Option<f64>,
+) -> Result<Mama, Error> {
+    let mama = mesa_adaptive_moving_average(input, fast_limit, slow_limit)?;
+
+    Ok(Mama {
+        mama: nan_padded(mama.mama, mama.begin, input.len()),
+        fama: nan_padded(mama.fama, mama.begin, input.len()),
+        begin: mama.begin,
+    })
+}
+
+/// Compute the [MESA Adaptive Moving
+/// Average](https://www.tadoc.org/indicator/MAMA.htm).
+///
+/// Same as [`mesa_adaptive_moving_average()`] but returns the lines as the
+/// columns `"mama"` and `"fama"`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn mesa_adaptive_moving_average_columns(
+    input: &[f64],
+    fast_limit: Option<f64>,
+    slow_limit: Option<f64>,
+) -> Result<IndicatorOutput, Error> {
+    mesa_adaptive_moving_average(input, fast_limit, slow_limit).map(Into::into)
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_mesa_adaptive_moving_average() {
+    let close_prices = (0..100)
+        .map(|index| 100.0 + index as f64)
+        .collect::<Vec<_>>();
+
+    assert!(matches!(
+        mesa_adaptive_moving_average(&close_prices, Some(2.0), None),
+        Err(Error::BadParam(_))
+    ));
+    assert!(matches!(
+        mesa_adaptive_moving_average(&close_prices, Some(0.1), Some(0.2)),
+        Err(Error::BadParam(_))
+    ));
+
+    let mama = mesa_adaptive_moving_average(&close_prices, None, None).unwrap();
+
+    assert_eq!(close_prices.len() - mama.begin, mama.mama.len());
+
+    // In a steady uptrend FAMA follows MAMA from below.
+    for (mama, fama) in mama.mama.iter().zip(&mama.fama).skip(10) {
+        assert!(fama < mama);
+    }
+}
+
+/// Compute the [Hilbert Transform - Dominant Cycle
+/// Period](https://www.tadoc.org/indicator/HT_DCPERIOD.htm).
+///
+/// The values are the length, in inputs, of the dominant price cycle at each
+/// input, between `6` and `50`. They drive adaptive indicators, e.g.
+/// [`adaptive_rsi()`].
+///
+/// Returns a tuple containing the list of HT_DCPERIOD values and the index of
+/// the first input to have an associated HT_DCPERIOD value.
+#[cfg(not(feature = "pure_rust"))]
+pub fn hilbert_dominant_cycle_period(input: &[f64]) -> Result<(Vec<f64>, usize), Error> {
+    check_range(0, input.len())?;
+
+    trace_span!("hilbert_dominant_cycle_period", input.len());
+
+    check_input_len(input.len())?;
+
+    let capacity = check_lookback(input.len(), unsafe { ta::TA_HT_DCPERIOD_Lookback() })?;
+
+    let mut out: Vec<f64> = Vec::with_capacity(capacity);
+    let mut out_begin: i32 = 0;
+    let mut out_size: i32 = 0;
+
+    unsafe {
+        let ret_code = ta::TA_HT_DCPERIOD(
+            0,
+            (input.len() - 1) as _,
+            input.as_ptr(),
+            &mut out_begin,
+            &mut out_size,
+            out.as_mut_ptr(),
+        );
+
+        match ret_code {
+            ta::TA_RetCode_TA_SUCCESS => {
+                out.set_len(output_len(out_size, out.capacity()));
+                Ok((out, checked_begin(out_begin, input.len(), capacity)))
+            }
+            _ => {
+                trace_error!(ret_code);
+
+                Err(Error::Computation(format!(
+                    "Could not compute HT_DCPERIOD; error: {:?}",
+                    ret_code
+                )))
+            }
+        }
+    }
+}
+
+/// Compute the [Standard Deviation](https://www.tadoc.org/indicator/STDDEV.htm)
+/// over a period.
+///
+/// The values are the population standard deviation of each trailing window
+/// multiplied by `num_deviations`, which defaults to `1`.
+///
+/// Returns a tuple containing the list of STDDEV values and the index of the
+/// first input to have an associated STDDEV value.
+#[cfg(not(feature = "pure_rust"))]
+pub fn standard_deviation(
+    input: &[f64],
+    period: Option<usize>,
+    num_deviations: Option<f64>,
+) -> Result<(Vec<f64>, usize), Error> {
+    check_range(0, input.len())?;
+
+    trace_span!("standard_deviation", input.len(), period, num_deviations);
+
+    check_input_len(input.len())?;
+
+    let period = period_or_default(period)?;
+    let num_deviations = num_deviations.unwrap_or(ta::TA_REAL_DEFAULT);
+
+    let capacity = check_lookback(input.len(), unsafe {
+        ta::TA_STDDEV_Lookback(period, num_deviations)
+    })?;
+
+    let mut out: Vec<f64> = Vec::with_capacity(capacity);
+    let mut out_begin: i32 = 0;
+    let mut out_size: i32 = 0;
+
+    unsafe {
+        let ret_code = ta::TA_STDDEV(
+            0,
+            (input.len() - 1) as _,
+            input.as_ptr(),
+            period,
+            num_deviations,
+            &mut out_begin,
+            &mut out_size,
+            out.as_mut_ptr(),
+        );
+
+        match ret_code {
+            ta::TA_RetCode_TA_SUCCESS => {
+                out.set_len(output_len(out_size, out.capacity()));
+                Ok((out, checked_begin(out_begin, input.len(), capacity)))
+            }
+            _ => {
+                trace_error!(ret_code);
+
+                Err(Error::Computation(format!(
+                    "Could not compute STDDEV; error: {:?}",
+                    ret_code
+                )))
+            }
+        }
+    }
+}
+
+/// Compute the [Standard Deviation](https://www.tadoc.org/indicator/STDDEV.htm)
+/// over a period.
+///
+/// Same as [`standard_deviation()`] but returns as many values as there are
+/// inputs.
+///
+/// Inputs without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn standard_deviation_padded(
+    input: &[f64],
+    period: Option<usize>,
+    num_deviations: Option<f64>,
+) -> Result<Vec<f64>, Error> {
+    let (values, begin) = standard_deviation(input, period, num_deviations)?;
+    Ok(nan_padded(values, begin, input.len()))
+}
+
+/// Compute the [Pearson's Correlation
+/// Coefficient](https://www.tadoc.org/indicator/CORREL.htm) of `a` and `b`
+/// over a period.
+///
+/// Returns a tuple containing the list of CORREL values and the index of the
+/// first input to have an associated CORREL value.
+#[cfg(not(feature = "pure_rust"))]
+pub fn pearson_correlation(
+    a: &[f64],
+    b: &[f64],
+    period: Option<usize>,
+) -> Result<(Vec<f64>, usize), Error> {
+    check_range(0, a.len())?;
+
+    trace_span!("pearson_correlation", a.len(), period);
+
+    check_aligned(&[a, b])?;
+
+    check_input_len(a.len())?;
+
+    let period = period_or_default(period)?;
+
+    let capacity = check_lookback(a.len(), unsafe { ta::TA_CORREL_Lookback(period) })?;
+
+    let mut out: Vec<f64> = Vec::with_capacity(capacity);
+    let mut out_begin: i32 = 0;
+    let mut out_size: i32 = 0;
+
+    unsafe {
+        let ret_code = ta::TA_CORREL(
+            0,
+            (a.len() - 1) as _,
+            a.as_ptr(),
+            b.as_ptr(),
+            period,
+            &mut out_begin,
+            &mut out_size,
+            out.as_mut_ptr(),
+        );
+
+        match ret_code {
+            ta::TA_RetCode_TA_SUCCESS => {
+                out.set_len(output_len(out_size, out.capacity()));
+                Ok((out, checked_begin(out_begin, a.len(), capacity)))
+            }
+            _ => {
+                trace_error!(ret_code);
+
+                Err(Error::Computation(format!(
+                    "Could not compute CORREL; error: {:?}",
+                    ret_code
+                )))
+            }
+        }
+    }
+}
+
+/// Compute the [Pearson's Correlation
+/// Coefficient](https://www.tadoc.org/indicator/CORREL.htm) of `a` and `b`
+/// over a period.
+///
+/// Same as [`pearson_correlation()`] but returns as many values as there are
+/// inputs.
+///
+/// Inputs without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn pearson_correlation_padded(
+    a: &[f64],
+    b: &[f64],
+    period: Option<usize>,
+) -> Result<Vec<f64>, Error> {
+    let (values, begin) = pearson_correlation(a, b, period)?;
+    Ok(nan_padded(values, begin, a.len()))
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_standard_deviation() {
+    let (values, begin) = standard_deviation(&[1.0, 3.0, 1.0, 3.0], Some(2), Some(2.0)).unwrap();
+
+    assert_eq!(1, begin);
+    for value in values {
+        assert!((2.0 - value).abs() < 1e-12);
+    }
+}
+
+/// The average absolute change between consecutive values, a measure of how
+/// jagged a line is.
+#[cfg(all(test, not(feature = "pure_rust")))]
+fn mean_absolute_difference(values: &[f64]) -> f64 {
+    values
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).abs())
+        .sum::<f64>()
+        / (values.len() - 1) as f64
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_stochastic() {
+    let high = SAMPLE_HIGH;
+    let low = SAMPLE_LOW;
+    let close = SAMPLE_CLOSE;
+
+    let stochastic =
+        stochastic(&high, &low, &close, Some(5), Some(3), None, Some(3), None).unwrap();
+
+    assert_eq!(8, stochastic.begin);
+    assert_eq!(close.len() - stochastic.begin, stochastic.slow_k.len());
+    assert_eq!(stochastic.slow_k.len(), stochastic.slow_d.len());
+    assert!(
+        mean_absolute_difference(&stochastic.slow_d) < mean_absolute_difference(&stochastic.slow_k)
+    );
+
+    let fast = fast_stochastic(&high, &low, &close, Some(5), Some(3), None).unwrap();
+
+    assert_eq!(6, fast.begin);
+    assert!(mean_absolute_difference(&fast.fast_d) < mean_absolute_difference(&fast.fast_k));
+
+    let padded =
+        stochastic_padded(&high, &low, &close, Some(5), Some(3), None, Some(3), None).unwrap();
+
+    assert_eq!(close.len(), padded.slow_k.len());
+    assert_eq!(stochastic.slow_d[..], padded.slow_d[stochastic.begin..]);
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_stochastic_relative_strength_index() {
+    let close_prices = (0..60)
+        .map(|index| 100.0 + (index as f64 * 0.7).sin() * 5.0 + index as f64 * 0.1)
+        .collect::<Vec<_>>();
+
+    let stochastic =
+        stochastic_relative_strength_index(&close_prices, Some(14), Some(5), Some(3), None)
+            .unwrap();
+
+    assert_eq!(14 + 4 + 2, stochastic.begin);
+    assert_eq!(
+        close_prices.len() - stochastic.begin,
+        stochastic.fast_k.len()
+    );
+    assert!(
+        mean_absolute_difference(&stochastic.fast_d) < mean_absolute_difference(&stochastic.fast_k)
+    );
+
+    let output =
+        stochastic_relative_strength_index_columns(&close_prices, Some(14), Some(5), Some(3), None)
+            .unwrap();
+
+    assert_eq!(Some(stochastic.fast_d.as_slice()), output.column("fast_d"));
+}
+
+/// Compute [On Balance Volume](https://www.tadoc.org/indicator/OBV.htm).
+///
+/// Returns [`Error::InvalidInput`] for the first volume that is not finite, as
+/// [`validate_series()`] does, and then for the first negative one.
+///
+/// Returns a tuple containing the list of OBV values and the
+/// index of the first candle to have an associated OBV value.
+#[cfg(not(feature = "pure_rust"))]
+pub fn on_balance_volume(close: &[f64], volume: &[f64]) -> Result<(Vec<f64>, usize), Error> {
+    check_range(0, close.len())?;
+
+    trace_span!("on_balance_volume", close.len());
+
+    check_aligned(&[close, volume])?;
+
+    check_input_len(close.len())?;
+
+    validate_series(volume)?;
+
+    if let Some(index) = volume.iter().position(|volume| *volume < 0.0) {
+        return Err(Error::InvalidInput { index });
+    }
+
+    let mut out: Vec<f64> = Vec::with_capacity(close.len());
+    let mut out_begin: i32 = 0;
+    let mut out_size: i32 = 0;
+
+    unsafe {
+        let ret_code = ta::TA_OBV(
+            0,
+            (close.len() - 1) as _,
+            close.as_ptr(),
+            volume.as_ptr(),
+            &mut out_begin,
+            &mut out_size,
+            out.as_mut_ptr(),
+        );
+
+        match ret_code {
+            ta::TA_RetCode_TA_SUCCESS => {
+                out.set_len(output_len(out_size, out.capacity()));
+                Ok((out, out_begin as _))
+            }
+            _ => {
+                trace_error!(ret_code);
+
+                Err(Error::Computation(format!(
+                    "Could not compute OBV; error: {:?}",
+                    ret_code
+                )))
+            }
+        }
+    }
+}
+
+/// Compute [On Balance Volume](https://www.tadoc.org/indicator/OBV.htm).
+///
+/// Same as [`on_balance_volume()`] but returns as many values as there are
+/// candles.
+///
+/// Candles without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn on_balance_volume_padded(close: &[f64], volume: &[f64]) -> Result<Vec<f64>, Error> {
+    let (values, begin) = on_balance_volume(close, volume)?;
+    Ok(nan_padded(values, begin, close.len()))
+}
+
+#[cfg(not(feature = "pure_rust"))]
 #[test]
-fn test_obv() {
-    println!(
-        "{:?}",
-        on_balance_volume(&[1.0, 2.0, 3.0, 4.0], &[1.0, 2.0, 3.0, 4.0])
-            .unwrap()
-            .0
+fn test_on_balance_volume() {
+    // The running total starts with the first volume. It adds the volume on
+    // up closes, subtracts it on down closes and keeps it on unchanged ones.
+    assert_eq!(
+        (vec![1.0, 3.0, 6.0, 10.0], 0),
+        on_balance_volume(&[1.0, 2.0, 3.0, 4.0], &[1.0, 2.0, 3.0, 4.0]).unwrap()
+    );
+    assert_eq!(
+        (vec![1.0, -1.0, -1.0, 3.0], 0),
+        on_balance_volume(&[4.0, 3.0, 3.0, 5.0], &[1.0, 2.0, 3.0, 4.0]).unwrap()
     );
-}*/
 
-/// Compute [On Balance Volume](https://www.tadoc.org/indicator/OBV.htm).
+    assert!(matches!(
+        on_balance_volume(&[1.0, 2.0, 3.0, 4.0], &[1.0, f64::NAN, 3.0, 4.0]),
+        Err(Error::InvalidInput { index: 1 })
+    ));
+
+    assert!(matches!(
+        on_balance_volume(&[1.0, 2.0, 3.0, 4.0], &[1.0, 2.0, -3.0, 4.0]),
+        Err(Error::InvalidInput { index: 2 })
+    ));
+}
+
+/// Compute the element-wise division of `numerator` by `denominator`.
 ///
-/// Returns a tuple containing the list of OBV values and the
-/// index of the first candle to have an associated OBV value.
-pub fn on_balance_volume(close: &[f64], volume: &[f64]) -> Result<(Vec<f64>, usize), Error> {
-    assert!(!close.is_empty());
-    assert!(close.len() <= volume.len());
+/// Zero denominators yield `f64::INFINITY`, `f64::NEG_INFINITY` or `f64::NAN`.
+/// Use [`divide_checked()`] to control this.
+///
+/// Returns a tuple containing the list of quotients and the index of the first
+/// input to have an associated quotient.
+#[cfg(not(feature = "pure_rust"))]
+pub fn divide(numerator: &[f64], denominator: &[f64]) -> Result<(Vec<f64>, usize), Error> {
+    check_range(0, numerator.len())?;
 
-    let mut out: Vec<f64> = Vec::with_capacity(close.len());
-    let mut out_begin = MaybeUninit::<i32>::uninit();
-    let mut out_size = MaybeUninit::<i32>::uninit();
+    trace_span!("divide", numerator.len());
+
+    check_aligned(&[numerator, denominator])?;
+
+    check_input_len(numerator.len())?;
+
+    let mut out: Vec<f64> = Vec::with_capacity(numerator.len());
+    let mut out_begin: i32 = 0;
+    let mut out_size: i32 = 0;
 
     unsafe {
-        let ret_code = ta::TA_OBV(
+        let ret_code = ta::TA_DIV(
             0,
-            (close.len() - 1) as _,
-            close.as_ptr(),
-            volume.as_ptr(),
-            out_begin.as_mut_ptr(),
-            out_size.as_mut_ptr(),
+            (numerator.len() - 1) as _,
+            numerator.as_ptr(),
+            denominator.as_ptr(),
+            &mut out_begin,
+            &mut out_size,
             out.as_mut_ptr(),
         );
 
         match ret_code {
             ta::TA_RetCode_TA_SUCCESS => {
-                out.set_len(out_size.assume_init() as _);
-                Ok((out, out_begin.assume_init() as _))
+                out.set_len(output_len(out_size, out.capacity()));
+                Ok((out, out_begin as _))
+            }
+            _ => {
+                trace_error!(ret_code);
+
+                Err(Error::Computation(format!(
+                    "Could not compute DIV; error: {:?}",
+                    ret_code
+                )))
             }
-            _ => Err(Error(format!(
-                "Could not compute OBV; error: {:?}",
-                ret_code
-            ))),
         }
     }
 }
 
+/// Compute the element-wise division of `numerator` by `denominator`.
+///
+/// Same as [`divide()`] but returns as many values as there are inputs.
+///
+/// Inputs without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn divide_padded(numerator: &[f64], denominator: &[f64]) -> Result<Vec<f64>, Error> {
+    let (values, begin) = divide(numerator, denominator)?;
+    Ok(nan_padded(values, begin, numerator.len()))
+}
+
+/// What [`divide_checked()`] does for a zero denominator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OnZero {
+    /// The quotient is `f64::NAN`.
+    Nan,
+    /// Fail with [`Error::DivisionByZero`].
+    Error,
+    /// The quotient is the given value.
+    Value(f64),
+}
+
+/// Compute the element-wise division of `numerator` by `denominator`.
+///
+/// Same as [`divide()`] but quotients with a zero denominator are handled as
+/// `on_zero` says.
+#[cfg(not(feature = "pure_rust"))]
+pub fn divide_checked(
+    numerator: &[f64],
+    denominator: &[f64],
+    on_zero: OnZero,
+) -> Result<(Vec<f64>, usize), Error> {
+    let (mut values, begin) = divide(numerator, denominator)?;
+
+    for (index, (value, denominator)) in values.iter_mut().zip(&denominator[begin..]).enumerate() {
+        if 0.0 == *denominator {
+            *value = match on_zero {
+                OnZero::Nan => f64::NAN,
+                OnZero::Error => {
+                    return Err(Error::DivisionByZero {
+                        index: begin + index,
+                    })
+                }
+                OnZero::Value(zero_value) => zero_value,
+            };
+        }
+    }
+
+    Ok((values, begin))
+}
+
+#[cfg(not(feature = "pure_rust"))]
 #[test]
-fn test_on_balance_volume() {
-    println!(
-        "{:?}",
-        on_balance_volume(&[1.0, 2.0, 3.0, 4.0], &[1.0, 2.0, 3.0, 4.0]).unwrap()
+fn test_divide_checked() {
+    let numerator = [1.0, 2.0, 3.0, 4.0];
+    let denominator = [2.0, 0.0, 1.0, 0.0];
+
+    let (values, begin) = divide(&numerator, &denominator).unwrap();
+    assert_eq!(0, begin);
+    assert_eq!(0.5, values[0]);
+    assert!(values[1].is_infinite());
+
+    let (values, _) = divide_checked(&numerator, &denominator, OnZero::Nan).unwrap();
+    assert_eq!(0.5, values[0]);
+    assert!(values[1].is_nan());
+    assert_eq!(3.0, values[2]);
+    assert!(values[3].is_nan());
+
+    let (values, _) = divide_checked(&numerator, &denominator, OnZero::Value(0.0)).unwrap();
+    assert_eq!(vec![0.5, 0.0, 3.0, 0.0], values);
+
+    assert!(matches!(
+        divide_checked(&numerator, &denominator, OnZero::Error),
+        Err(Error::DivisionByZero { index: 1 })
+    ));
+    assert_eq!(
+        vec![0.5, 1.0, 3.0],
+        divide_checked(&numerator[..3], &[2.0, 2.0, 1.0], OnZero::Error)
+            .unwrap()
+            .0
     );
 }
 
+/// Compute the element-wise product of `a` and `b`.
+///
+/// Returns a tuple containing the list of products and the index of the first
+/// input to have an associated product.
+#[cfg(not(feature = "pure_rust"))]
+pub fn multiply(a: &[f64], b: &[f64]) -> Result<(Vec<f64>, usize), Error> {
+    check_range(0, a.len())?;
+
+    trace_span!("multiply", a.len());
+
+    check_aligned(&[a, b])?;
+
+    check_input_len(a.len())?;
+
+    let mut out: Vec<f64> = Vec::with_capacity(a.len());
+    let mut out_begin: i32 = 0;
+    let mut out_size: i32 = 0;
+
+    unsafe {
+        let ret_code = ta::TA_MULT(
+            0,
+            (a.len() - 1) as _,
+            a.as_ptr(),
+            b.as_ptr(),
+            &mut out_begin,
+            &mut out_size,
+            out.as_mut_ptr(),
+        );
+
+        match ret_code {
+            ta::TA_RetCode_TA_SUCCESS => {
+                out.set_len(output_len(out_size, out.capacity()));
+                Ok((out, out_begin as _))
+            }
+            _ => {
+                trace_error!(ret_code);
+
+                Err(Error::Computation(format!(
+                    "Could not compute MULT; error: {:?}",
+                    ret_code
+                )))
+            }
+        }
+    }
+}
+
+/// Compute the element-wise product of `a` and `b`.
+///
+/// Same as [`multiply()`] but returns as many values as there are inputs.
+///
+/// Inputs without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn multiply_padded(a: &[f64], b: &[f64]) -> Result<Vec<f64>, Error> {
+    let (values, begin) = multiply(a, b)?;
+    Ok(nan_padded(values, begin, a.len()))
+}
+
 #[test]
 fn test_sma() {
-    let close_prices = [
-        1.087010, 1.087120, 1.087080, 1.087170, 1.087110, 1.087010, 1.087100, 1.087120, 1.087110,
-        1.087080, 1.087000, 1.086630, 1.086630, 1.086610, 1.086630, 1.086640, 1.086650, 1.086650,
-        1.086670, 1.086630,
-    ];
+    let close_prices = SAMPLE_PRICES;
 
-    // compute sma, since we use a period of 10, the first 10 closes won't have
+    // compute sma, since we use a period of 10, the first 9 closes won't have
     // an sma value because there is not enough data, so begin will be set to
-    // the index 29
-    let (sma_values, begin) = simple_moving_average(&close_prices, Some(10)).unwrap();
+    // the index 9
+    let Output {
+        values: sma_values,
+        begin,
+    } = simple_moving_average(close_prices, Some(10)).unwrap();
+
+    assert_eq!(9, begin);
+    assert_eq!(SAMPLE_SMA.len(), sma_values.len());
+
+    for (index, value) in indexed(&sma_values, begin) {
+        assert!((SAMPLE_SMA[index - begin] - value).abs() < 1e-9);
+
+        let mean = close_prices[index + 1 - 10..=index].iter().sum::<f64>() / 10.0;
+        assert!((mean - value).abs() < 1e-9);
+    }
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn test_tracing_span() {
+    use std::sync::{Arc, Mutex};
+    use tracing::{span, Event, Metadata, Subscriber};
+
+    /// Records the names of all spans created.
+    struct SpanRecorder(Arc<Mutex<Vec<String>>>);
+
+    impl Subscriber for SpanRecorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+            let mut names = self.0.lock().unwrap();
+            names.push(span.metadata().name().to_string());
+            span::Id::from_u64(names.len() as _)
+        }
+
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, _: &span::Id) {}
+
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    let names = Arc::new(Mutex::new(Vec::new()));
+
+    tracing::subscriber::with_default(SpanRecorder(names.clone()), || {
+        simple_moving_average([1.0, 2.0, 3.0], Some(2)).unwrap();
+    });
+
+    assert_eq!(vec!["simple_moving_average"], *names.lock().unwrap());
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_insufficient_data() {
+    assert!(matches!(
+        simple_moving_average([1.0, 2.0], Some(10)),
+        Err(Error::InsufficientData { have: 2, need: 10 })
+    ));
+
+    assert!(matches!(
+        average_true_range([1.0, 2.0], [1.0, 2.0], [1.0, 2.0], Some(2)),
+        Err(Error::InsufficientData { have: 2, need: 3 })
+    ));
+
+    assert!(matches!(
+        bollinger_bands(&[1.0, 2.0], Some(10), None, None, None),
+        Err(Error::InsufficientData { have: 2, need: 10 })
+    ));
+
+    assert!(matches!(
+        true_range([1.0], [1.0], [1.0]),
+        Err(Error::InsufficientData { have: 1, need: 2 })
+    ));
+
+    // Just enough data for a single value.
+    assert_eq!(
+        1,
+        simple_moving_average([1.0, 2.0], Some(2))
+            .unwrap()
+            .values
+            .len()
+    );
+}
+
+#[test]
+fn test_input_too_large() {
+    // Allocating an input this long is not an option in a test.
+    assert!(matches!(
+        check_input_len(i32::MAX as usize + 1),
+        Err(Error::InputTooLarge { .. })
+    ));
+    assert!(check_input_len(i32::MAX as usize).is_ok());
+
+    // A period that would truncate to 2 as an `i32` must not compute an SMA(2).
+    #[cfg(target_pointer_width = "64")]
+    assert!(simple_moving_average([1.0, 2.0, 3.0], Some((1 << 32) + 2)).is_err());
+}
+
+#[test]
+fn test_period_out_of_range() {
+    let input = [1.0, 2.0, 3.0];
+
+    assert!(matches!(
+        simple_moving_average(input, Some(0)),
+        Err(Error::BadParam(_))
+    ));
+    assert!(matches!(
+        simple_moving_average(input, Some(usize::MAX)),
+        Err(Error::BadParam(_))
+    ));
+    assert_eq!(None, simple_moving_average_lookback(Some(usize::MAX)));
+
+    // The functions implemented in Rust check the same way.
+    for period in [0, usize::MAX] {
+        assert!(matches!(
+            wilders_smoothing(&input, period),
+            Err(Error::BadParam(_))
+        ));
+        assert!(matches!(
+            rolling_apply(&input, period, |_| 0.0),
+            Err(Error::BadParam(_))
+        ));
+        assert!(matches!(
+            rolling_percent_rank(&input, period),
+            Err(Error::BadParam(_))
+        ));
+        assert!(matches!(
+            rolling_quantile(&input, period, 0.5),
+            Err(Error::BadParam(_))
+        ));
+        assert!(matches!(
+            rolling_skewness(&input, period),
+            Err(Error::BadParam(_))
+        ));
+        assert!(matches!(
+            rolling_kurtosis(&input, period),
+            Err(Error::BadParam(_))
+        ));
+        assert!(matches!(SmaState::new(period), Err(Error::BadParam(_))));
+        assert!(matches!(EmaState::new(period), Err(Error::BadParam(_))));
+        assert!(matches!(RsiState::new(period), Err(Error::BadParam(_))));
+    }
+
+    // RSI needs at least two changes.
+    assert!(matches!(RsiState::new(1), Err(Error::BadParam(_))));
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_period_equal_to_input_length() {
+    let high = [1.3, 1.4, 1.5, 1.4, 1.6];
+    let low = [1.1, 1.2, 1.3, 1.2, 1.4];
+    let close = [1.2, 1.3, 1.4, 1.3, 1.5];
+
+    // The SMA over all inputs is a single value belonging to the last input.
+    let Output { values, begin } = simple_moving_average(close, Some(close.len())).unwrap();
+
+    assert_eq!(close.len() - 1, begin);
+    assert_eq!(vec![(1.2 + 1.3 + 1.4 + 1.3 + 1.5) / 5.0], values);
+
+    // The ATR needs a previous close for its first true range so there is no
+    // value to compute. This is an error, never an empty `Ok`.
+    assert!(matches!(
+        average_true_range(high, low, close, Some(close.len())),
+        Err(Error::InsufficientData { have: 5, need: 6 })
+    ));
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_expected_begin() {
+    let high = SAMPLE_HIGH;
+    let low = SAMPLE_LOW;
+    let close = SAMPLE_CLOSE;
+
+    for period in [2, 5, 7, 10] {
+        assert_eq!(
+            expected_begin_sma(period).unwrap(),
+            simple_moving_average(close, Some(period)).unwrap().begin
+        );
+        assert_eq!(
+            expected_begin_ema(period).unwrap(),
+            exponential_moving_average(close, Some(period))
+                .unwrap()
+                .begin
+        );
+        assert_eq!(
+            expected_begin_atr(period).unwrap(),
+            average_true_range(high, low, close, Some(period))
+                .unwrap()
+                .begin
+        );
+        assert_eq!(
+            expected_begin_atr(period).unwrap(),
+            normalized_average_true_range(high, low, close, Some(period))
+                .unwrap()
+                .begin
+        );
+        assert_eq!(
+            expected_begin_directional_indicator(period).unwrap(),
+            positive_directional_indicator(high, low, close, Some(period))
+                .unwrap()
+                .begin
+        );
+        assert_eq!(
+            expected_begin_directional_indicator(period).unwrap(),
+            negative_directional_indicator(high, low, close, Some(period))
+                .unwrap()
+                .begin
+        );
+        assert_eq!(
+            expected_begin_adx(period).unwrap(),
+            average_directional_movement_index(high, low, close, Some(period))
+                .unwrap()
+                .begin
+        );
+    }
 
-    // print values
-    for (index, value) in sma_values.iter().enumerate() {
-        println!("Close index {} = {}", begin + index + 1, value);
+    assert_eq!(
+        expected_begin_true_range(),
+        true_range(high, low, close).unwrap().begin
+    );
+    for expected_begin in [
+        expected_begin_sma,
+        expected_begin_ema,
+        expected_begin_atr,
+        expected_begin_directional_indicator,
+        expected_begin_adx,
+    ] {
+        assert!(matches!(expected_begin(0), Err(Error::BadParam(_))));
+        assert!(matches!(
+            expected_begin(usize::MAX),
+            Err(Error::BadParam(_))
+        ));
+    }
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_directional_movement_lookback() {
+    let high = SAMPLE_HIGH;
+    let low = SAMPLE_LOW;
+    let close = SAMPLE_CLOSE;
+
+    for period in [2, 5, 7] {
+        // DX and the DIs need `period` changes, i.e. `period + 1` candles.
+        assert_eq!(
+            Some(period),
+            directional_movement_index_lookback(Some(period))
+        );
+        assert_eq!(
+            Some(period),
+            positive_directional_indicator_lookback(Some(period))
+        );
+        assert_eq!(
+            Some(period),
+            negative_directional_indicator_lookback(Some(period))
+        );
+        // ADX smoothes DX over another `period`.
+        assert_eq!(
+            Some(2 * period - 1),
+            average_directional_movement_index_lookback(Some(period))
+        );
+        // ADXR averages the ADX with the one from `period - 1` candles before.
+        assert_eq!(
+            Some(3 * period - 2),
+            average_directional_movement_index_rating_lookback(Some(period))
+        );
+
+        for (lookback, result) in [
+            (
+                directional_movement_index_lookback(Some(period)),
+                directional_movement_index(high, low, close, Some(period)),
+            ),
+            (
+                positive_directional_indicator_lookback(Some(period)),
+                positive_directional_indicator(high, low, close, Some(period)),
+            ),
+            (
+                negative_directional_indicator_lookback(Some(period)),
+                negative_directional_indicator(high, low, close, Some(period)),
+            ),
+            (
+                average_directional_movement_index_lookback(Some(period)),
+                average_directional_movement_index(high, low, close, Some(period)),
+            ),
+            (
+                average_directional_movement_index_rating_lookback(Some(period)),
+                average_directional_movement_index_rating(high, low, close, Some(period)),
+            ),
+        ] {
+            let Output { values, begin } = result.unwrap();
+
+            assert_eq!(lookback, Some(begin));
+            assert_eq!(close.len() - begin, values.len());
+        }
+    }
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_true_range_reference() {
+    let high = SAMPLE_HIGH;
+    let low = SAMPLE_LOW;
+    let close = SAMPLE_CLOSE;
+
+    let Output { values, begin } = true_range(high, low, close).unwrap();
+
+    assert_eq!(1, begin);
+    assert_eq!(close.len() - 1, values.len());
+
+    for (index, value) in indexed(&values, begin) {
+        let previous_close = close[index - 1];
+        let reference = (high[index] - low[index])
+            .max((high[index] - previous_close).abs())
+            .max((low[index] - previous_close).abs());
+
+        assert!(
+            (reference - value).abs() < 1e-12,
+            "index {}: {} != {}",
+            index,
+            reference,
+            value
+        );
+    }
+}
+
+#[test]
+fn test_append_incremental() {
+    let close_prices = SAMPLE_PRICES;
+
+    let Output {
+        values: mut stitched,
+        begin,
+    } = simple_moving_average(&close_prices[0..15], Some(3)).unwrap();
+    let Output {
+        values: new_values,
+        begin: new_begin,
+    } = simple_moving_average(&close_prices[10..20], Some(3)).unwrap();
+
+    // The windows share the bars 10..15.
+    append_incremental(&mut stitched, new_values, 5 - new_begin);
+
+    let Output {
+        values: full,
+        begin: full_begin,
+    } = simple_moving_average(close_prices, Some(3)).unwrap();
+
+    assert_eq!(full_begin, begin);
+    assert_eq!(full, stitched);
+}
+
+#[test]
+fn test_indexed() {
+    let close_prices = SAMPLE_PRICES;
+
+    let Output {
+        values: sma_values,
+        begin,
+    } = simple_moving_average(close_prices, Some(10)).unwrap();
+    let pairs = indexed(&sma_values, begin).collect::<Vec<_>>();
+
+    assert_eq!(begin, pairs[0].0);
+    assert_eq!(close_prices.len() - 1, pairs.last().unwrap().0);
+    assert_eq!(sma_values.len(), pairs.len());
+}
+
+#[cfg(feature = "f32")]
+#[test]
+fn test_simple_moving_average_f32() {
+    let close_prices = SAMPLE_PRICES;
+
+    let Output { values, begin } = simple_moving_average(close_prices, Some(10)).unwrap();
+    let (values_f32, begin_f32) = simple_moving_average_f32(close_prices, Some(10)).unwrap();
+
+    assert_eq!(begin, begin_f32);
+    assert_eq!(
+        values.iter().map(|value| *value as f32).collect::<Vec<_>>(),
+        values_f32
+    );
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_invalid_range() {
+    assert!(matches!(
+        simple_moving_average(Vec::new(), Some(3)),
+        Err(Error::InvalidRange { start: 0, end: 0 })
+    ));
+    assert!(matches!(
+        average_true_range(Vec::new(), Vec::new(), Vec::new(), Some(3)),
+        Err(Error::InvalidRange { start: 0, end: 0 })
+    ));
+    assert!(matches!(
+        divide(&[], &[]),
+        Err(Error::InvalidRange { start: 0, end: 0 })
+    ));
+    assert!(matches!(
+        check_range(5, 3),
+        Err(Error::InvalidRange { start: 5, end: 3 })
+    ));
+    assert!(check_range(3, 5).is_ok());
+}
+
+#[test]
+fn test_as_ref_input() {
+    let input = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+    let expected = simple_moving_average(&input[..], Some(5)).unwrap();
+
+    assert_eq!(expected, simple_moving_average(&input, Some(5)).unwrap());
+    assert_eq!(
+        expected,
+        simple_moving_average([1.0, 2.0, 3.0, 4.0, 5.0, 6.0], Some(5)).unwrap()
+    );
+    assert_eq!(expected, simple_moving_average(input, Some(5)).unwrap());
+}
+
+#[test]
+fn test_simple_moving_average_iter() {
+    let input = (0..20).map(|x| x as f64).collect::<Vec<_>>();
+
+    assert_eq!(
+        simple_moving_average(&input, Some(5)).unwrap(),
+        simple_moving_average_iter((0..20).map(|x| x as f64), Some(5)).unwrap()
+    );
+}
+
+#[test]
+fn test_simple_moving_average_multi() {
+    let input = (0..100).map(|x| x as f64).collect::<Vec<_>>();
+
+    let smas = simple_moving_average_multi(&input, &[5, 10, 20, 50]).unwrap();
+
+    assert_eq!(
+        vec![5, 10, 20, 50],
+        smas.iter()
+            .map(|(period, _, _)| *period)
+            .collect::<Vec<_>>()
+    );
+    for pair in smas.windows(2) {
+        assert!(pair[0].2 < pair[1].2);
+    }
+    for (period, values, begin) in smas {
+        assert_eq!(
+            simple_moving_average(&input, Some(period)).unwrap(),
+            Output { values, begin }
+        );
+    }
+
+    assert!(matches!(
+        simple_moving_average_multi(&input[..30], &[5, 50]),
+        Err(Error::InsufficientData { have: 30, need: 50 })
+    ));
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_output_len() {
+    let input = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+
+    // Every wrapper passes its output through `output_len()`.
+    let Output { values: sma, begin } = simple_moving_average(&input, Some(2)).unwrap();
+    assert_eq!(input.len() - begin, sma.len());
+
+    let bands = bollinger_bands(&input, Some(2), None, None, None).unwrap();
+    assert_eq!(input.len() - bands.begin, bands.upper.len());
+
+    assert_eq!(5, output_len(5, 5));
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+#[should_panic(expected = "exceeds output buffer capacity")]
+fn test_output_len_overflow() {
+    output_len(6, 5);
+}
+
+#[test]
+fn test_in_time_order() {
+    let ascending = (0..20)
+        .map(|index| 100.0 + (index as f64 * 0.7).sin())
+        .collect::<Vec<_>>();
+    let descending = ascending.iter().rev().copied().collect::<Vec<_>>();
+
+    let Output { values: sma, begin } = simple_moving_average(&ascending, Some(5)).unwrap();
+
+    assert_eq!(
+        (sma.clone(), begin),
+        in_time_order(&ascending, TimeOrder::Ascending, |input| {
+            simple_moving_average(input, Some(5))
+        })
+        .unwrap()
+    );
+
+    let (descending_sma, descending_begin) =
+        in_time_order(&descending, TimeOrder::Descending, |input| {
+            simple_moving_average(input, Some(5))
+        })
+        .unwrap();
+
+    assert_eq!(begin, descending_begin);
+    assert_eq!(
+        sma.iter().rev().copied().collect::<Vec<_>>(),
+        descending_sma
+    );
+    // The newest value is that of the newest window.
+    assert!((descending[..5].iter().sum::<f64>() / 5.0 - descending_sma[0]).abs() < 1e-9);
+}
+
+#[test]
+fn test_align_many() {
+    let close = (0..20)
+        .map(|index| 100.0 + (index as f64 * 0.3).cos())
+        .collect::<Vec<_>>();
+
+    let Output {
+        values: sma_5,
+        begin: begin_5,
+    } = simple_moving_average(&close, Some(5)).unwrap();
+    let Output {
+        values: sma_10,
+        begin: begin_10,
+    } = simple_moving_average(&close, Some(10)).unwrap();
+
+    let rows = align_many(&[(&sma_5, begin_5), (&sma_10, begin_10)], close.len());
+
+    assert_eq!(close.len(), rows.len());
+
+    for (row, (index, columns)) in rows.iter().enumerate() {
+        assert_eq!(row, *index);
+        assert_eq!(*index < begin_5, columns[0].is_none());
+        assert_eq!(*index < begin_10, columns[1].is_none());
+    }
+
+    assert_eq!(Some(sma_5[0]), rows[begin_5].1[0]);
+    assert_eq!(Some(sma_10[sma_10.len() - 1]), rows[close.len() - 1].1[1]);
+}
+
+#[test]
+fn test_common_range() {
+    let close = (0..20)
+        .map(|index| 100.0 + (index as f64 * 0.3).cos())
+        .collect::<Vec<_>>();
+
+    let Output {
+        values: sma_5,
+        begin: begin_5,
+    } = simple_moving_average(&close, Some(5)).unwrap();
+    let Output {
+        values: sma_10,
+        begin: begin_10,
+    } = simple_moving_average(&close, Some(10)).unwrap();
+
+    let (begin, a, b) = common_range((&sma_5, begin_5), (&sma_10, begin_10));
+
+    assert_eq!(begin_10, begin);
+    assert_eq!(a.len(), b.len());
+    assert_eq!(close.len() - begin, a.len());
+    // Both start at the same input element.
+    assert_eq!(sma_5[begin - begin_5], a[0]);
+    assert_eq!(sma_10[0], b[0]);
+    assert_eq!(sma_5.last(), a.last());
+
+    // The order does not matter.
+    assert_eq!(
+        (begin, b, a),
+        common_range((&sma_10, begin_10), (&sma_5, begin_5))
+    );
+
+    // Results that do not overlap.
+    let (begin, a, b) = common_range((&sma_5[..3], begin_5), (&sma_10, begin_10));
+    assert_eq!(begin_10, begin);
+    assert!(a.is_empty() && b.is_empty());
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_moving_average_type_try_from() {
+    assert_eq!(
+        MovingAverageType::ExponentialMovingAverage,
+        MovingAverageType::try_from(1).unwrap()
+    );
+    assert!(matches!(
+        MovingAverageType::try_from(99),
+        Err(Error::BadParam(_))
+    ));
+    assert!(MovingAverageType::try_from(-1).is_err());
+
+    for moving_average_type in MovingAverageType::ALL {
+        assert_eq!(
+            moving_average_type,
+            MovingAverageType::try_from(i32::from(moving_average_type)).unwrap()
+        );
+    }
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_moving_average_type_derives() {
+    let moving_average_types = MovingAverageType::ALL
+        .into_iter()
+        .chain(MovingAverageType::ALL)
+        .collect::<std::collections::HashSet<_>>();
+
+    assert_eq!(MovingAverageType::ALL.len(), moving_average_types.len());
+    assert!(moving_average_types.contains(&MovingAverageType::SimpleMovingAverage));
+    assert_eq!(
+        "ExponentialMovingAverage",
+        format!("{:?}", MovingAverageType::ExponentialMovingAverage)
+    );
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_checked_begin() {
+    let close = sample_wave(100);
+
+    let mama = mesa_adaptive_moving_average(&close, None, None).unwrap();
+    assert_eq!(32, mama.begin);
+    assert_eq!(close.len() - 32, mama.mama.len());
+
+    for (name, begin) in [
+        ("HT_DCPERIOD", 32),
+        ("HT_DCPHASE", 63),
+        ("HT_PHASOR", 32),
+        ("HT_SINE", 63),
+        ("HT_TRENDLINE", 63),
+        ("HT_TRENDMODE", 63),
+    ] {
+        let output = compute_by_name(name, &[&close], &[]).unwrap();
+        assert_eq!(begin, output.begin, "{}", name);
+        assert_eq!(close.len() - begin, output.columns[0].1.len(), "{}", name);
     }
+
+    // The unstable period comes on top of what the formula needs. Nothing
+    // else in the test suite computes HT_TRENDMODE.
+    let output = with_unstable_period(
+        UnstablePeriodFunction::HilbertTransformTrendVsCycleMode,
+        10,
+        || compute_by_name("HT_TRENDMODE", &[&close], &[]),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(63 + 10, output.begin);
+    assert_eq!(close.len() - 73, output.columns[0].1.len());
+
+    assert_eq!(3, checked_begin(3, 10, 7));
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Begin differs from the lookback.")]
+fn test_checked_begin_mismatch() {
+    checked_begin(4, 10, 7);
+}
+
+#[test]
+fn test_to_index_map() {
+    let close_prices = SAMPLE_PRICES;
+
+    let Output {
+        values: sma_values,
+        begin,
+    } = simple_moving_average(close_prices, Some(10)).unwrap();
+    let map = to_index_map(sma_values.clone(), begin);
+
+    assert_eq!(Some(&begin), map.keys().next());
+    assert_eq!(sma_values.len(), map.len());
+    assert_eq!(Some(&sma_values[0]), map.get(&begin));
 }
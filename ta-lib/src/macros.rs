@@ -1,19 +1,60 @@
+/// Enter a `tracing` span named after the wrapper for the rest of the
+/// enclosing block and, with the `panic_context` feature, record the wrapper
+/// for [`with_panic_context()`](crate::with_panic_context). Expands to nothing
+/// without either feature.
+macro_rules! trace_span {
+    ($name:expr, $len:expr $(, $parameter:ident)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($name, len = $len $(, $parameter = ?$parameter)*).entered();
+        #[cfg(feature = "panic_context")]
+        let _parameters = [$((stringify!($parameter), &$parameter as &dyn std::fmt::Debug)),*];
+        #[cfg(feature = "panic_context")]
+        let _context = crate::context::PanicContext::enter($name, $len, &_parameters);
+    };
+}
+
+/// Log the `RetCode` of a failed C call. Expands to nothing without the
+/// `tracing` feature.
+#[cfg(not(feature = "pure_rust"))]
+macro_rules! trace_error {
+    ($ret_code:expr) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(ret_code = $ret_code, "TA-Lib call failed");
+    };
+}
+
 macro_rules! define_high_low_close_period_fn {
-    ($(#[$attr:meta])* => $fn_name:ident, $ta_fn_name:ident) => {
+    ($(#[$attr:meta])* => $fn_name:ident, $ta_fn_name:ident $(, expected: $expected:expr)?) => {
         $(#[$attr])*
+        #[cfg(not(feature = "pure_rust"))]
         pub fn $fn_name(
-            high: &[f64],
-            low: &[f64],
-            close: &[f64],
+            high: impl AsRef<[f64]>,
+            low: impl AsRef<[f64]>,
+            close: impl AsRef<[f64]>,
             period: Option<usize>,
-        ) -> Result<(Vec<f64>, usize), Error> {
-            assert!(!close.is_empty());
-            assert!(close.len() <= high.len());
-            assert!(close.len() <= low.len());
+        ) -> Result<Output, Error> {
+            let (high, low, close) = (high.as_ref(), low.as_ref(), close.as_ref());
+
+            check_range(0, close.len())?;
+
+            trace_span!(stringify!($fn_name), close.len(), period);
+
+            check_aligned(&[close, high, low])?;
+
+            check_input_len(close.len())?;
+
+            let period = period_or_default(period)?;
 
-            let mut out: Vec<f64> = Vec::with_capacity(close.len());
-            let mut out_begin = MaybeUninit::<i32>::uninit();
-            let mut out_size = MaybeUninit::<i32>::uninit();
+            let capacity = check_lookback(
+                close.len(),
+                concat_idents!(lookback_fn_name = $ta_fn_name, _Lookback {
+                    unsafe { ta::lookback_fn_name(period) }
+                }),
+            )?;
+
+            let mut out: Vec<f64> = Vec::with_capacity(capacity);
+            let mut out_begin: i32 = 0;
+            let mut out_size: i32 = 0;
 
             unsafe {
                 let ret_code = ta::$ta_fn_name(
@@ -22,55 +63,140 @@ macro_rules! define_high_low_close_period_fn {
                     high.as_ptr(),
                     low.as_ptr(),
                     close.as_ptr(),
-                    if let Some(period) = period {
-                        period as _
-                    } else {
-                        // ta::TA_INTEGER_DEFAULT
-                        i32::MIN
-                    },
-                    out_begin.as_mut_ptr(),
-                    out_size.as_mut_ptr(),
+                    period,
+                    &mut out_begin,
+                    &mut out_size,
                     out.as_mut_ptr(),
                 );
 
                 match ret_code {
                     ta::TA_RetCode_TA_SUCCESS => {
-                        out.set_len(out_size.assume_init() as _);
-                        Ok((out, out_begin.assume_init() as _))
+                        out.set_len(output_len(out_size, out.capacity()));
+                        Ok(Output {
+                            values: out,
+                            begin: checked_begin(out_begin, close.len(), capacity),
+                        })
+                    }
+                    _ => {
+                        trace_error!(ret_code);
+
+                        Err(Error::Computation(format!(
+                            "Could not compute function; error: {:?}",
+                            ret_code
+                        )))
                     }
-                    _ => Err(Error(format!(
-                        "Could not compute function; error: {:?}",
-                        ret_code
-                    ))),
                 }
             }
         }
 
+        #[cfg(not(feature = "pure_rust"))]
+        concat_idents!(tuple_fn_name = $fn_name, _tuple {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but returns a tuple containing the ",
+                "values and `begin`."
+            )]
+            #[deprecated(
+                since = "0.1.2",
+                note = "use the function without the `_tuple` suffix which returns an `Output`"
+            )]
+            pub fn tuple_fn_name(
+                high: impl AsRef<[f64]>,
+                low: impl AsRef<[f64]>,
+                close: impl AsRef<[f64]>,
+                period: Option<usize>,
+            ) -> Result<(Vec<f64>, usize), Error> {
+                $fn_name(high, low, close, period).map(Into::into)
+            }
+        });
+
+        #[cfg(not(feature = "pure_rust"))]
+        concat_idents!(lookback_fn_name = $fn_name, _lookback {
+            #[doc = concat!(
+                "The number of inputs [`", stringify!($fn_name), "()`] consumes before it outputs ",
+                "its first value, i.e. its `begin`.\n\nReturns `None` if `period` is out of range."
+            )]
+            pub fn lookback_fn_name(period: Option<usize>) -> Option<usize> {
+                let lookback = concat_idents!(ta_lookback_fn_name = $ta_fn_name, _Lookback {
+                    unsafe { ta::ta_lookback_fn_name(period_or_default(period).ok()?) }
+                });
+
+                usize::try_from(lookback).ok()
+            }
+        });
+
+        #[cfg(feature = "f32")]
+        #[cfg(not(feature = "pure_rust"))]
+        concat_idents!(f32_fn_name = $fn_name, _f32 {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but returns the values as `f32`.\n\n",
+                "The computation is done in `f64`, only the results are rounded to the nearest ",
+                "`f32`. This halves their memory but leaves only about 7 significant decimal ",
+                "digits. `begin` is unchanged."
+            )]
+            pub fn f32_fn_name(
+                high: impl AsRef<[f64]>,
+                low: impl AsRef<[f64]>,
+                close: impl AsRef<[f64]>,
+                period: Option<usize>,
+            ) -> Result<(Vec<f32>, usize), Error> {
+                let Output { values, begin } = $fn_name(high, low, close, period)?;
+                Ok((to_f32(values), begin))
+            }
+        });
+
+        #[cfg(not(feature = "pure_rust"))]
+        concat_idents!(padded_fn_name = $fn_name, _padded {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but returns as many values as there are ",
+                "candles.\n\nCandles without an associated value, i.e. those before `begin`, get ",
+                "`f64::NAN`."
+            )]
+            pub fn padded_fn_name(
+                high: impl AsRef<[f64]>,
+                low: impl AsRef<[f64]>,
+                close: impl AsRef<[f64]>,
+                period: Option<usize>,
+            ) -> Result<Vec<f64>, Error> {
+                let (high, low, close) = (high.as_ref(), low.as_ref(), close.as_ref());
+
+                let Output { values, begin } = $fn_name(high, low, close, period)?;
+                Ok(nan_padded(values, begin, close.len()))
+            }
+        });
+
+        #[cfg(not(feature = "pure_rust"))]
         concat_idents!(test_name = test, $fn_name {
             #[test]
             fn test_name() {
-                let high = [
-                    1.087130, 1.087120, 1.087220, 1.087230, 1.087180, 1.087160, 1.087210, 1.087150, 1.087200,
-                    1.087230, 1.087070, 1.087000, 1.086630, 1.086650, 1.086680, 1.086690, 1.086690, 1.086690,
-                    1.086690, 1.086650,
-                ];
-                let low = [
-                    1.087010, 1.087120, 1.087080, 1.087170, 1.087110, 1.087010, 1.087100, 1.087120, 1.087110,
-                    1.087080, 1.087000, 1.086630, 1.086630, 1.086610, 1.086630, 1.086640, 1.086650, 1.086650,
-                    1.086670, 1.086630,
-                ];
-                let close = [
-                    1.087130, 1.087120, 1.087220, 1.087230, 1.087110, 1.087120, 1.087100, 1.087120, 1.087130,
-                    1.087080, 1.087000, 1.086630, 1.086630, 1.086650, 1.086640, 1.086690, 1.086650, 1.086690,
-                    1.086670, 1.086640,
-                ];
-
-                let (atr_values, begin) = $fn_name(&high, &low, &close, Some(7)).unwrap();
-
-                // print values
-                for (index, value) in atr_values.iter().enumerate() {
-                    println!("index {} = {}", begin + index + 1, value);
-                }
+                let high = SAMPLE_HIGH;
+                let low = SAMPLE_LOW;
+                let close = SAMPLE_CLOSE;
+
+                let Output { values: atr_values, begin } = $fn_name(&high, &low, &close, Some(7)).unwrap();
+
+                assert_eq!(
+                    Some(begin),
+                    concat_idents!(lookback_fn_name = $fn_name, _lookback {
+                        lookback_fn_name(Some(7))
+                    })
+                );
+
+                #[allow(deprecated)]
+                let tuple = concat_idents!(tuple_fn_name = $fn_name, _tuple {
+                    tuple_fn_name(&high, &low, &close, Some(7)).unwrap()
+                });
+
+                assert_eq!((atr_values.clone(), begin), tuple);
+
+                $(assert_expected(&$expected, &atr_values);)?
+
+                let padded = concat_idents!(padded_fn_name = $fn_name, _padded {
+                    padded_fn_name(&high, &low, &close, Some(7)).unwrap()
+                });
+
+                assert_eq!(close.len(), padded.len());
+                assert!(padded[..begin].iter().all(|value| value.is_nan()));
+                assert_eq!(atr_values, padded[begin..]);
             }
         });
     };
@@ -79,18 +205,32 @@ macro_rules! define_high_low_close_period_fn {
 macro_rules! define_high_low_close_fn {
     ($(#[$attr:meta])* => $fn_name:ident, $ta_fn_name:ident) => {
         $(#[$attr])*
+        #[cfg(not(feature = "pure_rust"))]
         pub fn $fn_name(
-            high: &[f64],
-            low: &[f64],
-            close: &[f64],
-        ) -> Result<(Vec<f64>, usize), Error> {
-            assert!(!close.is_empty());
-            assert!(close.len() <= high.len());
-            assert!(close.len() <= low.len());
-
-            let mut out: Vec<f64> = Vec::with_capacity(close.len());
-            let mut out_begin = MaybeUninit::<i32>::uninit();
-            let mut out_size = MaybeUninit::<i32>::uninit();
+            high: impl AsRef<[f64]>,
+            low: impl AsRef<[f64]>,
+            close: impl AsRef<[f64]>,
+        ) -> Result<Output, Error> {
+            let (high, low, close) = (high.as_ref(), low.as_ref(), close.as_ref());
+
+            check_range(0, close.len())?;
+
+            trace_span!(stringify!($fn_name), close.len());
+
+            check_aligned(&[close, high, low])?;
+
+            check_input_len(close.len())?;
+
+            let capacity = check_lookback(
+                close.len(),
+                concat_idents!(lookback_fn_name = $ta_fn_name, _Lookback {
+                    unsafe { ta::lookback_fn_name() }
+                }),
+            )?;
+
+            let mut out: Vec<f64> = Vec::with_capacity(capacity);
+            let mut out_begin: i32 = 0;
+            let mut out_size: i32 = 0;
 
             unsafe {
                 let ret_code = ta::$ta_fn_name(
@@ -99,114 +239,673 @@ macro_rules! define_high_low_close_fn {
                     high.as_ptr(),
                     low.as_ptr(),
                     close.as_ptr(),
-                    out_begin.as_mut_ptr(),
-                    out_size.as_mut_ptr(),
+                    &mut out_begin,
+                    &mut out_size,
                     out.as_mut_ptr(),
                 );
 
                 match ret_code {
                     ta::TA_RetCode_TA_SUCCESS => {
-                        out.set_len(out_size.assume_init() as _);
-                        Ok((out, out_begin.assume_init() as _))
+                        out.set_len(output_len(out_size, out.capacity()));
+                        Ok(Output {
+                            values: out,
+                            begin: checked_begin(out_begin, close.len(), capacity),
+                        })
+                    }
+                    _ => {
+                        trace_error!(ret_code);
+
+                        Err(Error::Computation(format!(
+                            "Could not compute function; error: {:?}",
+                            ret_code
+                        )))
                     }
-                    _ => Err(Error(format!(
-                        "Could not compute function; error: {:?}",
-                        ret_code
-                    ))),
                 }
             }
         }
 
+        #[cfg(not(feature = "pure_rust"))]
+        concat_idents!(tuple_fn_name = $fn_name, _tuple {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but returns a tuple containing the ",
+                "values and `begin`."
+            )]
+            #[deprecated(
+                since = "0.1.2",
+                note = "use the function without the `_tuple` suffix which returns an `Output`"
+            )]
+            pub fn tuple_fn_name(
+                high: impl AsRef<[f64]>,
+                low: impl AsRef<[f64]>,
+                close: impl AsRef<[f64]>,
+            ) -> Result<(Vec<f64>, usize), Error> {
+                $fn_name(high, low, close).map(Into::into)
+            }
+        });
+
+        #[cfg(not(feature = "pure_rust"))]
+        concat_idents!(lookback_fn_name = $fn_name, _lookback {
+            #[doc = concat!(
+                "The number of inputs [`", stringify!($fn_name), "()`] consumes before it outputs ",
+                "its first value, i.e. its `begin`."
+            )]
+            pub fn lookback_fn_name() -> usize {
+                concat_idents!(ta_lookback_fn_name = $ta_fn_name, _Lookback {
+                    unsafe { ta::ta_lookback_fn_name() as _ }
+                })
+            }
+        });
+
+        #[cfg(feature = "f32")]
+        #[cfg(not(feature = "pure_rust"))]
+        concat_idents!(f32_fn_name = $fn_name, _f32 {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but returns the values as `f32`.\n\n",
+                "The computation is done in `f64`, only the results are rounded to the nearest ",
+                "`f32`. This halves their memory but leaves only about 7 significant decimal ",
+                "digits. `begin` is unchanged."
+            )]
+            pub fn f32_fn_name(
+                high: impl AsRef<[f64]>,
+                low: impl AsRef<[f64]>,
+                close: impl AsRef<[f64]>,
+            ) -> Result<(Vec<f32>, usize), Error> {
+                let Output { values, begin } = $fn_name(high, low, close)?;
+                Ok((to_f32(values), begin))
+            }
+        });
+
+        #[cfg(not(feature = "pure_rust"))]
+        concat_idents!(padded_fn_name = $fn_name, _padded {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but returns as many values as there are ",
+                "candles.\n\nCandles without an associated value, i.e. those before `begin`, get ",
+                "`f64::NAN`."
+            )]
+            pub fn padded_fn_name(
+                high: impl AsRef<[f64]>,
+                low: impl AsRef<[f64]>,
+                close: impl AsRef<[f64]>,
+            ) -> Result<Vec<f64>, Error> {
+                let (high, low, close) = (high.as_ref(), low.as_ref(), close.as_ref());
+
+                let Output { values, begin } = $fn_name(high, low, close)?;
+                Ok(nan_padded(values, begin, close.len()))
+            }
+        });
+
+        #[cfg(not(feature = "pure_rust"))]
         concat_idents!(test_name = test, $fn_name {
             #[test]
             fn test_name() {
-                let high = [
-                    1.087130, 1.087120, 1.087220, 1.087230, 1.087180, 1.087160, 1.087210, 1.087150, 1.087200,
-                    1.087230, 1.087070, 1.087000, 1.086630, 1.086650, 1.086680, 1.086690, 1.086690, 1.086690,
-                    1.086690, 1.086650,
-                ];
-                let low = [
-                    1.087010, 1.087120, 1.087080, 1.087170, 1.087110, 1.087010, 1.087100, 1.087120, 1.087110,
-                    1.087080, 1.087000, 1.086630, 1.086630, 1.086610, 1.086630, 1.086640, 1.086650, 1.086650,
-                    1.086670, 1.086630,
-                ];
-                let close = [
-                    1.087130, 1.087120, 1.087220, 1.087230, 1.087110, 1.087120, 1.087100, 1.087120, 1.087130,
-                    1.087080, 1.087000, 1.086630, 1.086630, 1.086650, 1.086640, 1.086690, 1.086650, 1.086690,
-                    1.086670, 1.086640,
-                ];
-
-                let (atr_values, begin) = $fn_name(&high, &low, &close).unwrap();
-
-                // print values
-                for (index, value) in atr_values.iter().enumerate() {
-                    println!("index {} = {}", begin + index + 1, value);
-                }
+                let high = SAMPLE_HIGH;
+                let low = SAMPLE_LOW;
+                let close = SAMPLE_CLOSE;
+
+                let Output { values: atr_values, begin } = $fn_name(&high, &low, &close).unwrap();
+
+                assert_eq!(
+                    begin,
+                    concat_idents!(lookback_fn_name = $fn_name, _lookback { lookback_fn_name() })
+                );
+
+                #[allow(deprecated)]
+                let tuple = concat_idents!(tuple_fn_name = $fn_name, _tuple {
+                    tuple_fn_name(&high, &low, &close).unwrap()
+                });
+
+                assert_eq!((atr_values.clone(), begin), tuple);
+
+                let padded = concat_idents!(padded_fn_name = $fn_name, _padded {
+                    padded_fn_name(&high, &low, &close).unwrap()
+                });
+
+                assert_eq!(close.len(), padded.len());
+                assert!(padded[..begin].iter().all(|value| value.is_nan()));
+                assert_eq!(atr_values, padded[begin..]);
             }
         });
     };
 }
 
 macro_rules! define_values_period_fn {
-    ($(#[$attr:meta])* => $fn_name:ident, $ta_fn_name:ident) => {
+    // With the `pure_rust` feature the functions with a `fallback`, from the
+    // `fallback` module, call it and all others are left out.
+    //
+    // Functions with `memory: true`, e.g. EMA, depend on all inputs. Their
+    // `_latest` variant computes all values instead of only the last window.
+    (
+        $(#[$attr:meta])* => $fn_name:ident, $ta_fn_name:ident $(, memory: $memory:tt)?,
+        fallback: $fallback:ident $(, expected: $expected:expr)?
+    ) => {
+        define_values_period_fn!(
+            @define cfg(all()), [$($memory)?], $(#[$attr])* => $fn_name, $ta_fn_name, $fallback
+            $(, expected: $expected)?
+        );
+    };
+    (
+        $(#[$attr:meta])* => $fn_name:ident, $ta_fn_name:ident $(, expected: $expected:expr)?
+    ) => {
+        define_values_period_fn!(
+            @define cfg(not(feature = "pure_rust")), [], $(#[$attr])* => $fn_name, $ta_fn_name
+            $(, expected: $expected)?
+        );
+    };
+    (
+        @latest [true] $cfg:meta, $fn_name:ident, $ta_fn_name:ident $(, $fallback:ident)?
+    ) => {
+        #[$cfg]
+        concat_idents!(latest_fn_name = $fn_name, _latest {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but returns only the value of the last ",
+                "input.\n\nReturns `None` if there are too few inputs for a single value.\n\n",
+                "The value depends on all inputs so this computes all values. Use the streaming ",
+                "states, e.g. [`RsiState`](crate::RsiState), to update the latest value cheaply."
+            )]
+            pub fn latest_fn_name(
+                input: impl AsRef<[f64]>,
+                period: Option<usize>,
+            ) -> Result<Option<f64>, Error> {
+                match $fn_name(input, period) {
+                    Ok(output) => Ok(output.last().copied()),
+                    Err(Error::InsufficientData { .. }) => Ok(None),
+                    Err(error) => Err(error),
+                }
+            }
+        });
+    };
+    (
+        @latest [] $cfg:meta, $fn_name:ident, $ta_fn_name:ident $(, $fallback:ident)?
+    ) => {
+        #[$cfg]
+        concat_idents!(latest_fn_name = $fn_name, _latest {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but computes only the value of the last ",
+                "input, from the `lookback + 1` inputs ending with it.\n\nReturns `None` if ",
+                "there are fewer inputs than that. The value is the last of computing all of them, ",
+                "up to rounding."
+            )]
+            pub fn latest_fn_name(
+                input: impl AsRef<[f64]>,
+                period: Option<usize>,
+            ) -> Result<Option<f64>, Error> {
+                let input = input.as_ref();
+
+                check_range(0, input.len())?;
+
+                trace_span!(stringify!($fn_name), input.len(), period);
+
+                check_input_len(input.len())?;
+
+                $(
+                    #[cfg(feature = "pure_rust")]
+                    return fallback::latest(
+                        input,
+                        period,
+                        fallback::$fallback,
+                        concat_idents!(fallback_lookback_fn_name = $fallback, _lookback {
+                            fallback::fallback_lookback_fn_name
+                        }),
+                    );
+                )?
+
+                #[cfg(not(feature = "pure_rust"))]
+                {
+                    let period = period_or_default(period)?;
+
+                    match check_lookback(
+                        input.len(),
+                        concat_idents!(lookback_fn_name = $ta_fn_name, _Lookback {
+                            unsafe { ta::lookback_fn_name(period) }
+                        }),
+                    ) {
+                        Err(Error::InsufficientData { .. }) => return Ok(None),
+                        result => result?,
+                    };
+
+                    let mut out = f64::NAN;
+                    let mut out_begin: i32 = 0;
+                    let mut out_size: i32 = 0;
+
+                    unsafe {
+                        let ret_code = ta::$ta_fn_name(
+                            (input.len() - 1) as _,
+                            (input.len() - 1) as _,
+                            input.as_ptr(),
+                            period,
+                            &mut out_begin,
+                            &mut out_size,
+                            &mut out,
+                        );
+
+                        match ret_code {
+                            ta::TA_RetCode_TA_SUCCESS => Ok((1 == out_size).then_some(out)),
+                            _ => {
+                                trace_error!(ret_code);
+
+                                Err(Error::Computation(format!(
+                                    "Could not compute function; error: {:?}",
+                                    ret_code
+                                )))
+                            }
+                        }
+                    }
+                }
+            }
+        });
+    };
+    (
+        @define $cfg:meta, [$($memory:tt)?], $(#[$attr:meta])* => $fn_name:ident,
+        $ta_fn_name:ident $(, $fallback:ident)? $(, expected: $expected:expr)?
+    ) => {
         $(#[$attr])*
+        #[$cfg]
         pub fn $fn_name(
-            input: &[f64],
+            input: impl AsRef<[f64]>,
             period: Option<usize>,
-        ) -> Result<(Vec<f64>, usize), Error> {
-            assert!(!input.is_empty());
+        ) -> Result<Output, Error> {
+            let mut out = Vec::new();
+            let begin = concat_idents!(into_fn_name = $fn_name, _into {
+                into_fn_name(input, period, &mut out)?
+            });
 
-            let mut out: Vec<f64> = Vec::with_capacity(input.len());
-            let mut out_begin = MaybeUninit::<i32>::uninit();
-            let mut out_size = MaybeUninit::<i32>::uninit();
+            Ok(Output { values: out, begin })
+        }
+
+        #[$cfg]
+        concat_idents!(into_fn_name = $fn_name, _into {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but writes the values to `out` and ",
+                "returns `begin`.\n\n`out` is cleared first and only grows if it has too little ",
+                "capacity. Reusing it across calls avoids allocating."
+            )]
+            pub fn into_fn_name(
+                input: impl AsRef<[f64]>,
+                period: Option<usize>,
+                out: &mut Vec<f64>,
+            ) -> Result<usize, Error> {
+                let input = input.as_ref();
+
+                check_range(0, input.len())?;
+
+                trace_span!(stringify!($fn_name), input.len(), period);
+
+                check_input_len(input.len())?;
+
+                $(
+                    #[cfg(feature = "pure_rust")]
+                    return fallback::$fallback(input, period, out);
+                )?
+
+                #[cfg(not(feature = "pure_rust"))]
+                {
+                    let period = period_or_default(period)?;
+
+                    let capacity = check_lookback(
+                        input.len(),
+                        concat_idents!(lookback_fn_name = $ta_fn_name, _Lookback {
+                            unsafe { ta::lookback_fn_name(period) }
+                        }),
+                    )?;
+
+                    out.clear();
+                    out.reserve_exact(capacity);
+
+                    let mut out_begin: i32 = 0;
+                    let mut out_size: i32 = 0;
+
+                    unsafe {
+                        let ret_code = ta::$ta_fn_name(
+                            0,
+                            (input.len() - 1) as _,
+                            input.as_ptr(),
+                            period,
+                            &mut out_begin,
+                            &mut out_size,
+                            out.as_mut_ptr(),
+                        );
+
+                        match ret_code {
+                            ta::TA_RetCode_TA_SUCCESS => {
+                                out.set_len(output_len(out_size, out.capacity()));
+                                Ok(checked_begin(out_begin, input.len(), capacity))
+                            }
+                            _ => {
+                                trace_error!(ret_code);
+
+                                Err(Error::Computation(format!(
+                                    "Could not compute function; error: {:?}",
+                                    ret_code
+                                )))
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        define_values_period_fn!(@latest [$($memory)?] $cfg, $fn_name, $ta_fn_name $(, $fallback)?);
+
+        #[$cfg]
+        concat_idents!(tuple_fn_name = $fn_name, _tuple {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but returns a tuple containing the ",
+                "values and `begin`."
+            )]
+            #[deprecated(
+                since = "0.1.2",
+                note = "use the function without the `_tuple` suffix which returns an `Output`"
+            )]
+            pub fn tuple_fn_name(
+                input: impl AsRef<[f64]>,
+                period: Option<usize>,
+            ) -> Result<(Vec<f64>, usize), Error> {
+                $fn_name(input, period).map(Into::into)
+            }
+        });
+
+        #[$cfg]
+        concat_idents!(lookback_fn_name = $fn_name, _lookback {
+            #[doc = concat!(
+                "The number of inputs [`", stringify!($fn_name), "()`] consumes before it outputs ",
+                "its first value, i.e. its `begin`.\n\nReturns `None` if `period` is out of range."
+            )]
+            pub fn lookback_fn_name(period: Option<usize>) -> Option<usize> {
+                $(
+                    #[cfg(feature = "pure_rust")]
+                    return concat_idents!(fallback_lookback_fn_name = $fallback, _lookback {
+                        fallback::fallback_lookback_fn_name(period).ok()
+                    });
+                )?
+
+                #[cfg(not(feature = "pure_rust"))]
+                {
+                    let lookback = concat_idents!(ta_lookback_fn_name = $ta_fn_name, _Lookback {
+                        unsafe { ta::ta_lookback_fn_name(period_or_default(period).ok()?) }
+                    });
+
+                    usize::try_from(lookback).ok()
+                }
+            }
+        });
+
+        #[cfg(feature = "f32")]
+        #[$cfg]
+        concat_idents!(f32_fn_name = $fn_name, _f32 {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but returns the values as `f32`.\n\n",
+                "The computation is done in `f64`, only the results are rounded to the nearest ",
+                "`f32`. This halves their memory but leaves only about 7 significant decimal ",
+                "digits. `begin` is unchanged."
+            )]
+            pub fn f32_fn_name(
+                input: impl AsRef<[f64]>,
+                period: Option<usize>,
+            ) -> Result<(Vec<f32>, usize), Error> {
+                let Output { values, begin } = $fn_name(input, period)?;
+                Ok((to_f32(values), begin))
+            }
+        });
+
+        #[$cfg]
+        concat_idents!(padded_fn_name = $fn_name, _padded {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but returns as many values as there are ",
+                "inputs.\n\nInputs without an associated value, i.e. those before `begin`, get ",
+                "`f64::NAN`."
+            )]
+            pub fn padded_fn_name(
+                input: impl AsRef<[f64]>,
+                period: Option<usize>,
+            ) -> Result<Vec<f64>, Error> {
+                let input = input.as_ref();
+
+                let Output { values, begin } = $fn_name(input, period)?;
+                Ok(nan_padded(values, begin, input.len()))
+            }
+        });
+
+        #[$cfg]
+        concat_idents!(test_name = test, $fn_name {
+            #[test]
+            fn test_name() {
+                let close_prices = SAMPLE_PRICES;
+
+                // compute sma, since we use a period of 10, the first 9 closes won't have
+                // an sma value because there is not enough data, so begin will be set to
+                // the index 9
+                let Output { values: sma_values, begin } = $fn_name(&close_prices, Some(10)).unwrap();
+
+                assert_eq!(
+                    Some(begin),
+                    concat_idents!(lookback_fn_name = $fn_name, _lookback {
+                        lookback_fn_name(Some(10))
+                    })
+                );
+
+                $(assert_expected(&$expected, &sma_values);)?
+
+                #[allow(deprecated)]
+                let tuple = concat_idents!(tuple_fn_name = $fn_name, _tuple {
+                    tuple_fn_name(&close_prices, Some(10)).unwrap()
+                });
+
+                assert_eq!((sma_values.clone(), begin), tuple);
+
+                let mut out = vec![0.0; 3];
+                let into_begin = concat_idents!(into_fn_name = $fn_name, _into {
+                    into_fn_name(&close_prices, Some(10), &mut out).unwrap()
+                });
+                assert_eq!(begin, into_begin);
+                assert_eq!(sma_values, out);
+
+                let latest = concat_idents!(latest_fn_name = $fn_name, _latest {
+                    latest_fn_name(&close_prices, Some(10)).unwrap().unwrap()
+                });
+                assert!((sma_values[sma_values.len() - 1] - latest).abs() < 1e-9);
+                assert_eq!(
+                    None,
+                    concat_idents!(latest_fn_name = $fn_name, _latest {
+                        latest_fn_name(&close_prices[..begin], Some(10)).unwrap()
+                    })
+                );
+
+                let padded = concat_idents!(padded_fn_name = $fn_name, _padded {
+                    padded_fn_name(&close_prices, Some(10)).unwrap()
+                });
+
+                assert_eq!(close_prices.len(), padded.len());
+                assert!(padded[..begin].iter().all(|value| value.is_nan()));
+                assert_eq!(sma_values, padded[begin..]);
+            }
+        });
+    };
+}
+
+macro_rules! define_values_two_period_fn {
+    ($(#[$attr:meta])* => $fn_name:ident, $ta_fn_name:ident) => {
+        $(#[$attr])*
+        #[cfg(not(feature = "pure_rust"))]
+        pub fn $fn_name(
+            input: impl AsRef<[f64]>,
+            fast_period: Option<usize>,
+            slow_period: Option<usize>,
+            moving_average_type: Option<MovingAverageType>,
+        ) -> Result<Output, Error> {
+            let input = input.as_ref();
+
+            check_range(0, input.len())?;
+
+            trace_span!(stringify!($fn_name), input.len(), fast_period, slow_period, moving_average_type);
+
+            check_input_len(input.len())?;
+
+            let fast_period = period_or_default(fast_period)?;
+            let slow_period = period_or_default(slow_period)?;
+            let moving_average_type =
+                moving_average_type.unwrap_or(MovingAverageType::SimpleMovingAverage) as _;
+
+            let capacity = check_lookback(
+                input.len(),
+                concat_idents!(lookback_fn_name = $ta_fn_name, _Lookback {
+                    unsafe { ta::lookback_fn_name(fast_period, slow_period, moving_average_type) }
+                }),
+            )?;
+
+            let mut out: Vec<f64> = Vec::with_capacity(capacity);
+            let mut out_begin: i32 = 0;
+            let mut out_size: i32 = 0;
 
             unsafe {
                 let ret_code = ta::$ta_fn_name(
                     0,
                     (input.len() - 1) as _,
                     input.as_ptr(),
-                    if let Some(period) = period {
-                        period as _
-                    } else {
-                        // ta::TA_INTEGER_DEFAULT
-                        i32::MIN
-                    },
-                    out_begin.as_mut_ptr(),
-                    out_size.as_mut_ptr(),
+                    fast_period,
+                    slow_period,
+                    moving_average_type,
+                    &mut out_begin,
+                    &mut out_size,
                     out.as_mut_ptr(),
                 );
 
                 match ret_code {
                     ta::TA_RetCode_TA_SUCCESS => {
-                        out.set_len(out_size.assume_init() as _);
-                        Ok((out, out_begin.assume_init() as _))
+                        out.set_len(output_len(out_size, out.capacity()));
+                        Ok(Output {
+                            values: out,
+                            begin: checked_begin(out_begin, input.len(), capacity),
+                        })
+                    }
+                    _ => {
+                        trace_error!(ret_code);
+
+                        Err(Error::Computation(format!(
+                            "Could not compute function; error: {:?}",
+                            ret_code
+                        )))
                     }
-                    _ => Err(Error(format!(
-                        "Could not compute function; error: {:?}",
-                        ret_code
-                    ))),
                 }
             }
         }
 
+        #[cfg(not(feature = "pure_rust"))]
+        concat_idents!(tuple_fn_name = $fn_name, _tuple {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but returns a tuple containing the ",
+                "values and `begin`."
+            )]
+            #[deprecated(
+                since = "0.1.2",
+                note = "use the function without the `_tuple` suffix which returns an `Output`"
+            )]
+            pub fn tuple_fn_name(
+                input: impl AsRef<[f64]>,
+                fast_period: Option<usize>,
+                slow_period: Option<usize>,
+                moving_average_type: Option<MovingAverageType>,
+            ) -> Result<(Vec<f64>, usize), Error> {
+                $fn_name(input, fast_period, slow_period, moving_average_type).map(Into::into)
+            }
+        });
+
+        #[cfg(not(feature = "pure_rust"))]
+        concat_idents!(lookback_fn_name = $fn_name, _lookback {
+            #[doc = concat!(
+                "The number of inputs [`", stringify!($fn_name), "()`] consumes before it outputs ",
+                "its first value, i.e. its `begin`.\n\nReturns `None` if a parameter is out of ",
+                "range."
+            )]
+            pub fn lookback_fn_name(
+                fast_period: Option<usize>,
+                slow_period: Option<usize>,
+                moving_average_type: Option<MovingAverageType>,
+            ) -> Option<usize> {
+                let lookback = concat_idents!(ta_lookback_fn_name = $ta_fn_name, _Lookback {
+                    unsafe {
+                        ta::ta_lookback_fn_name(
+                            period_or_default(fast_period).ok()?,
+                            period_or_default(slow_period).ok()?,
+                            moving_average_type.unwrap_or(MovingAverageType::SimpleMovingAverage)
+                                as _,
+                        )
+                    }
+                });
+
+                usize::try_from(lookback).ok()
+            }
+        });
+
+        #[cfg(feature = "f32")]
+        #[cfg(not(feature = "pure_rust"))]
+        concat_idents!(f32_fn_name = $fn_name, _f32 {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but returns the values as `f32`.\n\n",
+                "The computation is done in `f64`, only the results are rounded to the nearest ",
+                "`f32`. This halves their memory but leaves only about 7 significant decimal ",
+                "digits. `begin` is unchanged."
+            )]
+            pub fn f32_fn_name(
+                input: impl AsRef<[f64]>,
+                fast_period: Option<usize>,
+                slow_period: Option<usize>,
+                moving_average_type: Option<MovingAverageType>,
+            ) -> Result<(Vec<f32>, usize), Error> {
+                let Output { values, begin } = $fn_name(input, fast_period, slow_period, moving_average_type)?;
+                Ok((to_f32(values), begin))
+            }
+        });
+
+        #[cfg(not(feature = "pure_rust"))]
+        concat_idents!(padded_fn_name = $fn_name, _padded {
+            #[doc = concat!(
+                "Same as [`", stringify!($fn_name), "()`] but returns as many values as there are ",
+                "inputs.\n\nInputs without an associated value, i.e. those before `begin`, get ",
+                "`f64::NAN`."
+            )]
+            pub fn padded_fn_name(
+                input: impl AsRef<[f64]>,
+                fast_period: Option<usize>,
+                slow_period: Option<usize>,
+                moving_average_type: Option<MovingAverageType>,
+            ) -> Result<Vec<f64>, Error> {
+                let input = input.as_ref();
+
+                let Output { values, begin } = $fn_name(input, fast_period, slow_period, moving_average_type)?;
+                Ok(nan_padded(values, begin, input.len()))
+            }
+        });
+
+        #[cfg(not(feature = "pure_rust"))]
         concat_idents!(test_name = test, $fn_name {
             #[test]
             fn test_name() {
-                let close_prices = [
-                    1.087010, 1.087120, 1.087080, 1.087170, 1.087110, 1.087010, 1.087100, 1.087120, 1.087110,
-                    1.087080, 1.087000, 1.086630, 1.086630, 1.086610, 1.086630, 1.086640, 1.086650, 1.086650,
-                    1.086670, 1.086630,
-                ];
+                let close_prices = SAMPLE_PRICES;
 
-                // compute sma, since we use a period of 10, the first 10 closes won't have
-                // an sma value because there is not enough data, so begin will be set to
-                // the index 29
-                let (sma_values, begin) = $fn_name(&close_prices, Some(10)).unwrap();
+                // The slow SMA over 10 closes needs the first 9 closes to warm up.
+                let Output { values, begin } = $fn_name(&close_prices, Some(5), Some(10), None).unwrap();
 
-                // print values
-                for (index, value) in sma_values.iter().enumerate() {
-                    println!("Close index {} = {}", begin + index + 1, value);
-                }
+                assert_eq!(
+                    Some(begin),
+                    concat_idents!(lookback_fn_name = $fn_name, _lookback {
+                        lookback_fn_name(Some(5), Some(10), None)
+                    })
+                );
+
+                #[allow(deprecated)]
+                let tuple = concat_idents!(tuple_fn_name = $fn_name, _tuple {
+                    tuple_fn_name(&close_prices, Some(5), Some(10), None).unwrap()
+                });
+
+                assert_eq!((values.clone(), begin), tuple);
+
+                let padded = concat_idents!(padded_fn_name = $fn_name, _padded {
+                    padded_fn_name(&close_prices, Some(5), Some(10), None).unwrap()
+                });
+
+                assert_eq!(close_prices.len(), padded.len());
+                assert!(padded[..begin].iter().all(|value| value.is_nan()));
+                assert_eq!(values, padded[begin..]);
             }
         });
     };
@@ -0,0 +1,1074 @@
+//! Oscillators composed from wrapped TA functions.
+use crate::{
+    check_aligned, exponential_moving_average, hilbert_dominant_cycle_period,
+    moving_average_convergence_divergence, nan_padded, percentage_price_oscillator, rate_of_change,
+    rebase_too_short, relative_strength_index, rolling_max, rolling_min, rolling_sum, true_range,
+    validate_aligned, validate_series, weighted_moving_average, Error, IndicatorOutput,
+    MovingAverageType, Output,
+};
+use std::collections::{btree_map::Entry, BTreeMap};
+
+/// Compute the [Coppock Curve](https://en.wikipedia.org/wiki/Coppock_curve).
+///
+/// This is the WMA over `wma_period` of the sum of the ROC over `roc_long` and
+/// the ROC over `roc_short`. The classic parameters are `14`, `11` and `10`
+/// for monthly closes.
+///
+/// The sum starts at the later `begin` of the two ROCs, i.e. at `roc_long`
+/// for `roc_short < roc_long`. The WMA needs another `wma_period - 1` sums to
+/// warm up.
+///
+/// Returns a tuple containing the list of Coppock Curve values and the index of
+/// the first input to have an associated value.
+pub fn coppock_curve(
+    input: &[f64],
+    roc_long: usize,
+    roc_short: usize,
+    wma_period: usize,
+) -> Result<(Vec<f64>, usize), Error> {
+    let Output {
+        values: long,
+        begin: long_begin,
+    } = rate_of_change(input, Some(roc_long))?;
+    let Output {
+        values: short,
+        begin: short_begin,
+    } = rate_of_change(input, Some(roc_short))?;
+
+    let begin = long_begin.max(short_begin);
+
+    let sum = long[begin - long_begin..]
+        .iter()
+        .zip(&short[begin - short_begin..])
+        .map(|(long, short)| long + short)
+        .collect::<Vec<_>>();
+
+    let Output {
+        values,
+        begin: wma_begin,
+    } = weighted_moving_average(&sum, Some(wma_period))
+        .map_err(|error| rebase_too_short(error, begin))?;
+
+    Ok((values, begin + wma_begin))
+}
+
+/// Compute an RSI adapting its period to the dominant price cycle.
+///
+/// At each input the period is half the
+/// [`hilbert_dominant_cycle_period()`], rounded, but at least `2`, as
+/// suggested by Ehlers. Its value is that of the
+/// [`relative_strength_index()`](crate::relative_strength_index) over all
+/// inputs with this period, i.e. Wilder-smoothed from the first input.
+///
+/// The values start with the dominant cycle period, i.e. `begin` is its
+/// `begin`.
+///
+/// Returns a tuple containing the list of adaptive RSI values and the index
+/// of the first input to have an associated value.
+pub fn adaptive_rsi(close: &[f64]) -> Result<(Vec<f64>, usize), Error> {
+    let (cycle_periods, begin) = hilbert_dominant_cycle_period(close)?;
+
+    // The RSI over each distinct period, computed once.
+    let mut rsis = BTreeMap::new();
+
+    let values = cycle_periods
+        .iter()
+        .enumerate()
+        .map(|(index, cycle_period)| {
+            let period = ((cycle_period / 2.0).round() as usize).max(2);
+
+            let rsi = match rsis.entry(period) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    entry.insert(match relative_strength_index(close, Some(period)) {
+                        Ok(rsi) => Some(rsi),
+                        Err(Error::InsufficientData { .. }) => None,
+                        Err(error) => return Err(error),
+                    })
+                }
+            };
+
+            // The dominant cycle needs more inputs than the longest period,
+            // unless the RSI has an unstable period.
+            Ok(rsi
+                .as_ref()
+                .and_then(|rsi| {
+                    (begin + index)
+                        .checked_sub(rsi.begin)
+                        .map(|offset| rsi.values[offset])
+                })
+                .unwrap_or(f64::NAN))
+        })
+        .collect::<Result<_, Error>>()?;
+
+    Ok((values, begin))
+}
+
+/// Compute Chaikin Volatility.
+///
+/// This is the ROC over `roc_period` of the EMA over `ema_period` of each
+/// candle's high minus its low. The classic parameters are `10` and `10`.
+///
+/// The spread starts at the first candle. The EMA needs `ema_period - 1`
+/// spreads to warm up and the ROC another `roc_period` EMA values.
+///
+/// Returns [`Error::LengthMismatch`] if `low` differs in length from `high`.
+///
+/// Returns a tuple containing the list of Chaikin Volatility values and the
+/// index of the first candle to have an associated value.
+pub fn chaikin_volatility(
+    high: &[f64],
+    low: &[f64],
+    ema_period: usize,
+    roc_period: usize,
+) -> Result<(Vec<f64>, usize), Error> {
+    check_aligned(&[high, low])?;
+
+    let spread = high
+        .iter()
+        .zip(low)
+        .map(|(high, low)| high - low)
+        .collect::<Vec<_>>();
+
+    let Output { values: ema, begin } = exponential_moving_average(&spread, Some(ema_period))?;
+
+    let Output {
+        values,
+        begin: roc_begin,
+    } = rate_of_change(&ema, Some(roc_period)).map_err(|error| rebase_too_short(error, begin))?;
+
+    Ok((values, begin + roc_begin))
+}
+
+/// Compute Williams Accumulation/Distribution.
+///
+/// Each up close adds its distance from the true low, the lower of its low
+/// and the previous close. Each down close adds its (negative) distance from
+/// the true high, the higher of its high and the previous close. Unchanged
+/// closes add nothing. Unlike Chaikin's A/D line this ignores volume.
+///
+/// Each value needs a previous close so the line starts at `1`.
+///
+/// Returns [`Error::LengthMismatch`] if `high` or `low` differ in length from
+/// `close` and [`Error::InvalidInput`] for the first value that is not finite,
+/// see [`validate_aligned()`].
+///
+/// Returns a tuple containing the list of WAD values and the index of the
+/// first candle to have an associated value.
+pub fn williams_ad(high: &[f64], low: &[f64], close: &[f64]) -> Result<(Vec<f64>, usize), Error> {
+    validate_aligned(&[close, high, low])?;
+
+    if close.len() < 2 {
+        return Err(Error::InsufficientData {
+            have: close.len(),
+            need: 2,
+        });
+    }
+
+    let mut wad = 0.0;
+
+    Ok((
+        (1..close.len())
+            .map(|index| {
+                let previous_close = close[index - 1];
+
+                if previous_close < close[index] {
+                    wad += close[index] - low[index].min(previous_close);
+                } else if close[index] < previous_close {
+                    wad += close[index] - high[index].max(previous_close);
+                }
+
+                wad
+            })
+            .collect(),
+        1,
+    ))
+}
+
+/// The PVO, signal and histogram lines returned by
+/// [`percentage_volume_oscillator()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pvo {
+    pub pvo: Vec<f64>,
+    pub signal: Vec<f64>,
+    pub histogram: Vec<f64>,
+    /// The index of the first candle to have an associated value.
+    pub begin: usize,
+}
+
+impl From<Pvo> for IndicatorOutput {
+    fn from(pvo: Pvo) -> Self {
+        Self {
+            columns: vec![
+                ("pvo".to_string(), pvo.pvo),
+                ("signal".to_string(), pvo.signal),
+                ("histogram".to_string(), pvo.histogram),
+            ],
+            begin: pvo.begin,
+        }
+    }
+}
+
+/// Compute the [Percentage Volume
+/// Oscillator](https://school.stockcharts.com/doku.php?id=technical_indicators:percentage_volume_oscillator_pvo).
+///
+/// This is the MACD of `volume` as a percentage: the PVO line is the
+/// [`percentage_price_oscillator()`](crate::percentage_price_oscillator) of
+/// the volume with exponential moving averages, the signal line its EMA over
+/// `signal_period` and the histogram their difference. The classic parameters
+/// are `12`, `26` and `9`.
+///
+/// The PVO line needs `slow_period - 1` volumes to warm up, the signal line
+/// another `signal_period - 1` PVO values. All three lines start at the
+/// signal line's `begin`.
+///
+/// Returns [`Error::InvalidInput`] for the first volume that is negative or
+/// not finite.
+pub fn percentage_volume_oscillator(
+    volume: &[f64],
+    fast_period: usize,
+    slow_period: usize,
+    signal_period: usize,
+) -> Result<Pvo, Error> {
+    validate_series(volume)?;
+
+    if let Some(index) = volume.iter().position(|volume| *volume < 0.0) {
+        return Err(Error::InvalidInput { index });
+    }
+
+    let Output {
+        values: pvo,
+        begin: pvo_begin,
+    } = percentage_price_oscillator(
+        volume,
+        Some(fast_period),
+        Some(slow_period),
+        Some(MovingAverageType::ExponentialMovingAverage),
+    )?;
+
+    let Output {
+        values: signal,
+        begin: signal_begin,
+    } = exponential_moving_average(&pvo, Some(signal_period))
+        .map_err(|error| rebase_too_short(error, pvo_begin))?;
+
+    let pvo = pvo[signal_begin..].to_vec();
+
+    Ok(Pvo {
+        histogram: pvo
+            .iter()
+            .zip(&signal)
+            .map(|(pvo, signal)| pvo - signal)
+            .collect(),
+        pvo,
+        signal,
+        begin: pvo_begin + signal_begin,
+    })
+}
+
+/// Compute the [Percentage Volume
+/// Oscillator](https://school.stockcharts.com/doku.php?id=technical_indicators:percentage_volume_oscillator_pvo).
+///
+/// Same as [`percentage_volume_oscillator()`] but each line has as many
+/// values as there are candles.
+///
+/// Candles without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+pub fn percentage_volume_oscillator_padded(
+    volume: &[f64],
+    fast_period: usize,
+    slow_period: usize,
+    signal_period: usize,
+) -> Result<Pvo, Error> {
+    let pvo = percentage_volume_oscillator(volume, fast_period, slow_period, signal_period)?;
+
+    Ok(Pvo {
+        pvo: nan_padded(pvo.pvo, pvo.begin, volume.len()),
+        signal: nan_padded(pvo.signal, pvo.begin, volume.len()),
+        histogram: nan_padded(pvo.histogram, pvo.begin, volume.len()),
+        begin: pvo.begin,
+    })
+}
+
+/// Compute the [Percentage Volume
+/// Oscillator](https://school.stockcharts.com/doku.php?id=technical_indicators:percentage_volume_oscillator_pvo).
+///
+/// Same as [`percentage_volume_oscillator()`] but returns the lines as the
+/// columns `"pvo"`, `"signal"` and `"histogram"`.
+pub fn percentage_volume_oscillator_columns(
+    volume: &[f64],
+    fast_period: usize,
+    slow_period: usize,
+    signal_period: usize,
+) -> Result<IndicatorOutput, Error> {
+    percentage_volume_oscillator(volume, fast_period, slow_period, signal_period).map(Into::into)
+}
+
+/// The bull and bear power returned by [`elder_ray()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElderRay {
+    pub bull_power: Vec<f64>,
+    pub bear_power: Vec<f64>,
+    /// The index of the first candle to have an associated value.
+    pub begin: usize,
+}
+
+impl From<ElderRay> for IndicatorOutput {
+    fn from(elder_ray: ElderRay) -> Self {
+        Self {
+            columns: vec![
+                ("bull_power".to_string(), elder_ray.bull_power),
+                ("bear_power".to_string(), elder_ray.bear_power),
+            ],
+            begin: elder_ray.begin,
+        }
+    }
+}
+
+/// Compute [Elder Ray](https://www.investopedia.com/terms/e/elderray.asp).
+///
+/// The bull power is each high minus the EMA of the closes over `period`, the
+/// bear power each low minus it.
+///
+/// Both start at the EMA's `begin`. The highs and lows before it are skipped
+/// so each power is computed from the candle the EMA value belongs to.
+///
+/// Returns [`Error::LengthMismatch`] if `high` or `low` differ in length from
+/// `close`.
+pub fn elder_ray(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    period: usize,
+) -> Result<ElderRay, Error> {
+    check_aligned(&[close, high, low])?;
+
+    let Output { values: ema, begin } = exponential_moving_average(close, Some(period))?;
+
+    let power = |prices: &[f64]| {
+        prices[begin..]
+            .iter()
+            .zip(&ema)
+            .map(|(price, ema)| price - ema)
+            .collect()
+    };
+
+    Ok(ElderRay {
+        bull_power: power(high),
+        bear_power: power(low),
+        begin,
+    })
+}
+
+/// Compute [Elder Ray](https://www.investopedia.com/terms/e/elderray.asp).
+///
+/// Same as [`elder_ray()`] but each power has as many values as there are
+/// candles.
+///
+/// Candles without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+pub fn elder_ray_padded(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    period: usize,
+) -> Result<ElderRay, Error> {
+    let elder_ray = elder_ray(high, low, close, period)?;
+
+    Ok(ElderRay {
+        bull_power: nan_padded(elder_ray.bull_power, elder_ray.begin, close.len()),
+        bear_power: nan_padded(elder_ray.bear_power, elder_ray.begin, close.len()),
+        begin: elder_ray.begin,
+    })
+}
+
+/// Compute [Elder Ray](https://www.investopedia.com/terms/e/elderray.asp).
+///
+/// Same as [`elder_ray()`] but returns the powers as the columns
+/// `"bull_power"` and `"bear_power"`.
+pub fn elder_ray_columns(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    period: usize,
+) -> Result<IndicatorOutput, Error> {
+    elder_ray(high, low, close, period).map(Into::into)
+}
+
+/// Compute the [Elder Impulse System](https://school.stockcharts.com/doku.php?id=chart_analysis:elder_impulse_system).
+///
+/// A candle is `1` (green) if both the EMA over `ema_period` and the MACD
+/// histogram rose from the previous candle, `-1` (red) if both fell and `0`
+/// (blue) otherwise. The classic parameters are `13` and `12`, `26`, `9`.
+///
+/// The slopes need a previous value of each, so `begin` is one more than the
+/// later `begin` of the EMA and the MACD.
+///
+/// Returns a tuple containing the list of impulses and the index of the first
+/// input to have an associated impulse.
+pub fn elder_impulse(
+    close: &[f64],
+    ema_period: usize,
+    macd_fast: usize,
+    macd_slow: usize,
+    macd_signal: usize,
+) -> Result<(Vec<i8>, usize), Error> {
+    let Output {
+        values: ema,
+        begin: ema_begin,
+    } = exponential_moving_average(close, Some(ema_period))?;
+    let macd = moving_average_convergence_divergence(
+        close,
+        Some(macd_fast),
+        Some(macd_slow),
+        Some(macd_signal),
+    )?;
+
+    let begin = ema_begin.max(macd.begin) + 1;
+    if close.len() <= begin {
+        return Err(Error::InsufficientData {
+            have: close.len(),
+            need: begin + 1,
+        });
+    }
+
+    let ema = &ema[begin - 1 - ema_begin..];
+    let histogram = &macd.histogram[begin - 1 - macd.begin..];
+
+    Ok((
+        ema.windows(2)
+            .zip(histogram.windows(2))
+            .map(|(ema, histogram)| {
+                if ema[0] < ema[1] && histogram[0] < histogram[1] {
+                    1
+                } else if ema[1] < ema[0] && histogram[1] < histogram[0] {
+                    -1
+                } else {
+                    0
+                }
+            })
+            .collect(),
+        begin,
+    ))
+}
+
+/// The positive and negative vortex returned by [`vortex_indicator()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vortex {
+    pub plus: Vec<f64>,
+    pub minus: Vec<f64>,
+    /// The index of the first candle to have an associated value.
+    pub begin: usize,
+}
+
+impl From<Vortex> for IndicatorOutput {
+    fn from(vortex: Vortex) -> Self {
+        Self {
+            columns: vec![
+                ("plus".to_string(), vortex.plus),
+                ("minus".to_string(), vortex.minus),
+            ],
+            begin: vortex.begin,
+        }
+    }
+}
+
+/// Compute the [Vortex Indicator](https://en.wikipedia.org/wiki/Vortex_indicator).
+///
+/// The positive vortex movement of a candle is the distance of its high from
+/// the previous low, the negative one that of its low from the previous high.
+/// VI+ and VI- are their sums over `period` divided by the sum of the true
+/// range over `period`. VI+ crossing above VI- marks the start of an uptrend,
+/// crossing below it that of a downtrend.
+///
+/// Like the true range the movements need a previous candle and start at `1`.
+/// The sums need another `period - 1` of them, so `begin` is `period`.
+///
+/// Returns [`Error::LengthMismatch`] if `high` or `low` differ in length from
+/// `close`.
+pub fn vortex_indicator(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    period: usize,
+) -> Result<Vortex, Error> {
+    check_aligned(&[close, high, low])?;
+
+    let Output {
+        values: true_range,
+        begin: true_range_begin,
+    } = true_range(high, low, close)?;
+
+    let movement = |from: &[f64], to: &[f64]| {
+        (true_range_begin..close.len())
+            .map(|index| (from[index] - to[index - 1]).abs())
+            .collect::<Vec<_>>()
+    };
+
+    let sum = |values: &[f64]| {
+        rolling_sum(values, Some(period)).map_err(|error| rebase_too_short(error, true_range_begin))
+    };
+
+    let true_range_sum = sum(&true_range)?;
+    let vortex = |movement: Vec<f64>| -> Result<Vec<f64>, Error> {
+        Ok(sum(&movement)?
+            .iter()
+            .zip(true_range_sum.iter())
+            .map(|(movement, true_range)| movement / true_range)
+            .collect())
+    };
+
+    Ok(Vortex {
+        plus: vortex(movement(high, low))?,
+        minus: vortex(movement(low, high))?,
+        begin: true_range_begin + true_range_sum.begin,
+    })
+}
+
+/// Compute the [Vortex Indicator](https://en.wikipedia.org/wiki/Vortex_indicator).
+///
+/// Same as [`vortex_indicator()`] but VI+ and VI- have as many values as
+/// there are candles.
+///
+/// Candles without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+pub fn vortex_indicator_padded(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    period: usize,
+) -> Result<Vortex, Error> {
+    let vortex = vortex_indicator(high, low, close, period)?;
+
+    Ok(Vortex {
+        plus: nan_padded(vortex.plus, vortex.begin, close.len()),
+        minus: nan_padded(vortex.minus, vortex.begin, close.len()),
+        begin: vortex.begin,
+    })
+}
+
+/// Compute the [Vortex Indicator](https://en.wikipedia.org/wiki/Vortex_indicator).
+///
+/// Same as [`vortex_indicator()`] but returns VI+ and VI- as the columns
+/// `"plus"` and `"minus"`.
+pub fn vortex_indicator_columns(
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+    period: usize,
+) -> Result<IndicatorOutput, Error> {
+    vortex_indicator(high, low, close, period).map(Into::into)
+}
+
+/// The Fisher line and its trigger returned by [`fisher_transform()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FisherTransform {
+    pub fisher: Vec<f64>,
+    /// The Fisher line lagged by one candle.
+    pub trigger: Vec<f64>,
+    /// The index of the first candle to have an associated value.
+    pub begin: usize,
+}
+
+impl From<FisherTransform> for IndicatorOutput {
+    fn from(fisher_transform: FisherTransform) -> Self {
+        Self {
+            columns: vec![
+                ("fisher".to_string(), fisher_transform.fisher),
+                ("trigger".to_string(), fisher_transform.trigger),
+            ],
+            begin: fisher_transform.begin,
+        }
+    }
+}
+
+/// Compute the [Fisher Transform](https://www.investopedia.com/terms/f/fisher-transform.asp).
+///
+/// Each candle's midpoint, `(high + low) / 2`, is normalized to `-1..1`
+/// between the lowest and highest midpoint over `period` and smoothed as
+/// `x = 0.33 * normalized + 0.67 * previous x`. The Fisher line is
+/// `0.5 * ln((1 + x) / (1 - x)) + 0.5 * previous Fisher`. It stretches
+/// values close to `±1`, which makes turning points stand out.
+///
+/// `x` is clamped to `±0.999` to keep the logarithm finite. A window without
+/// range normalizes to `0`. The recursions start at `0` so the first trigger
+/// value is `0`.
+///
+/// The window needs `period` candles, so `begin` is `period - 1`.
+///
+/// Returns [`Error::LengthMismatch`] if `low` differs in length from `high`.
+pub fn fisher_transform(
+    high: &[f64],
+    low: &[f64],
+    period: usize,
+) -> Result<FisherTransform, Error> {
+    check_aligned(&[high, low])?;
+
+    let midpoint = high
+        .iter()
+        .zip(low)
+        .map(|(high, low)| (high + low) / 2.0)
+        .collect::<Vec<_>>();
+
+    let Output {
+        values: highest,
+        begin,
+    } = rolling_max(&midpoint, Some(period))?;
+    let Output { values: lowest, .. } = rolling_min(&midpoint, Some(period))?;
+
+    let mut x = 0.0;
+    let mut fisher = 0.0;
+    let mut trigger = Vec::with_capacity(highest.len());
+
+    let fisher = midpoint[begin..]
+        .iter()
+        .zip(highest.iter().zip(&lowest))
+        .map(|(midpoint, (highest, lowest))| {
+            let range = highest - lowest;
+            let normalized = if 0.0 < range {
+                2.0 * ((midpoint - lowest) / range - 0.5)
+            } else {
+                0.0
+            };
+
+            x = (0.33 * normalized + 0.67 * x).clamp(-0.999, 0.999);
+
+            trigger.push(fisher);
+            fisher = 0.5 * ((1.0 + x) / (1.0 - x)).ln() + 0.5 * fisher;
+
+            fisher
+        })
+        .collect();
+
+    Ok(FisherTransform {
+        fisher,
+        trigger,
+        begin,
+    })
+}
+
+/// Compute the [Fisher Transform](https://www.investopedia.com/terms/f/fisher-transform.asp).
+///
+/// Same as [`fisher_transform()`] but both lines have as many values as there
+/// are candles.
+///
+/// Candles without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+pub fn fisher_transform_padded(
+    high: &[f64],
+    low: &[f64],
+    period: usize,
+) -> Result<FisherTransform, Error> {
+    let fisher_transform = fisher_transform(high, low, period)?;
+
+    Ok(FisherTransform {
+        fisher: nan_padded(fisher_transform.fisher, fisher_transform.begin, high.len()),
+        trigger: nan_padded(fisher_transform.trigger, fisher_transform.begin, high.len()),
+        begin: fisher_transform.begin,
+    })
+}
+
+/// Compute the [Fisher Transform](https://www.investopedia.com/terms/f/fisher-transform.asp).
+///
+/// Same as [`fisher_transform()`] but returns the lines as the columns
+/// `"fisher"` and `"trigger"`.
+pub fn fisher_transform_columns(
+    high: &[f64],
+    low: &[f64],
+    period: usize,
+) -> Result<IndicatorOutput, Error> {
+    fisher_transform(high, low, period).map(Into::into)
+}
+
+#[test]
+fn test_coppock_curve() {
+    let input = (0..40)
+        .map(|index| 100.0 + index as f64 + (index as f64 * 0.7).sin())
+        .collect::<Vec<_>>();
+
+    let (values, begin) = coppock_curve(&input, 14, 11, 10).unwrap();
+
+    assert_eq!(
+        crate::rate_of_change_lookback(Some(14)).unwrap()
+            + crate::weighted_moving_average_lookback(Some(10)).unwrap(),
+        begin
+    );
+    assert_eq!(input.len() - begin, values.len());
+
+    // The first value is the WMA of the first ten sums.
+    let sums = (14..24)
+        .map(|index| {
+            (input[index] / input[index - 14] - 1.0) * 100.0
+                + (input[index] / input[index - 11] - 1.0) * 100.0
+        })
+        .collect::<Vec<_>>();
+    let expected = sums
+        .iter()
+        .enumerate()
+        .map(|(index, sum)| (index + 1) as f64 * sum)
+        .sum::<f64>()
+        / 55.0;
+
+    assert!((expected - values[0]).abs() < 1e-9);
+
+    assert!(matches!(
+        coppock_curve(&input[..20], 14, 11, 10),
+        Err(Error::InsufficientData { have: 20, need: 24 })
+    ));
+}
+
+#[test]
+fn test_chaikin_volatility() {
+    // A constant range for 30 candles, then one widening by 5% each candle.
+    let low = vec![100.0; 60];
+    let high = (0..60)
+        .map(|index| {
+            101.0
+                + if index < 30 {
+                    0.0
+                } else {
+                    1.05f64.powi(index - 29) - 1.0
+                }
+        })
+        .collect::<Vec<_>>();
+
+    let (values, begin) = chaikin_volatility(&high, &low, 10, 10).unwrap();
+
+    assert_eq!(
+        crate::exponential_moving_average_lookback(Some(10)).unwrap()
+            + crate::rate_of_change_lookback(Some(10)).unwrap(),
+        begin
+    );
+    assert_eq!(high.len() - begin, values.len());
+
+    // No change in volatility while the range is constant.
+    for (index, value) in crate::indexed(&values, begin) {
+        if index < 30 {
+            assert!(value.abs() < 1e-9);
+        }
+    }
+
+    // It rises once the range expands.
+    let expanding = &values[40 - begin..];
+    assert!(expanding.iter().all(|value| 0.0 < *value));
+    assert!(expanding.windows(2).all(|values| values[0] < values[1]));
+
+    assert!(matches!(
+        chaikin_volatility(&high[..19], &low[..19], 10, 10),
+        Err(Error::InsufficientData { have: 19, need: 20 })
+    ));
+    assert!(matches!(
+        chaikin_volatility(&high, &low[1..], 10, 10),
+        Err(Error::LengthMismatch {
+            expected: 60,
+            actual: 59
+        })
+    ));
+}
+
+#[test]
+fn test_williams_ad() {
+    // Up for 10 candles, then down for 10.
+    let close = (0..20)
+        .map(|index| 100.0 + 10.0 - (index as f64 - 10.0).abs())
+        .collect::<Vec<_>>();
+    let high = close.iter().map(|close| close + 0.5).collect::<Vec<_>>();
+    let low = close.iter().map(|close| close - 0.5).collect::<Vec<_>>();
+
+    let (values, begin) = williams_ad(&high, &low, &close).unwrap();
+
+    assert_eq!(1, begin);
+    assert_eq!(close.len() - begin, values.len());
+
+    // Up closes are above the previous close, the true low of each candle.
+    assert_eq!(1.0, values[0]);
+
+    for (index, value) in crate::indexed(&values, begin).skip(1) {
+        let previous = values[index - begin - 1];
+
+        if close[index - 1] < close[index] {
+            assert!(previous < value);
+        } else {
+            assert!(value < previous);
+        }
+    }
+
+    assert!(matches!(
+        williams_ad(&high[..1], &low[..1], &close[..1]),
+        Err(Error::InsufficientData { have: 1, need: 2 })
+    ));
+    assert!(matches!(
+        williams_ad(&high, &low[1..], &close),
+        Err(Error::LengthMismatch {
+            expected: 20,
+            actual: 19
+        })
+    ));
+
+    let mut gap = low.clone();
+    gap[5] = f64::NAN;
+    assert!(matches!(
+        williams_ad(&high, &gap, &close),
+        Err(Error::InvalidInput { index: 5 })
+    ));
+}
+
+#[test]
+fn test_elder_ray() {
+    // A strong uptrend: each close is well above the EMA trailing it.
+    let close = (0..40)
+        .map(|index| 100.0 + 2.0 * index as f64)
+        .collect::<Vec<_>>();
+    let high = close.iter().map(|close| close + 1.0).collect::<Vec<_>>();
+    let low = close.iter().map(|close| close - 1.0).collect::<Vec<_>>();
+
+    let ray = elder_ray(&high, &low, &close, 13).unwrap();
+    let Output { values: ema, begin } = exponential_moving_average(&close, Some(13)).unwrap();
+
+    assert_eq!(begin, ray.begin);
+    assert_eq!(close.len() - begin, ray.bull_power.len());
+    assert_eq!(close.len() - begin, ray.bear_power.len());
+    assert!(ray.bull_power.iter().all(|power| 0.0 <= *power));
+    assert_eq!(high[begin] - ema[0], ray.bull_power[0]);
+    assert_eq!(low[begin] - ema[0], ray.bear_power[0]);
+
+    let padded = elder_ray_padded(&high, &low, &close, 13).unwrap();
+    assert!(padded.bull_power[..begin]
+        .iter()
+        .all(|power| power.is_nan()));
+    assert_eq!(ray.bear_power, padded.bear_power[begin..]);
+
+    assert!(matches!(
+        elder_ray(&high[1..], &low, &close, 13),
+        Err(Error::LengthMismatch {
+            expected: 40,
+            actual: 39
+        })
+    ));
+}
+
+#[test]
+fn test_vortex_indicator() {
+    // Up for 30 candles, then down, with some noise.
+    let close = (0..60)
+        .map(|index| 130.0 - (index as f64 - 30.0).abs() + (index as f64 * 1.3).sin() * 1.5)
+        .collect::<Vec<_>>();
+    let high = close.iter().map(|close| close + 1.0).collect::<Vec<_>>();
+    let low = close.iter().map(|close| close - 1.0).collect::<Vec<_>>();
+
+    let vortex = vortex_indicator(&high, &low, &close, 14).unwrap();
+
+    assert_eq!(14, vortex.begin);
+    assert_eq!(close.len() - vortex.begin, vortex.plus.len());
+    assert_eq!(close.len() - vortex.begin, vortex.minus.len());
+    assert!(vortex
+        .plus
+        .iter()
+        .chain(&vortex.minus)
+        .all(|value| 0.0 < *value));
+
+    // The first value covers the movements of candles 1 to 14.
+    let sum = |f: &dyn Fn(usize) -> f64| (1..=14).map(f).sum::<f64>();
+    let true_range = sum(&|index| {
+        (high[index] - low[index])
+            .max((high[index] - close[index - 1]).abs())
+            .max((low[index] - close[index - 1]).abs())
+    });
+
+    assert!(
+        (sum(&|index| (high[index] - low[index - 1]).abs()) / true_range - vortex.plus[0]).abs()
+            < 1e-12
+    );
+    assert!(
+        (sum(&|index| (low[index] - high[index - 1]).abs()) / true_range - vortex.minus[0]).abs()
+            < 1e-12
+    );
+
+    // VI+ crosses below VI- once, after the top.
+    let crossings = (1..vortex.plus.len())
+        .filter(|index| {
+            (vortex.plus[index - 1] > vortex.minus[index - 1])
+                != (vortex.plus[*index] > vortex.minus[*index])
+        })
+        .map(|index| vortex.begin + index)
+        .collect::<Vec<_>>();
+
+    assert_eq!(1, crossings.len());
+    assert!((31..31 + 14).contains(&crossings[0]));
+    assert!(vortex.plus.last() < vortex.minus.last());
+
+    let padded = vortex_indicator_padded(&high, &low, &close, 14).unwrap();
+    assert!(padded.plus[..14].iter().all(|value| value.is_nan()));
+    assert_eq!(vortex.minus, padded.minus[14..]);
+
+    assert!(matches!(
+        vortex_indicator(&high[..14], &low[..14], &close[..14], 14),
+        Err(Error::InsufficientData { have: 14, need: 15 })
+    ));
+    assert!(matches!(
+        vortex_indicator(&high, &low[1..], &close, 14),
+        Err(Error::LengthMismatch {
+            expected: 60,
+            actual: 59
+        })
+    ));
+}
+
+#[test]
+fn test_fisher_transform() {
+    let midpoint = (0..100)
+        .map(|index| 100.0 + 10.0 * (index as f64 * std::f64::consts::PI / 20.0).sin())
+        .collect::<Vec<_>>();
+    let high = midpoint
+        .iter()
+        .map(|midpoint| midpoint + 1.0)
+        .collect::<Vec<_>>();
+    let low = midpoint
+        .iter()
+        .map(|midpoint| midpoint - 1.0)
+        .collect::<Vec<_>>();
+
+    let FisherTransform {
+        fisher,
+        trigger,
+        begin,
+    } = fisher_transform(&high, &low, 10).unwrap();
+
+    assert_eq!(9, begin);
+    assert_eq!(high.len() - begin, fisher.len());
+    assert_eq!(0.0, trigger[0]);
+    assert_eq!(&fisher[..fisher.len() - 1], &trigger[1..]);
+
+    // The normalized midpoint stays within ±1, the Fisher line goes well
+    // beyond it at the extremes.
+    let Output {
+        values: highest, ..
+    } = rolling_max(&midpoint, Some(10)).unwrap();
+    let Output { values: lowest, .. } = rolling_min(&midpoint, Some(10)).unwrap();
+    let normalized = midpoint[begin..]
+        .iter()
+        .zip(highest.iter().zip(&lowest))
+        .map(|(midpoint, (highest, lowest))| 2.0 * ((midpoint - lowest) / (highest - lowest) - 0.5))
+        .collect::<Vec<_>>();
+
+    let extreme = |values: &[f64]| {
+        values
+            .iter()
+            .fold(0.0f64, |max, value| max.max(value.abs()))
+    };
+    assert!(extreme(&normalized) <= 1.0);
+    assert!(2.0 < extreme(&fisher));
+    assert!(fisher.iter().all(|value| value.is_finite()));
+
+    // The series peaks at 50 and bottoms out at 70.
+    assert!(0.0 < fisher[48 - begin]);
+    assert!(fisher[68 - begin] < 0.0);
+
+    assert!(matches!(
+        fisher_transform(&high, &low[1..], 10),
+        Err(Error::LengthMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_elder_impulse() {
+    // An accelerating uptrend, so the MACD histogram keeps rising too.
+    let close = (0..100)
+        .map(|index| 100.0 * 1.01f64.powi(index))
+        .collect::<Vec<_>>();
+
+    let (impulses, begin) = elder_impulse(&close, 13, 12, 26, 9).unwrap();
+
+    assert_eq!(34, begin);
+    assert_eq!(close.len() - begin, impulses.len());
+    assert!(impulses.iter().all(|impulse| 1 == *impulse));
+
+    // The mirrored downtrend.
+    let falling = close.iter().map(|close| 1000.0 - close).collect::<Vec<_>>();
+    let (impulses, _) = elder_impulse(&falling, 13, 12, 26, 9).unwrap();
+    assert!(impulses.iter().all(|impulse| -1 == *impulse));
+
+    assert!(matches!(
+        elder_impulse(&close[..34], 13, 12, 26, 9),
+        Err(Error::InsufficientData { have: 34, need: 35 })
+    ));
+}
+
+#[test]
+fn test_adaptive_rsi() {
+    // A cycle of 20 inputs, i.e. an RSI over 10.
+    let close = (0..200)
+        .map(|index| 100.0 + 5.0 * (index as f64 * std::f64::consts::PI / 10.0).sin())
+        .collect::<Vec<_>>();
+
+    let (cycle_periods, cycle_begin) = hilbert_dominant_cycle_period(&close).unwrap();
+    let (values, begin) = adaptive_rsi(&close).unwrap();
+
+    assert_eq!(cycle_begin, begin);
+    assert_eq!(cycle_periods.len(), values.len());
+    assert!(values.iter().all(|value| (0.0..=100.0).contains(value)));
+
+    // Each value is that of the RSI with the period at its input.
+    for (index, (cycle_period, value)) in cycle_periods.iter().zip(&values).enumerate() {
+        let period = ((cycle_period / 2.0).round() as usize).max(2);
+        let rsi = relative_strength_index(&close, Some(period)).unwrap();
+
+        assert_eq!(rsi.values[begin + index - rsi.begin], *value);
+    }
+
+    // Once the cycle is detected, the RSI is that over half of it.
+    let index = close.len() - 1;
+    let rsi = relative_strength_index(&close, Some(10)).unwrap();
+    assert!((cycle_periods[index - begin] - 20.0).abs() < 1.0);
+    assert_eq!(rsi.values.last(), values.last());
+}
+
+#[test]
+fn test_percentage_volume_oscillator() {
+    let volume = (0..60)
+        .map(|index| 1000.0 + 300.0 * (index as f64 * 0.4).sin() + 10.0 * index as f64)
+        .collect::<Vec<_>>();
+
+    let Pvo {
+        pvo,
+        signal,
+        histogram,
+        begin,
+    } = percentage_volume_oscillator(&volume, 12, 26, 9).unwrap();
+
+    assert_eq!(25 + 8, begin);
+    assert_eq!(volume.len() - begin, pvo.len());
+    assert_eq!(pvo.len(), signal.len());
+    assert_eq!(pvo.len(), histogram.len());
+
+    // The PVO line is the PPO of the volume.
+    let Output {
+        values: ppo,
+        begin: ppo_begin,
+    } = percentage_price_oscillator(
+        &volume,
+        Some(12),
+        Some(26),
+        Some(MovingAverageType::ExponentialMovingAverage),
+    )
+    .unwrap();
+    for (ppo, pvo) in ppo[begin - ppo_begin..].iter().zip(&pvo) {
+        assert!((ppo - pvo).abs() < 1e-9);
+    }
+
+    let Output { values: ema, .. } = exponential_moving_average(&ppo, Some(9)).unwrap();
+    assert_eq!(ema, signal);
+    assert!((pvo[0] - signal[0] - histogram[0]).abs() < 1e-12);
+
+    let mut negative = volume.clone();
+    negative[7] = -1.0;
+    assert!(matches!(
+        percentage_volume_oscillator(&negative, 12, 26, 9),
+        Err(Error::InvalidInput { index: 7 })
+    ));
+
+    assert!(matches!(
+        percentage_volume_oscillator(&volume[..30], 12, 26, 9),
+        Err(Error::InsufficientData { have: 30, need: 34 })
+    ));
+}
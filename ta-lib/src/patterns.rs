@@ -0,0 +1,298 @@
+//! Candlestick pattern recognition.
+use crate::{compute_by_name, Error};
+
+/// The candlestick pattern functions of TA-Lib.
+pub const CANDLESTICK_PATTERNS: [&str; 61] = [
+    "CDL2CROWS",
+    "CDL3BLACKCROWS",
+    "CDL3INSIDE",
+    "CDL3LINESTRIKE",
+    "CDL3OUTSIDE",
+    "CDL3STARSINSOUTH",
+    "CDL3WHITESOLDIERS",
+    "CDLABANDONEDBABY",
+    "CDLADVANCEBLOCK",
+    "CDLBELTHOLD",
+    "CDLBREAKAWAY",
+    "CDLCLOSINGMARUBOZU",
+    "CDLCONCEALBABYSWALL",
+    "CDLCOUNTERATTACK",
+    "CDLDARKCLOUDCOVER",
+    "CDLDOJI",
+    "CDLDOJISTAR",
+    "CDLDRAGONFLYDOJI",
+    "CDLENGULFING",
+    "CDLEVENINGDOJISTAR",
+    "CDLEVENINGSTAR",
+    "CDLGAPSIDESIDEWHITE",
+    "CDLGRAVESTONEDOJI",
+    "CDLHAMMER",
+    "CDLHANGINGMAN",
+    "CDLHARAMI",
+    "CDLHARAMICROSS",
+    "CDLHIGHWAVE",
+    "CDLHIKKAKE",
+    "CDLHIKKAKEMOD",
+    "CDLHOMINGPIGEON",
+    "CDLIDENTICAL3CROWS",
+    "CDLINNECK",
+    "CDLINVERTEDHAMMER",
+    "CDLKICKING",
+    "CDLKICKINGBYLENGTH",
+    "CDLLADDERBOTTOM",
+    "CDLLONGLEGGEDDOJI",
+    "CDLLONGLINE",
+    "CDLMARUBOZU",
+    "CDLMATCHINGLOW",
+    "CDLMATHOLD",
+    "CDLMORNINGDOJISTAR",
+    "CDLMORNINGSTAR",
+    "CDLONNECK",
+    "CDLPIERCING",
+    "CDLRICKSHAWMAN",
+    "CDLRISEFALL3METHODS",
+    "CDLSEPARATINGLINES",
+    "CDLSHOOTINGSTAR",
+    "CDLSHORTLINE",
+    "CDLSPINNINGTOP",
+    "CDLSTALLEDPATTERN",
+    "CDLSTICKSANDWICH",
+    "CDLTAKURI",
+    "CDLTASUKIGAP",
+    "CDLTHRUSTING",
+    "CDLTRISTAR",
+    "CDLUNIQUE3RIVER",
+    "CDLUPSIDEGAP2CROWS",
+    "CDLXSIDEGAP3METHODS",
+];
+
+/// The direction of a detected candlestick pattern.
+///
+/// Patterns without a direction, e.g. a doji, are reported as
+/// [`CandleSignal::Bullish`], like TA-Lib reports them as positive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CandleSignal {
+    Bullish,
+    Bearish,
+}
+
+/// Compute the candlestick pattern function `name`, e.g. `"CDLDOJI"`, of
+/// [`CANDLESTICK_PATTERNS`].
+///
+/// TA-Lib scores each candle `100` for a bullish, `-100` for a bearish and
+/// `0` for no pattern. `CDLHIKKAKE` and `CDLHIKKAKEMOD` score confirmations
+/// of earlier patterns `200` and `-200`.
+///
+/// Returns [`Error::BadParam`] if `name` is not a candlestick pattern.
+///
+/// Returns a tuple containing the list of scores and the index of the first
+/// candle to have an associated score.
+pub fn candlestick_pattern(
+    name: &str,
+    open: &[f64],
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+) -> Result<(Vec<i32>, usize), Error> {
+    if !CANDLESTICK_PATTERNS.contains(&name) {
+        return Err(Error::BadParam(format!(
+            "{} is not a candlestick pattern.",
+            name
+        )));
+    }
+
+    let output = compute_by_name(name, &[open, high, low, close], &[])?;
+
+    Ok((
+        output.columns[0]
+            .1
+            .iter()
+            .map(|score| *score as i32)
+            .collect(),
+        output.begin,
+    ))
+}
+
+/// Compute the candlestick pattern function `name`, e.g. `"CDLDOJI"`, of
+/// [`CANDLESTICK_PATTERNS`].
+///
+/// Same as [`candlestick_pattern()`] but divides the scores by `100`. This
+/// gives `1.0` for a bullish, `-1.0` for a bearish and `0.0` for no pattern,
+/// e.g. for blending with other signals. The confirmations of e.g.
+/// `"CDLHIKKAKE"`, scored `200`, are clamped to `1.0` and `-1.0` too.
+pub fn candlestick_pattern_normalized(
+    name: &str,
+    open: &[f64],
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+) -> Result<(Vec<f64>, usize), Error> {
+    let (scores, begin) = candlestick_pattern(name, open, high, low, close)?;
+
+    Ok((
+        scores
+            .into_iter()
+            .map(|score| (score as f64 / 100.0).clamp(-1.0, 1.0))
+            .collect(),
+        begin,
+    ))
+}
+
+/// Run every candlestick pattern function over the candles and collect the
+/// hits.
+///
+/// Returns the index of the candle completing each pattern, the name of the
+/// pattern function, e.g. `"CDLENGULFING"`, and its direction. The hits are
+/// ordered by index and then by the order of [`CANDLESTICK_PATTERNS`].
+///
+/// Patterns needing more candles than there are and those the linked C
+/// library does not provide are skipped.
+pub fn scan_candlesticks(
+    open: &[f64],
+    high: &[f64],
+    low: &[f64],
+    close: &[f64],
+) -> Result<Vec<(usize, &'static str, CandleSignal)>, Error> {
+    let mut hits = Vec::new();
+
+    for pattern in CANDLESTICK_PATTERNS {
+        let output = match compute_by_name(pattern, &[open, high, low, close], &[]) {
+            Ok(output) => output,
+            Err(Error::InsufficientData { .. } | Error::Unsupported { .. }) => continue,
+            Err(error) => return Err(error),
+        };
+
+        hits.extend(
+            output.columns[0]
+                .1
+                .iter()
+                .enumerate()
+                .filter(|(_, value)| 0.0 != **value)
+                .map(|(index, value)| {
+                    (
+                        output.begin + index,
+                        pattern,
+                        if 0.0 < *value {
+                            CandleSignal::Bullish
+                        } else {
+                            CandleSignal::Bearish
+                        },
+                    )
+                }),
+        );
+    }
+
+    // Stable, so patterns at the same index keep their order.
+    hits.sort_by_key(|(index, _, _)| *index);
+
+    Ok(hits)
+}
+
+#[test]
+fn test_scan_candlesticks() {
+    let [open, high, low, close] = test_candles();
+
+    let hits = scan_candlesticks(&open, &high, &low, &close).unwrap();
+
+    assert!(hits.contains(&(13, "CDLENGULFING", CandleSignal::Bullish)));
+    assert!(hits.contains(&(14, "CDLDOJI", CandleSignal::Bullish)));
+    assert!(!hits
+        .iter()
+        .any(|(index, pattern, _)| "CDLDOJI" == *pattern && 14 != *index));
+    assert!(hits.windows(2).all(|hits| hits[0].0 <= hits[1].0));
+}
+
+#[cfg(test)]
+fn test_candles() -> [Vec<f64>; 4] {
+    // Alternating candles with bodies half their range.
+    let mut open = (0..12)
+        .map(|index| if 0 == index % 2 { 100.0 } else { 101.0 })
+        .collect::<Vec<f64>>();
+    let mut close = open.iter().map(|open| 201.0 - open).collect::<Vec<_>>();
+
+    // A bearish candle engulfed by a bullish one at 13.
+    open.extend([102.0, 100.5]);
+    close.extend([101.0, 102.5]);
+    // A doji at 14.
+    open.push(101.0);
+    close.push(101.0);
+
+    let high = open
+        .iter()
+        .zip(&close)
+        .map(|(open, close)| open.max(*close) + 0.5)
+        .collect::<Vec<_>>();
+    let low = open
+        .iter()
+        .zip(&close)
+        .map(|(open, close)| open.min(*close) - 0.5)
+        .collect::<Vec<_>>();
+
+    [open, high, low, close]
+}
+
+#[test]
+fn test_candlestick_pattern() {
+    let [open, high, low, close] = test_candles();
+
+    let (scores, begin) = candlestick_pattern("CDLENGULFING", &open, &high, &low, &close).unwrap();
+    let (normalized, normalized_begin) =
+        candlestick_pattern_normalized("CDLENGULFING", &open, &high, &low, &close).unwrap();
+
+    assert_eq!(begin, normalized_begin);
+    assert_eq!(100, scores[13 - begin]);
+    assert_eq!(scores.len(), normalized.len());
+
+    for (score, normalized) in scores.iter().zip(&normalized) {
+        assert_eq!(*score as f64 / 100.0, *normalized);
+        assert!((-1.0..=1.0).contains(normalized));
+    }
+
+    assert!(matches!(
+        candlestick_pattern("SMA", &open, &high, &low, &close),
+        Err(Error::BadParam(_))
+    ));
+}
+
+#[test]
+fn test_candlestick_pattern_normalized_confirmation() {
+    // An inside candle at 6, broken downwards at 7 for a bullish Hikkake and
+    // confirmed by closing above the inside candle's high at 8.
+    let high = [
+        110.0, 110.0, 110.0, 110.0, 110.0, 108.0, 105.0, 104.0, 107.0,
+    ];
+    let low = [90.0, 90.0, 90.0, 90.0, 90.0, 92.0, 95.0, 94.0, 100.0];
+    let mut close = high
+        .iter()
+        .zip(&low)
+        .map(|(high, low)| (high + low) / 2.0)
+        .collect::<Vec<_>>();
+    close[8] = 106.0;
+
+    let assert_clamped = |high: &[f64], low: &[f64], close: &[f64], direction: i32| {
+        let (scores, begin) = candlestick_pattern("CDLHIKKAKE", close, high, low, close).unwrap();
+        let (normalized, _) =
+            candlestick_pattern_normalized("CDLHIKKAKE", close, high, low, close).unwrap();
+
+        assert_eq!(
+            [100 * direction, 200 * direction],
+            scores[7 - begin..=8 - begin]
+        );
+        assert_eq!(
+            [direction as f64, direction as f64],
+            normalized[7 - begin..=8 - begin]
+        );
+        assert!(normalized.iter().all(|value| (-1.0..=1.0).contains(value)));
+    };
+
+    assert_clamped(&high, &low, &close, 1);
+
+    // The mirrored candles give the bearish pattern and confirmation.
+    let mirrored = |values: &[f64]| {
+        values
+            .iter()
+            .map(|value| 1000.0 - value)
+            .collect::<Vec<_>>()
+    };
+    assert_clamped(&mirrored(&low), &mirrored(&high), &mirrored(&close), -1);
+}
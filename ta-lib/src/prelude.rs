@@ -0,0 +1,25 @@
+//! The most commonly used types and functions.
+//!
+//! ```
+//! use ta_lib::prelude::*;
+//!
+//! let close = (0..30)
+//!     .map(|index| 100.0 + (index as f64 * 0.4).sin())
+//!     .collect::<Vec<_>>();
+//!
+//! let sma = simple_moving_average(&close, Some(10))?;
+//! let rsi = relative_strength_index(&close, Some(14))?;
+//!
+//! assert_eq!(close.len(), sma.begin + sma.len());
+//! assert_eq!(close.len(), rsi.begin + rsi.len());
+//! # Ok::<(), Error>(())
+//! ```
+#[cfg(not(feature = "pure_rust"))]
+pub use crate::{
+    average_true_range, bollinger_bands, moving_average, moving_average_convergence_divergence,
+    stochastic, BollingerBands, Indicator, Macd, MovingAverageType, Stochastic,
+};
+pub use crate::{
+    exponential_moving_average, indexed, nan_padded, relative_strength_index,
+    simple_moving_average, Candles, Error, IndicatorOutput, Output, Series,
+};
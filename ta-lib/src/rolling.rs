@@ -0,0 +1,701 @@
+//! Windowed statistics implemented in Rust, or composed from wrapped TA
+//! functions, that the C library does not provide.
+//!
+//! These follow the same conventions as the wrapped TA functions: they return
+//! the values for every complete trailing window plus the index of the first
+//! input to have an associated value.
+#[cfg(not(feature = "pure_rust"))]
+use crate::{
+    check_aligned, multiply, nan_padded, pearson_correlation, rolling_sum, simple_moving_average,
+    standard_deviation, IndicatorOutput, Output,
+};
+use crate::{check_lookback, check_range, checked_period, Error};
+
+/// The population standard deviation below which a window has no spread.
+///
+/// Note that TA-Lib's [`standard_deviation()`](crate::standard_deviation)
+/// reports zero already for variances below `1e-8`, i.e. for standard
+/// deviations below `1e-4`.
+const MIN_STANDARD_DEVIATION: f64 = 0.00000001;
+
+/// Apply `f` to each trailing window of `period` values.
+///
+/// This computes windowed statistics neither TA-Lib nor this crate provide.
+///
+/// Returns a tuple containing the list of values `f` returned and the index of
+/// the first input to have an associated value, i.e. `period - 1`.
+///
+/// ```
+/// # use ta_lib::*;
+/// let input = vec![1.0, 3.0, 2.0, 5.0];
+///
+/// let (ranges, begin) = rolling_apply(&input, 2, |window| {
+///     window.iter().cloned().fold(f64::MIN, f64::max)
+///         - window.iter().cloned().fold(f64::MAX, f64::min)
+/// })
+/// .unwrap();
+///
+/// assert_eq!(vec![2.0, 1.0, 3.0], ranges);
+/// assert_eq!(1, begin);
+/// ```
+pub fn rolling_apply(
+    input: &[f64],
+    period: usize,
+    f: impl Fn(&[f64]) -> f64,
+) -> Result<(Vec<f64>, usize), Error> {
+    check_range(0, input.len())?;
+
+    trace_span!("rolling_apply", input.len(), period);
+
+    let period = checked_period(period, 1)?;
+
+    check_lookback(input.len(), (period - 1) as _)?;
+
+    Ok((input.windows(period).map(f).collect(), period - 1))
+}
+
+/// Compute the percentage rank of each value within the trailing window of
+/// `period` values ending at it.
+///
+/// The rank is the fraction of the window's values strictly below the current
+/// value. It is `0.0` for the smallest value of the window and `(period - 1) /
+/// period` for the largest.
+///
+/// Returns a tuple containing the list of ranks and the index of the first
+/// input to have an associated rank.
+pub fn rolling_percent_rank(input: &[f64], period: usize) -> Result<(Vec<f64>, usize), Error> {
+    check_range(0, input.len())?;
+
+    let period = checked_period(period, 1)?;
+
+    check_lookback(input.len(), (period - 1) as _)?;
+
+    Ok((
+        input
+            .windows(period)
+            .map(|window| {
+                let current = window[period - 1];
+                window.iter().filter(|value| **value < current).count() as f64 / period as f64
+            })
+            .collect(),
+        period - 1,
+    ))
+}
+
+/// Compute the `q` quantile of the trailing window of `period` values ending
+/// at each value, e.g. the 5th percentile for a `q` of `0.05`.
+///
+/// The quantile is linearly interpolated between the two closest values of
+/// the sorted window, like `numpy.quantile()` does by default. A `q` of `0.0`
+/// gives the window's minimum and a `q` of `1.0` its maximum.
+///
+/// Returns [`Error::BadParam`] unless `0.0 <= q <= 1.0`.
+///
+/// Returns a tuple containing the list of quantiles and the index of the first
+/// input to have an associated quantile.
+pub fn rolling_quantile(input: &[f64], period: usize, q: f64) -> Result<(Vec<f64>, usize), Error> {
+    if !(0.0..=1.0).contains(&q) {
+        return Err(Error::BadParam(format!(
+            "Quantile {} is outside of 0.0..=1.0.",
+            q
+        )));
+    }
+
+    rolling_apply(input, period, |window| {
+        let mut sorted = window.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        let position = q * (period - 1) as f64;
+        let below = position.floor() as usize;
+        let above = position.ceil() as usize;
+
+        sorted[below] + (sorted[above] - sorted[below]) * (position - below as f64)
+    })
+}
+
+/// Compute the skewness of the trailing window of `period` values ending at
+/// each value.
+///
+/// This is the population skewness `m3 / m2^1.5`, where `mk` is the `k`th
+/// central moment of the window. Each window is computed in two passes, first
+/// its mean, then the moments around it, which avoids the cancellation the
+/// one-pass formula from raw power sums suffers from. It is `f64::NAN` where
+/// the window has no spread, i.e. where its standard deviation is below
+/// `1e-8`.
+///
+/// Returns a tuple containing the list of skewness values and the index of the
+/// first input to have an associated value.
+pub fn rolling_skewness(input: &[f64], period: usize) -> Result<(Vec<f64>, usize), Error> {
+    rolling_apply(input, period, |window| {
+        let (m2, m3, _) = central_moments(window);
+
+        if m2.sqrt() < MIN_STANDARD_DEVIATION {
+            f64::NAN
+        } else {
+            m3 / m2.powf(1.5)
+        }
+    })
+}
+
+/// Compute the excess kurtosis of the trailing window of `period` values
+/// ending at each value.
+///
+/// This is the population excess kurtosis `m4 / m2^2 - 3`, where `mk` is the
+/// `k`th central moment of the window, so that of a normal distribution is
+/// `0`. It is computed in two passes and is `f64::NAN` for windows without
+/// spread, see [`rolling_skewness()`].
+///
+/// Returns a tuple containing the list of kurtosis values and the index of the
+/// first input to have an associated value.
+pub fn rolling_kurtosis(input: &[f64], period: usize) -> Result<(Vec<f64>, usize), Error> {
+    rolling_apply(input, period, |window| {
+        let (m2, _, m4) = central_moments(window);
+
+        if m2.sqrt() < MIN_STANDARD_DEVIATION {
+            f64::NAN
+        } else {
+            m4 / (m2 * m2) - 3.0
+        }
+    })
+}
+
+/// The second, third and fourth central moments of `window`.
+fn central_moments(window: &[f64]) -> (f64, f64, f64) {
+    let len = window.len() as f64;
+    let mean = window.iter().sum::<f64>() / len;
+
+    let (m2, m3, m4) = window.iter().fold((0.0, 0.0, 0.0), |(m2, m3, m4), value| {
+        let deviation = value - mean;
+        let square = deviation * deviation;
+        (m2 + square, m3 + square * deviation, m4 + square * square)
+    });
+
+    (m2 / len, m3 / len, m4 / len)
+}
+
+/// Compute the z-score of each value relative to the mean and standard
+/// deviation of the trailing window of `period` values ending at it.
+///
+/// The z-score is `(value - mean) / standard_deviation`, using the population
+/// standard deviation. It is `f64::NAN` where the window has no spread, i.e.
+/// where its standard deviation is below `1e-8`. TA-Lib treats variances
+/// below `1e-8` as zero already, so this is where its standard deviation is
+/// below `1e-4`.
+///
+/// Returns a tuple containing the list of z-scores and the index of the first
+/// input to have an associated z-score.
+#[cfg(not(feature = "pure_rust"))]
+pub fn rolling_zscore(input: &[f64], period: Option<usize>) -> Result<(Vec<f64>, usize), Error> {
+    let Output {
+        values: means,
+        begin: mean_begin,
+    } = simple_moving_average(input, period)?;
+    let (deviations, deviation_begin) = standard_deviation(input, period, None)?;
+
+    let begin = mean_begin.max(deviation_begin);
+
+    Ok((
+        input[begin..]
+            .iter()
+            .zip(&means[begin - mean_begin..])
+            .zip(&deviations[begin - deviation_begin..])
+            .map(|((value, mean), deviation)| {
+                if *deviation < MIN_STANDARD_DEVIATION {
+                    f64::NAN
+                } else {
+                    (value - mean) / deviation
+                }
+            })
+            .collect(),
+        begin,
+    ))
+}
+
+/// Compute the [Volume Weighted Average
+/// Price](https://en.wikipedia.org/wiki/Volume-weighted_average_price) over the
+/// trailing window of `period` candles.
+///
+/// This is `sum(typical_price * volume) / sum(volume)` of each window. It is
+/// `f64::NAN` for windows without any volume.
+///
+/// The prices are usually the [`typical_price()`](crate::typical_price) of
+/// the candles, see also
+/// [`Candles::typical_price()`](crate::Candles::typical_price).
+///
+/// Returns [`Error::LengthMismatch`] if `volume` differs in length from
+/// `typical_price`.
+///
+/// Returns a tuple containing the list of VWAP values and the index of the
+/// first candle to have an associated VWAP value.
+#[cfg(not(feature = "pure_rust"))]
+pub fn vwap(
+    typical_price: &[f64],
+    volume: &[f64],
+    period: usize,
+) -> Result<(Vec<f64>, usize), Error> {
+    check_aligned(&[typical_price, volume])?;
+
+    let (turnover, _) = multiply(typical_price, volume)?;
+    let Output {
+        values: turnover_sums,
+        begin,
+    } = rolling_sum(&turnover, Some(period))?;
+    let Output {
+        values: volume_sums,
+        ..
+    } = rolling_sum(volume, Some(period))?;
+
+    Ok((
+        turnover_sums
+            .iter()
+            .zip(&volume_sums)
+            .map(|(turnover_sum, volume_sum)| {
+                if 0.0 == *volume_sum {
+                    f64::NAN
+                } else {
+                    turnover_sum / volume_sum
+                }
+            })
+            .collect(),
+        begin,
+    ))
+}
+
+/// Compute the [`pearson_correlation()`] of each pair of `series` over the
+/// trailing window of `period` values.
+///
+/// Element `[i][j]` of the result holds the correlations of `series[i]` and
+/// `series[j]` and their `begin`. The matrix is symmetric, each pair is only
+/// computed once.
+///
+/// Returns [`Error::LengthMismatch`] unless all series have the same length.
+#[cfg(not(feature = "pure_rust"))]
+#[allow(clippy::type_complexity)]
+pub fn correlation_matrix(
+    series: &[&[f64]],
+    period: usize,
+) -> Result<Vec<Vec<(Vec<f64>, usize)>>, Error> {
+    check_aligned(series)?;
+
+    let mut matrix: Vec<Vec<(Vec<f64>, usize)>> = Vec::with_capacity(series.len());
+
+    for (row, a) in series.iter().enumerate() {
+        let mut correlations = Vec::with_capacity(series.len());
+
+        for (column, b) in series.iter().enumerate() {
+            correlations.push(if column < row {
+                matrix[column][row].clone()
+            } else {
+                pearson_correlation(a, b, Some(period))?
+            });
+        }
+
+        matrix.push(correlations);
+    }
+
+    Ok(matrix)
+}
+
+/// The rolling alpha and beta returned by [`rolling_alpha_beta()`].
+#[cfg(not(feature = "pure_rust"))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlphaBeta {
+    pub alpha: Vec<f64>,
+    pub beta: Vec<f64>,
+    /// The index of the first input to have an associated value.
+    pub begin: usize,
+}
+
+#[cfg(not(feature = "pure_rust"))]
+impl From<AlphaBeta> for IndicatorOutput {
+    fn from(alpha_beta: AlphaBeta) -> Self {
+        Self {
+            columns: vec![
+                ("alpha".to_string(), alpha_beta.alpha),
+                ("beta".to_string(), alpha_beta.beta),
+            ],
+            begin: alpha_beta.begin,
+        }
+    }
+}
+
+/// Compute the beta and alpha of `asset` relative to `market` over the
+/// trailing window of `period` values, as in the
+/// [CAPM](https://en.wikipedia.org/wiki/Capital_asset_pricing_model).
+///
+/// The beta is the [`pearson_correlation()`] of the two times the ratio of
+/// their [`standard_deviation()`]s. The alpha is the mean of `asset` minus beta
+/// times the mean of `market`. Both are `f64::NAN` for windows in which
+/// `market` is constant, i.e. where its standard deviation is below `1e-8`,
+/// or below `1e-4` as TA-Lib computes it, see [`rolling_zscore()`].
+///
+/// The inputs are usually returns, not prices.
+///
+/// Returns [`Error::LengthMismatch`] unless both inputs have the same length.
+#[cfg(not(feature = "pure_rust"))]
+pub fn rolling_alpha_beta(
+    asset: &[f64],
+    market: &[f64],
+    period: usize,
+) -> Result<AlphaBeta, Error> {
+    check_aligned(&[asset, market])?;
+
+    let (correlation, begin) = pearson_correlation(asset, market, Some(period))?;
+    let (asset_std, _) = standard_deviation(asset, Some(period), Some(1.0))?;
+    let (market_std, _) = standard_deviation(market, Some(period), Some(1.0))?;
+    let Output {
+        values: asset_mean, ..
+    } = simple_moving_average(asset, Some(period))?;
+    let Output {
+        values: market_mean,
+        ..
+    } = simple_moving_average(market, Some(period))?;
+
+    let beta = correlation
+        .iter()
+        .zip(asset_std.iter().zip(&market_std))
+        .map(|(correlation, (asset_std, market_std))| {
+            if *market_std < MIN_STANDARD_DEVIATION {
+                f64::NAN
+            } else {
+                correlation * asset_std / market_std
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Ok(AlphaBeta {
+        alpha: beta
+            .iter()
+            .zip(asset_mean.iter().zip(&market_mean))
+            .map(|(beta, (asset_mean, market_mean))| asset_mean - beta * market_mean)
+            .collect(),
+        beta,
+        begin,
+    })
+}
+
+/// Same as [`rolling_alpha_beta()`] but alpha and beta have as many values as
+/// there are inputs.
+///
+/// Inputs without an associated value, i.e. those before `begin`, get
+/// `f64::NAN`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn rolling_alpha_beta_padded(
+    asset: &[f64],
+    market: &[f64],
+    period: usize,
+) -> Result<AlphaBeta, Error> {
+    let alpha_beta = rolling_alpha_beta(asset, market, period)?;
+
+    Ok(AlphaBeta {
+        alpha: nan_padded(alpha_beta.alpha, alpha_beta.begin, asset.len()),
+        beta: nan_padded(alpha_beta.beta, alpha_beta.begin, asset.len()),
+        begin: alpha_beta.begin,
+    })
+}
+
+/// Same as [`rolling_alpha_beta()`] but returns alpha and beta as the columns
+/// `"alpha"` and `"beta"`.
+#[cfg(not(feature = "pure_rust"))]
+pub fn rolling_alpha_beta_columns(
+    asset: &[f64],
+    market: &[f64],
+    period: usize,
+) -> Result<IndicatorOutput, Error> {
+    rolling_alpha_beta(asset, market, period).map(Into::into)
+}
+
+/// Compute the [Sharpe ratio](https://en.wikipedia.org/wiki/Sharpe_ratio) of
+/// `returns` over the trailing window of `period` values.
+///
+/// This is the mean of each window minus `risk_free`, divided by the
+/// population standard deviation of the window. Both are per period, e.g. per
+/// day for daily returns, and the ratio is not annualized. It is `f64::NAN`
+/// for windows without volatility, i.e. where the standard deviation is below
+/// `1e-8`.
+///
+/// The variance of each window is computed in two passes, like that of
+/// [`rolling_skewness()`]. Unlike TA-Lib's, it keeps the small variances of
+/// e.g. daily returns.
+///
+/// Returns [`Error::BadParam`] if `period` is less than `2`.
+///
+/// Returns a tuple containing the list of Sharpe ratios and the index of the
+/// first input to have an associated value.
+pub fn rolling_sharpe(
+    returns: &[f64],
+    period: usize,
+    risk_free: f64,
+) -> Result<(Vec<f64>, usize), Error> {
+    checked_period(period, 2)?;
+
+    rolling_apply(returns, period, |window| {
+        let mean = window.iter().sum::<f64>() / window.len() as f64;
+        let (m2, _, _) = central_moments(window);
+        let deviation = m2.sqrt();
+
+        if deviation < MIN_STANDARD_DEVIATION {
+            f64::NAN
+        } else {
+            (mean - risk_free) / deviation
+        }
+    })
+}
+
+#[test]
+fn test_rolling_apply() {
+    let input = vec![5.0, 1.0, 4.0, 2.0, 3.0, 9.0, 0.0];
+
+    let (medians, begin) = rolling_apply(&input, 3, |window| {
+        let mut window = window.to_vec();
+        window.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        window[window.len() / 2]
+    })
+    .unwrap();
+
+    assert_eq!(2, begin);
+    assert_eq!(vec![4.0, 2.0, 3.0, 3.0, 3.0], medians);
+
+    assert!(matches!(
+        rolling_apply(&input, 8, |window| window[0]),
+        Err(Error::InsufficientData { have: 7, need: 8 })
+    ));
+}
+
+#[test]
+fn test_rolling_percent_rank() {
+    let input = (0..50).map(|x| x as f64).collect::<Vec<_>>();
+
+    let (ranks, begin) = rolling_percent_rank(&input, 20).unwrap();
+
+    assert_eq!(19, begin);
+    assert_eq!(input.len() - begin, ranks.len());
+    // Every value is the largest of its window.
+    for rank in ranks {
+        assert_eq!(19.0 / 20.0, rank);
+    }
+
+    let (ranks, _) = rolling_percent_rank(&[3.0, 1.0, 2.0, 0.0], 3).unwrap();
+
+    assert_eq!(vec![1.0 / 3.0, 0.0], ranks);
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_rolling_zscore() {
+    let mut input = (0..40)
+        .map(|index| 100.0 + (index % 2) as f64)
+        .collect::<Vec<_>>();
+    input[30] = 110.0;
+
+    let (scores, begin) = rolling_zscore(&input, Some(10)).unwrap();
+
+    assert_eq!(9, begin);
+    assert_eq!(input.len() - begin, scores.len());
+
+    for (index, score) in crate::indexed(&scores, begin) {
+        if 30 == index {
+            assert!(2.5 < score);
+        } else {
+            assert!(score.abs() < 2.5);
+        }
+    }
+
+    let (scores, _) = rolling_zscore(&[5.0; 10], Some(5)).unwrap();
+
+    assert!(scores.iter().all(|score| score.is_nan()));
+
+    // A standard deviation of 5e-5 is a variance below the threshold.
+    let input = (0..10)
+        .map(|index| 100.0 + 0.0001 * (index % 2) as f64)
+        .collect::<Vec<_>>();
+    let (scores, _) = rolling_zscore(&input, Some(4)).unwrap();
+
+    assert!(scores.iter().all(|score| score.is_nan()));
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_vwap() {
+    let typical_price = vec![10.0, 11.0, 12.0, 13.0, 14.0];
+    let volume = vec![1.0, 3.0, 0.0, 0.0, 2.0];
+
+    let (values, begin) = vwap(&typical_price, &volume, 2).unwrap();
+
+    assert_eq!(1, begin);
+    assert_eq!(4, values.len());
+    // (10 * 1 + 11 * 3) / (1 + 3)
+    assert!((values[0] - 10.75).abs() < 1e-9);
+    // 11 * 3 / 3
+    assert!((values[1] - 11.0).abs() < 1e-9);
+    // No volume at all.
+    assert!(values[2].is_nan());
+    assert!((values[3] - 14.0).abs() < 1e-9);
+
+    assert_eq!(
+        Err(Error::LengthMismatch {
+            expected: 5,
+            actual: 4
+        }),
+        vwap(&typical_price, &volume[1..], 2)
+    );
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_correlation_matrix() {
+    let a = (0..30).map(|x| (x as f64 * 0.4).sin()).collect::<Vec<_>>();
+    let b = (0..30).map(|x| (x as f64 * 0.4).cos()).collect::<Vec<_>>();
+    let c = (0..30)
+        .map(|x| x as f64 + (x as f64).sin())
+        .collect::<Vec<_>>();
+
+    let matrix = correlation_matrix(&[&a, &b, &c], 10).unwrap();
+
+    assert_eq!(3, matrix.len());
+
+    for (row, correlations) in matrix.iter().enumerate() {
+        assert_eq!(3, correlations.len());
+
+        let (diagonal, begin) = &correlations[row];
+        assert_eq!(9, *begin);
+        assert!(diagonal.iter().all(|value| (value - 1.0).abs() < 1e-6));
+
+        for (column, correlation) in correlations.iter().enumerate() {
+            assert_eq!(&matrix[column][row], correlation);
+        }
+    }
+
+    assert_eq!(pearson_correlation(&a, &c, Some(10)).unwrap(), matrix[0][2]);
+
+    assert!(matches!(
+        correlation_matrix(&[&a, &b[..20]], 10),
+        Err(Error::LengthMismatch {
+            expected: 30,
+            actual: 20
+        })
+    ));
+}
+
+#[test]
+fn test_rolling_skewness_kurtosis() {
+    // Every window is a permutation of 1..=5, a discrete uniform distribution
+    // with a skewness of 0 and an excess kurtosis of -6 (n² + 1) / 5 (n² - 1).
+    let input = (0..20).map(|x| (x % 5 + 1) as f64).collect::<Vec<_>>();
+
+    let (skewness, begin) = rolling_skewness(&input, 5).unwrap();
+
+    assert_eq!(4, begin);
+    assert_eq!(16, skewness.len());
+    assert!(skewness.iter().all(|value| value.abs() < 1e-12));
+
+    let (kurtosis, begin) = rolling_kurtosis(&input, 5).unwrap();
+
+    assert_eq!(4, begin);
+    assert!(kurtosis
+        .iter()
+        .all(|value| (value + 6.0 * 26.0 / (5.0 * 24.0)).abs() < 1e-12));
+
+    // A long right tail.
+    let (skewness, _) = rolling_skewness(&[1.0, 1.0, 1.0, 1.0, 10.0], 5).unwrap();
+    assert!(0.0 < skewness[0]);
+
+    let (kurtosis, _) = rolling_kurtosis(&[2.0; 5], 5).unwrap();
+    assert!(kurtosis[0].is_nan());
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_rolling_alpha_beta() {
+    let market = (0..40)
+        .map(|index| (index as f64 * 0.8).sin() * 0.01)
+        .collect::<Vec<_>>();
+    let asset = market.iter().map(|market| 2.0 * market).collect::<Vec<_>>();
+
+    let alpha_beta = rolling_alpha_beta(&asset, &market, 10).unwrap();
+
+    assert_eq!(9, alpha_beta.begin);
+    assert_eq!(market.len() - 9, alpha_beta.beta.len());
+    assert!(alpha_beta.beta.iter().all(|beta| (beta - 2.0).abs() < 1e-6));
+    assert!(alpha_beta.alpha.iter().all(|alpha| alpha.abs() < 1e-9));
+
+    let constant = vec![1.0; 40];
+    let alpha_beta = rolling_alpha_beta(&asset, &constant, 10).unwrap();
+    assert!(alpha_beta.beta.iter().all(|beta| beta.is_nan()));
+
+    assert!(matches!(
+        rolling_alpha_beta(&asset[1..], &market, 10),
+        Err(Error::LengthMismatch {
+            expected: 39,
+            actual: 40
+        })
+    ));
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_rolling_quantile() {
+    let close_prices = crate::SAMPLE_PRICES;
+
+    assert_eq!(
+        crate::rolling_min(close_prices, Some(5)).unwrap(),
+        Output::from(rolling_quantile(&close_prices, 5, 0.0).unwrap())
+    );
+    assert_eq!(
+        crate::rolling_max(close_prices, Some(5)).unwrap(),
+        Output::from(rolling_quantile(&close_prices, 5, 1.0).unwrap())
+    );
+
+    // Halfway between the two middle values of each window of four.
+    let (medians, begin) = rolling_quantile(&[4.0, 1.0, 3.0, 2.0, 8.0], 4, 0.5).unwrap();
+    assert_eq!(3, begin);
+    assert_eq!(vec![2.5, 2.5], medians);
+
+    for q in [-0.1, 1.1, f64::NAN] {
+        assert!(matches!(
+            rolling_quantile(&close_prices, 5, q),
+            Err(Error::BadParam(_))
+        ));
+    }
+}
+
+#[test]
+fn test_rolling_sharpe() {
+    // A steady 1% return with a little noise.
+    let returns = (0..40)
+        .map(|index| 0.01 + if index % 2 == 0 { 0.001 } else { -0.001 })
+        .collect::<Vec<_>>();
+
+    let (sharpe, begin) = rolling_sharpe(&returns, 10, 0.0).unwrap();
+
+    assert_eq!(9, begin);
+    assert_eq!(returns.len() - begin, sharpe.len());
+    assert!(sharpe.iter().all(|sharpe| (sharpe - 10.0).abs() < 1e-6));
+
+    // The risk free rate eats half of it.
+    let (sharpe, _) = rolling_sharpe(&returns, 10, 0.005).unwrap();
+    assert!(sharpe.iter().all(|sharpe| (sharpe - 5.0).abs() < 1e-6));
+
+    // Zero mean returns.
+    let returns = (0..40)
+        .map(|index| (index as f64 * 0.9).sin() * 0.02)
+        .collect::<Vec<_>>();
+    let (sharpe, _) = rolling_sharpe(&returns, 20, 0.0).unwrap();
+    assert!(sharpe.iter().all(|sharpe| sharpe.abs() < 0.5));
+
+    // Constant returns have no volatility.
+    let (sharpe, _) = rolling_sharpe(&[0.01; 20], 10, 0.0).unwrap();
+    assert!(sharpe.iter().all(|sharpe| sharpe.is_nan()));
+
+    // A standard deviation of 5e-5, whose variance TA-Lib treats as zero.
+    let returns = (0..20)
+        .map(|index| 0.001 + if index % 2 == 0 { 0.00005 } else { -0.00005 })
+        .collect::<Vec<_>>();
+    let (sharpe, _) = rolling_sharpe(&returns, 10, 0.0).unwrap();
+    assert!(sharpe.iter().all(|sharpe| (sharpe - 20.0).abs() < 1e-6));
+
+    assert!(matches!(
+        rolling_sharpe(&returns, 1, 0.0),
+        Err(Error::BadParam(_))
+    ));
+}
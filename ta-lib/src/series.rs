@@ -0,0 +1,355 @@
+//! Values with their `begin` and chaining computations over them.
+use crate::{
+    exponential_moving_average, indexed, nan_padded, rebase_too_short, relative_strength_index,
+    simple_moving_average, weighted_moving_average, Error,
+};
+#[cfg(not(feature = "pure_rust"))]
+use crate::{rate_of_change, rolling_zscore};
+use std::ops::Deref;
+
+/// Values together with the index of the input the first one belongs to.
+///
+/// The wrappers of TA-Lib's functions with a single output return this. It
+/// dereferences to its values, so they can be iterated or indexed directly.
+///
+/// ```
+/// # use ta_lib::*;
+/// let close = vec![1.0, 2.0, 3.0, 4.0];
+///
+/// let sma = simple_moving_average(&close, Some(2))?;
+///
+/// assert_eq!(1, sma.begin);
+/// assert_eq!(3, sma.len());
+/// assert_eq!(Some(&3.5), sma.last());
+/// assert!(sma.aligned(close.len())[0].is_nan());
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    pub values: Vec<f64>,
+    /// The index of the input the first of `values` belongs to.
+    pub begin: usize,
+}
+
+/// An [`Output`] to compute further indicators over.
+///
+/// Each method computes an indicator over the values and adds its `begin` to
+/// that of `self`, so the result's `begin` is always an index into the
+/// original input.
+///
+/// ```
+/// # use ta_lib::*;
+/// let close = (0..20).map(|close| close as f64).collect::<Vec<_>>();
+///
+/// let series = Series::new(close).sma(5)?.ema(3)?;
+///
+/// // SMA(5) needs 4 warm-up values and the EMA(3) of that another 2.
+/// assert_eq!(6, series.begin);
+/// # Ok::<(), Error>(())
+/// ```
+///
+/// Any `(values, begin)` tuple returned by this crate converts into a
+/// `Series`.
+///
+/// ```
+/// # use ta_lib::*;
+/// let close = (0..20).map(|close| close as f64).collect::<Vec<_>>();
+///
+/// let rank = Series::from(rolling_percent_rank(&close, 5)?).sma(3)?;
+///
+/// assert_eq!(4 + 2, rank.begin);
+/// # Ok::<(), Error>(())
+/// ```
+pub type Series = Output;
+
+impl Output {
+    /// A series of raw input values, i.e. with a `begin` of `0`.
+    pub fn new(values: Vec<f64>) -> Self {
+        Self { values, begin: 0 }
+    }
+
+    /// The values padded with leading and trailing `f64::NAN`s so there is
+    /// one for each of the `len` inputs.
+    ///
+    /// See [`nan_padded()`].
+    pub fn aligned(&self, len: usize) -> Vec<f64> {
+        nan_padded(self.values.clone(), self.begin, len)
+    }
+
+    /// Iterate over the values with the index of the input each belongs to.
+    ///
+    /// See [`indexed()`].
+    pub fn indexed(&self) -> impl Iterator<Item = (usize, f64)> + '_ {
+        indexed(&self.values, self.begin)
+    }
+
+    /// Transform each value with `f`, e.g. to clamp, round or scale them.
+    ///
+    /// `begin` is unchanged.
+    pub fn map(self, f: impl Fn(f64) -> f64) -> Self {
+        Self {
+            values: self.values.into_iter().map(f).collect(),
+            begin: self.begin,
+        }
+    }
+
+    /// Add `begin` to a result computed over `self.values`.
+    fn chain(&self, output: impl Into<Output>) -> Self {
+        let Output { values, begin } = output.into();
+
+        Self {
+            values,
+            begin: self.begin + begin,
+        }
+    }
+
+    /// The [`simple_moving_average()`] of the values.
+    pub fn sma(&self, period: usize) -> Result<Self, Error> {
+        Ok(self.chain(simple_moving_average(&self.values, Some(period))?))
+    }
+
+    /// The [`exponential_moving_average()`] of the values.
+    pub fn ema(&self, period: usize) -> Result<Self, Error> {
+        Ok(self.chain(exponential_moving_average(&self.values, Some(period))?))
+    }
+
+    /// The [`rolling_zscore()`] of the values.
+    #[cfg(not(feature = "pure_rust"))]
+    pub fn zscore(&self, period: usize) -> Result<Self, Error> {
+        Ok(self.chain(rolling_zscore(&self.values, Some(period))?))
+    }
+}
+
+impl Deref for Output {
+    type Target = [f64];
+
+    fn deref(&self) -> &[f64] {
+        &self.values
+    }
+}
+
+impl From<(Vec<f64>, usize)> for Output {
+    fn from((values, begin): (Vec<f64>, usize)) -> Self {
+        Self { values, begin }
+    }
+}
+
+impl From<Output> for (Vec<f64>, usize) {
+    fn from(output: Output) -> Self {
+        (output.values, output.begin)
+    }
+}
+
+/// A single series operation of a [`Pipeline`], with its period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Step {
+    /// [`simple_moving_average()`]
+    Sma(usize),
+    /// [`exponential_moving_average()`]
+    Ema(usize),
+    /// [`weighted_moving_average()`]
+    Wma(usize),
+    /// [`relative_strength_index()`]
+    Rsi(usize),
+    /// [`rate_of_change()`]
+    #[cfg(not(feature = "pure_rust"))]
+    Roc(usize),
+    /// [`rolling_zscore()`]
+    #[cfg(not(feature = "pure_rust"))]
+    ZScore(usize),
+}
+
+impl Step {
+    fn compute(self, input: &[f64]) -> Result<Output, Error> {
+        match self {
+            Step::Sma(period) => simple_moving_average(input, Some(period)),
+            Step::Ema(period) => exponential_moving_average(input, Some(period)),
+            Step::Wma(period) => weighted_moving_average(input, Some(period)),
+            Step::Rsi(period) => relative_strength_index(input, Some(period)),
+            #[cfg(not(feature = "pure_rust"))]
+            Step::Roc(period) => rate_of_change(input, Some(period)),
+            #[cfg(not(feature = "pure_rust"))]
+            Step::ZScore(period) => rolling_zscore(input, Some(period)).map(Output::from),
+        }
+    }
+}
+
+/// Indicators computed over each other's output, e.g. from a config.
+///
+/// Each [`Step`] consumes the values of the previous one. Like with
+/// [`Series`], the `begin` of the result is an index into the input.
+///
+/// ```
+/// # use ta_lib::*;
+/// let close = (0..30).map(|close| close as f64).collect::<Vec<_>>();
+///
+/// let wma = Pipeline::new(&close)
+///     .then(Step::Sma(10))
+///     .then(Step::Wma(5))
+///     .compute()?;
+///
+/// // SMA(10) needs 9 warm-up values and the WMA(5) of that another 4.
+/// assert_eq!(13, wma.begin);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pipeline<'a> {
+    input: &'a [f64],
+    steps: Vec<Step>,
+}
+
+impl<'a> Pipeline<'a> {
+    /// A pipeline without steps. Computing it gives the `input` as is.
+    pub fn new(input: &'a [f64]) -> Self {
+        Self {
+            input,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Append `step`, computed over the output of the steps before it.
+    pub fn then(mut self, step: Step) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// The steps in the order they are computed.
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Compute the steps in order.
+    ///
+    /// Should a step not get enough values, the resulting
+    /// [`Error::InsufficientData`] counts inputs, not the values of the step
+    /// before.
+    pub fn compute(&self) -> Result<Series, Error> {
+        let mut series = Series::new(self.input.to_vec());
+
+        for step in &self.steps {
+            series = series.chain(
+                step.compute(&series.values)
+                    .map_err(|error| rebase_too_short(error, series.begin))?,
+            );
+        }
+
+        Ok(series)
+    }
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_series() {
+    let close = (0..30)
+        .map(|index| 100.0 + (index as f64 * 0.5).sin())
+        .collect::<Vec<_>>();
+    let candles = crate::Candles::try_new(
+        close.clone(),
+        close.iter().map(|close| close + 1.0).collect(),
+        close.iter().map(|close| close - 1.0).collect(),
+        close,
+        Vec::new(),
+    )
+    .unwrap();
+
+    let series = candles.typical_price().unwrap().sma(5).unwrap();
+
+    // TYPPRICE has no warm-up, SMA(5) needs 4 values.
+    assert_eq!(4, series.begin);
+
+    let series = series.zscore(10).unwrap();
+
+    assert_eq!(4 + 9, series.begin);
+    assert_eq!(candles.len() - series.begin, series.values.len());
+}
+
+#[test]
+fn test_output() {
+    let close = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+
+    let sma = simple_moving_average(&close, Some(2)).unwrap();
+
+    assert_eq!(1, sma.begin);
+
+    // The values can be iterated directly.
+    let mut expected = vec![1.5, 2.5, 3.5, 4.5].into_iter();
+    for value in sma.iter() {
+        assert_eq!(expected.next(), Some(*value));
+    }
+    assert_eq!(None, expected.next());
+
+    assert_eq!(2.5, sma[1]);
+    assert_eq!(
+        (vec![1.5, 2.5, 3.5, 4.5], 1),
+        <(Vec<f64>, usize)>::from(sma)
+    );
+}
+
+#[test]
+fn test_series_deref() {
+    let close = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+
+    let sma = simple_moving_average(&close, Some(2)).unwrap();
+
+    assert_eq!(1, sma.begin);
+    assert_eq!(4, sma.len());
+    assert_eq!(
+        vec![1.5, 2.5, 3.5, 4.5],
+        sma.iter().cloned().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![(1, 1.5), (2, 2.5), (3, 3.5), (4, 4.5)],
+        sma.indexed().collect::<Vec<_>>()
+    );
+
+    let aligned = sma.aligned(close.len());
+
+    assert!(aligned[0].is_nan());
+    assert_eq!(&aligned[1..], &sma[..]);
+}
+
+#[test]
+fn test_series_map() {
+    let close = vec![1.0, 2.0, 4.0, 7.0, 11.0];
+
+    let sma = simple_moving_average(&close, Some(2)).unwrap();
+    let rounded = sma.clone().map(|value| value.round());
+
+    assert_eq!(sma.begin, rounded.begin);
+    assert_eq!(vec![1.5, 3.0, 5.5, 9.0], sma.values);
+    assert_eq!(vec![2.0, 3.0, 6.0, 9.0], rounded.values);
+}
+
+#[cfg(not(feature = "pure_rust"))]
+#[test]
+fn test_pipeline() {
+    let close = (0..40)
+        .map(|index| 100.0 + (index as f64 * 0.5).sin())
+        .collect::<Vec<_>>();
+
+    let pipeline = Pipeline::new(&close).then(Step::Sma(10)).then(Step::Roc(5));
+    let roc = pipeline.compute().unwrap();
+
+    assert_eq!(&[Step::Sma(10), Step::Roc(5)], pipeline.steps());
+    assert_eq!(
+        crate::simple_moving_average_lookback(Some(10)).unwrap()
+            + crate::rate_of_change_lookback(Some(5)).unwrap(),
+        roc.begin
+    );
+    assert_eq!(close.len() - roc.begin, roc.len());
+
+    let series = Series::new(close.clone()).sma(10).unwrap();
+    let expected = rate_of_change(&series.values, Some(5)).unwrap();
+    assert_eq!(expected.values, roc.values);
+
+    assert_eq!(close, Pipeline::new(&close).compute().unwrap().values);
+
+    // ROC(5) of SMA(36) needs 35 + 5 + 1 inputs.
+    assert!(matches!(
+        Pipeline::new(&close)
+            .then(Step::Sma(36))
+            .then(Step::Roc(5))
+            .compute(),
+        Err(Error::InsufficientData { have: 40, need: 41 })
+    ));
+}
@@ -0,0 +1,356 @@
+//! TA-Lib's global settings and scoped changes to them.
+//!
+//! The settings are process-global. Changing them affects all computations
+//! afterwards, on any thread. The setters, the scoped `with_*()` functions
+//! and [`reset_global_settings()`] are serialized. They can be called from
+//! within the closure of a `with_*()` function on the same thread.
+use crate::Error;
+use std::{
+    cell::Cell,
+    sync::{Mutex, MutexGuard},
+};
+use ta_lib_sys as ta;
+
+/// Serializes changes to the global settings so concurrent scoped overrides
+/// can not interleave.
+static SETTINGS: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    /// Whether this thread holds [`SETTINGS`] already, i.e. is inside the
+    /// closure of a `with_*()` function.
+    static HOLDS_SETTINGS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Holds [`SETTINGS`] until dropped, unless the thread did already when it
+/// was created.
+struct SettingsLock(Option<MutexGuard<'static, ()>>);
+
+impl Drop for SettingsLock {
+    fn drop(&mut self) {
+        if self.0.is_some() {
+            HOLDS_SETTINGS.with(|holds| holds.set(false));
+        }
+    }
+}
+
+/// Lock [`SETTINGS`] on this thread, reentrantly so the setters do not
+/// deadlock when called from within a `with_*()` closure.
+fn lock_settings() -> SettingsLock {
+    if HOLDS_SETTINGS.with(Cell::get) {
+        return SettingsLock(None);
+    }
+
+    // A panic in another closure leaves nothing inconsistent behind as the
+    // settings get restored on unwind.
+    let guard = SETTINGS.lock().unwrap_or_else(|error| error.into_inner());
+    HOLDS_SETTINGS.with(|holds| holds.set(true));
+
+    SettingsLock(Some(guard))
+}
+
+/// The functions with an unstable period.
+///
+/// See the [TA-Lib docs](https://ta-lib.org/d_api/ta_setunstableperiod.html)
+/// for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnstablePeriodFunction {
+    AverageDirectionalMovementIndex = ta::TA_FuncUnstId_TA_FUNC_UNST_ADX as _,
+    AverageDirectionalMovementIndexRating = ta::TA_FuncUnstId_TA_FUNC_UNST_ADXR as _,
+    AverageTrueRange = ta::TA_FuncUnstId_TA_FUNC_UNST_ATR as _,
+    ChandeMomentumOscillator = ta::TA_FuncUnstId_TA_FUNC_UNST_CMO as _,
+    DirectionalMovementIndex = ta::TA_FuncUnstId_TA_FUNC_UNST_DX as _,
+    ExponentialMovingAverage = ta::TA_FuncUnstId_TA_FUNC_UNST_EMA as _,
+    HilbertTransformDominantCyclePeriod = ta::TA_FuncUnstId_TA_FUNC_UNST_HT_DCPERIOD as _,
+    HilbertTransformDominantCyclePhase = ta::TA_FuncUnstId_TA_FUNC_UNST_HT_DCPHASE as _,
+    HilbertTransformPhasorComponents = ta::TA_FuncUnstId_TA_FUNC_UNST_HT_PHASOR as _,
+    HilbertTransformSineWave = ta::TA_FuncUnstId_TA_FUNC_UNST_HT_SINE as _,
+    HilbertTransformInstantaneousTrendline = ta::TA_FuncUnstId_TA_FUNC_UNST_HT_TRENDLINE as _,
+    HilbertTransformTrendVsCycleMode = ta::TA_FuncUnstId_TA_FUNC_UNST_HT_TRENDMODE as _,
+    KaufmanAdaptiveMovingAverage = ta::TA_FuncUnstId_TA_FUNC_UNST_KAMA as _,
+    MESAAdaptiveMovingAverage = ta::TA_FuncUnstId_TA_FUNC_UNST_MAMA as _,
+    MoneyFlowIndex = ta::TA_FuncUnstId_TA_FUNC_UNST_MFI as _,
+    NegativeDirectionalIndicator = ta::TA_FuncUnstId_TA_FUNC_UNST_MINUS_DI as _,
+    NegativeDirectionalMovement = ta::TA_FuncUnstId_TA_FUNC_UNST_MINUS_DM as _,
+    NormalizedAverageTrueRange = ta::TA_FuncUnstId_TA_FUNC_UNST_NATR as _,
+    PositiveDirectionalIndicator = ta::TA_FuncUnstId_TA_FUNC_UNST_PLUS_DI as _,
+    PositiveDirectionalMovement = ta::TA_FuncUnstId_TA_FUNC_UNST_PLUS_DM as _,
+    RelativeStrengthIndex = ta::TA_FuncUnstId_TA_FUNC_UNST_RSI as _,
+    StochasticRelativeStrengthIndex = ta::TA_FuncUnstId_TA_FUNC_UNST_STOCHRSI as _,
+    TripleGeneralizedDoubleExponentialMovingAverage = ta::TA_FuncUnstId_TA_FUNC_UNST_T3 as _,
+    /// All of the above.
+    All = ta::TA_FuncUnstId_TA_FUNC_UNST_ALL as _,
+}
+
+/// How TA-Lib computes the functions whose results differ between trading
+/// platforms.
+///
+/// Only [`relative_strength_index()`](crate::relative_strength_index) and
+/// CMO are affected. With [`Compatibility::Metastock`] RSI has one value more,
+/// i.e. its `begin` is one less. The values from there on are the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Compatibility {
+    Default = ta::TA_Compatibility_TA_COMPATIBILITY_DEFAULT as _,
+    Metastock = ta::TA_Compatibility_TA_COMPATIBILITY_METASTOCK as _,
+}
+
+/// The current compatibility mode.
+pub fn compatibility() -> Compatibility {
+    match unsafe { ta::TA_GetCompatibility() } {
+        ta::TA_Compatibility_TA_COMPATIBILITY_METASTOCK => Compatibility::Metastock,
+        _ => Compatibility::Default,
+    }
+}
+
+/// Set the compatibility mode.
+///
+/// Like the unstable period this is a global setting. It affects all
+/// functions computed afterwards, on any thread. Use [`with_compatibility()`]
+/// to change it only for a computation.
+pub fn set_compatibility(mode: Compatibility) {
+    let _lock = lock_settings();
+
+    unsafe { ta::TA_SetCompatibility(mode as _) };
+}
+
+/// Restores the saved compatibility mode when dropped, even if the closure
+/// passed to [`with_compatibility()`] panics.
+struct RestoreCompatibility(Compatibility);
+
+impl Drop for RestoreCompatibility {
+    fn drop(&mut self) {
+        unsafe { ta::TA_SetCompatibility(self.0 as _) };
+    }
+}
+
+/// Run `f` with the compatibility mode set to `mode`.
+///
+/// The previous mode is restored once `f` returns. The same caveats as for
+/// [`with_unstable_period()`] apply.
+pub fn with_compatibility<T>(mode: Compatibility, f: impl FnOnce() -> T) -> T {
+    let _lock = lock_settings();
+
+    let _restore = RestoreCompatibility(compatibility());
+
+    unsafe { ta::TA_SetCompatibility(mode as _) };
+
+    f()
+}
+
+/// Restores the saved unstable periods when dropped, even if the closure
+/// passed to [`with_unstable_period()`] panics.
+struct RestoreUnstablePeriods(Vec<(ta::TA_FuncUnstId, u32)>);
+
+impl Drop for RestoreUnstablePeriods {
+    fn drop(&mut self) {
+        for (id, period) in &self.0 {
+            unsafe { ta::TA_SetUnstablePeriod(*id, *period as _) };
+        }
+    }
+}
+
+/// The unstable period of every function until it is changed.
+///
+/// With it the functions output as soon as they have enough inputs for their
+/// formula, like e.g. `pandas-ta` does. Libraries that hold back the first
+/// values of RSI or CMO until they converge start later.
+pub const DEFAULT_UNSTABLE_PERIOD: usize = 0;
+
+/// The current unstable period of `function`.
+///
+/// For [`UnstablePeriodFunction::All`] this is always `0`.
+pub fn unstable_period(function: UnstablePeriodFunction) -> usize {
+    unsafe { ta::TA_GetUnstablePeriod(function as _) as _ }
+}
+
+/// The current unstable period of
+/// [`relative_strength_index()`](crate::relative_strength_index).
+///
+/// Each additional period moves its `begin` one input later.
+pub fn rsi_unstable_period() -> usize {
+    unstable_period(UnstablePeriodFunction::RelativeStrengthIndex)
+}
+
+/// The current unstable period of CMO, the Chande Momentum Oscillator.
+pub fn cmo_unstable_period() -> usize {
+    unstable_period(UnstablePeriodFunction::ChandeMomentumOscillator)
+}
+
+/// Set the unstable period of `function`, or of all functions for
+/// [`UnstablePeriodFunction::All`].
+///
+/// This is a global setting. It affects all functions computed afterwards, on
+/// any thread. Use [`with_unstable_period()`] to change it only for a
+/// computation.
+///
+/// Returns [`Error::BadParam`] if `period` does not fit TA-Lib's unsigned
+/// 32-bit setting.
+pub fn set_unstable_period(function: UnstablePeriodFunction, period: usize) -> Result<(), Error> {
+    let period = checked_unstable_period(period)?;
+    let _lock = lock_settings();
+
+    unsafe { ta::TA_SetUnstablePeriod(function as _, period) };
+
+    Ok(())
+}
+
+/// `period` as the unsigned 32-bit integer TA-Lib stores unstable periods in.
+fn checked_unstable_period(period: usize) -> Result<u32, Error> {
+    u32::try_from(period).map_err(|_| {
+        Error::BadParam(format!(
+            "The unstable period must be at most {} but is {}.",
+            u32::MAX,
+            period
+        ))
+    })
+}
+
+/// Run `f` with the unstable period of `function` set to `period`.
+///
+/// The previous unstable period is restored once `f` returns. Calls to this
+/// function are serialized so overrides from different threads do not leak
+/// into each other.
+///
+/// TA-Lib's settings are global. Functions called on other threads, outside
+/// of `with_unstable_period()`, still see the override while `f` runs.
+///
+/// `f` may change the settings itself, e.g. with a nested
+/// `with_unstable_period()` or [`set_unstable_period()`]. This does not
+/// deadlock as the calls on the thread running `f` are not serialized
+/// against it. The unstable period of `function` is restored regardless once
+/// `f` returns.
+///
+/// Returns [`Error::BadParam`], without calling `f`, if `period` does not fit
+/// TA-Lib's unsigned 32-bit setting.
+///
+/// # Examples
+/// ```
+/// use ta_lib::{average_true_range, with_unstable_period, UnstablePeriodFunction};
+///
+/// let close = (0..50)
+///     .map(|index| 100.0 + index as f64)
+///     .collect::<Vec<_>>();
+/// let high = close.iter().map(|close| close + 1.0).collect::<Vec<_>>();
+/// let low = close.iter().map(|close| close - 1.0).collect::<Vec<_>>();
+///
+/// let atr = with_unstable_period(UnstablePeriodFunction::AverageTrueRange, 10, || {
+///     average_true_range(&high, &low, &close, Some(10))
+/// })
+/// .unwrap()
+/// .unwrap();
+///
+/// assert_eq!(20, atr.begin);
+/// ```
+pub fn with_unstable_period<T>(
+    function: UnstablePeriodFunction,
+    period: usize,
+    f: impl FnOnce() -> T,
+) -> Result<T, Error> {
+    let period = checked_unstable_period(period)?;
+    let _lock = lock_settings();
+
+    let ids = if UnstablePeriodFunction::All == function {
+        (0..ta::TA_FuncUnstId_TA_FUNC_UNST_ALL).collect()
+    } else {
+        vec![function as ta::TA_FuncUnstId]
+    };
+
+    let _restore = RestoreUnstablePeriods(
+        ids.into_iter()
+            .map(|id| (id, unsafe { ta::TA_GetUnstablePeriod(id) } as _))
+            .collect(),
+    );
+
+    unsafe { ta::TA_SetUnstablePeriod(function as _, period) };
+
+    Ok(f())
+}
+
+/// Restore TA-Lib's defaults: no unstable period for any function, the
+/// default compatibility mode and the default candle settings of the
+/// candlestick pattern functions.
+///
+/// Like the setters this is global, e.g. for long-running applications and
+/// tests changing the settings one after another. Overrides of
+/// [`with_unstable_period()`] and [`with_compatibility()`] on other threads
+/// are not affected. They are serialized with this function.
+pub fn reset_global_settings() {
+    let _lock = lock_settings();
+
+    unsafe {
+        ta::TA_SetUnstablePeriod(
+            ta::TA_FuncUnstId_TA_FUNC_UNST_ALL,
+            DEFAULT_UNSTABLE_PERIOD as _,
+        );
+        ta::TA_SetCompatibility(ta::TA_Compatibility_TA_COMPATIBILITY_DEFAULT);
+        ta::TA_RestoreCandleDefaultSettings(ta::TA_CandleSettingType_TA_AllCandleSettings);
+    }
+}
+
+#[test]
+fn test_with_unstable_period() {
+    // Use a function nothing else in the test suite computes so concurrently
+    // running tests are not affected.
+    let function = UnstablePeriodFunction::MoneyFlowIndex;
+
+    let threads = [7, 13]
+        .into_iter()
+        .map(|period| {
+            std::thread::spawn(move || {
+                for _ in 0..100 {
+                    with_unstable_period(function, period, || {
+                        assert_eq!(period, unstable_period(function));
+                        std::thread::yield_now();
+                        assert_eq!(period, unstable_period(function));
+                    })
+                    .unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    assert_eq!(0, unstable_period(function));
+}
+
+#[test]
+fn test_with_unstable_period_nested() {
+    // Not computed anywhere in the test suite either.
+    let function = UnstablePeriodFunction::HilbertTransformPhasorComponents;
+
+    with_unstable_period(function, 3, || {
+        with_unstable_period(function, 5, || {
+            assert_eq!(5, unstable_period(function));
+        })
+        .unwrap();
+        assert_eq!(3, unstable_period(function));
+
+        set_unstable_period(function, 4).unwrap();
+        assert_eq!(4, unstable_period(function));
+    })
+    .unwrap();
+
+    assert_eq!(0, unstable_period(function));
+}
+
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn test_unstable_period_out_of_range() {
+    // The rejected periods leave the setting alone so the other tests are
+    // not affected.
+    let function = UnstablePeriodFunction::HilbertTransformSineWave;
+
+    for period in [u32::MAX as usize + 1, usize::MAX] {
+        assert!(matches!(
+            set_unstable_period(function, period),
+            Err(Error::BadParam(_))
+        ));
+        assert!(matches!(
+            with_unstable_period(function, period, || unreachable!()),
+            Err(Error::BadParam(_))
+        ));
+        assert_eq!(0, unstable_period(function));
+    }
+}
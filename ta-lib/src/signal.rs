@@ -0,0 +1,94 @@
+//! Trading signals derived from indicator values.
+use crate::{check_aligned, Error};
+
+/// The direction of a crossover detected by [`crossovers()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cross {
+    /// `fast` went from below to above `slow`.
+    Up,
+    /// `fast` went from above to below `slow`.
+    Down,
+}
+
+/// Find the indices where `fast` crosses `slow`, e.g. the MACD line crossing
+/// its signal or the close crossing a moving average.
+///
+/// The index is that of the first value on the new side. When the lines touch
+/// before crossing, the cross is reported once the lines part on the other
+/// side. Touching and returning to the same side is no cross. `f64::NAN`
+/// values are skipped.
+///
+/// Both slices must be aligned, i.e. `fast[i]` and `slow[i]` belong to the
+/// same input. For indicators with different `begin`s slice off the warm-up of
+/// the one starting earlier, e.g. with [`common_range()`](crate::common_range).
+/// Returns [`Error::LengthMismatch`] if they differ in length.
+///
+/// ```
+/// # use ta_lib::*;
+/// let fast = [1.0, 2.0, 3.0, 2.0, 1.0];
+/// let slow = [2.0, 2.0, 2.0, 2.5, 2.5];
+///
+/// assert_eq!(
+///     vec![(2, Cross::Up), (3, Cross::Down)],
+///     crossovers(&fast, &slow)?
+/// );
+/// # Ok::<(), Error>(())
+/// ```
+pub fn crossovers(fast: &[f64], slow: &[f64]) -> Result<Vec<(usize, Cross)>, Error> {
+    check_aligned(&[fast, slow])?;
+
+    Ok(crossings(fast.iter().copied().zip(slow.iter().copied())))
+}
+
+/// The crossovers of the pairs of `fast` and `slow` values, see
+/// [`crossovers()`].
+pub(crate) fn crossings(pairs: impl Iterator<Item = (f64, f64)>) -> Vec<(usize, Cross)> {
+    let mut crosses = Vec::new();
+    // Whether `fast` was above `slow` the last time they were apart.
+    let mut above = None;
+
+    for (index, (fast, slow)) in pairs.enumerate() {
+        let is_above = if fast > slow {
+            true
+        } else if fast < slow {
+            false
+        } else {
+            // Touching or `NaN`.
+            continue;
+        };
+
+        match above {
+            Some(false) if is_above => crosses.push((index, Cross::Up)),
+            Some(true) if !is_above => crosses.push((index, Cross::Down)),
+            _ => (),
+        }
+
+        above = Some(is_above);
+    }
+
+    crosses
+}
+
+#[test]
+fn test_crossovers() {
+    let fast = [1.0, 2.0, 3.0, 4.0, 3.0, 2.0, 1.0, 2.0, 3.0];
+    let slow = [2.5; 9];
+
+    assert_eq!(
+        vec![(2, Cross::Up), (5, Cross::Down), (8, Cross::Up)],
+        crossovers(&fast, &slow).unwrap()
+    );
+
+    // Touching without crossing is no cross, crossing after touching is one.
+    let fast = [1.0, 2.5, 1.0, 2.5, f64::NAN, 3.0];
+
+    assert_eq!(vec![(5, Cross::Up)], crossovers(&fast, &[2.5; 6]).unwrap());
+
+    assert_eq!(
+        Err(Error::LengthMismatch {
+            expected: 6,
+            actual: 5
+        }),
+        crossovers(&fast, &[2.5; 5])
+    );
+}
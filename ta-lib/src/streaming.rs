@@ -0,0 +1,303 @@
+//! Indicators computed incrementally, one value at a time.
+//!
+//! These are implemented in Rust and follow the exact arithmetic of the
+//! corresponding C functions, so feeding a series value by value yields the
+//! same results as the batch function computed over the whole series.
+//!
+//! The states assume the global TA-Lib settings are at their defaults, i.e. no
+//! unstable period and the default compatibility mode.
+//!
+//! With the `serde` feature the states can be serialized, e.g. to disk, and
+//! resumed later. They hold nothing but what is needed to compute the next
+//! value.
+use crate::{checked_period, Error};
+use std::collections::VecDeque;
+
+/// Incremental [Simple Moving Average](https://www.tadoc.org/indicator/SMA.htm).
+///
+/// Produces the same values as
+/// [`simple_moving_average()`](crate::simple_moving_average).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SmaState {
+    period: usize,
+    window: VecDeque<f64>,
+    total: f64,
+}
+
+impl SmaState {
+    /// Returns [`Error::BadParam`] for a `period` of zero or one beyond
+    /// `i32::MAX`, like the wrapped functions.
+    pub fn new(period: usize) -> Result<Self, Error> {
+        let period = checked_period(period, 1)?;
+
+        Ok(Self {
+            period,
+            window: VecDeque::with_capacity(period),
+            total: 0.0,
+        })
+    }
+
+    /// Add the next input `value`.
+    ///
+    /// Returns `None` until `period` values were added.
+    pub fn next(&mut self, value: f64) -> Option<f64> {
+        self.window.push_back(value);
+        self.total += value;
+
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        let sma = self.total / self.period as f64;
+        self.total -= self.window.pop_front().unwrap();
+
+        Some(sma)
+    }
+}
+
+/// Incremental [Exponential Moving Average](https://www.tadoc.org/indicator/EMA.htm).
+///
+/// Like TA-Lib, the EMA is seeded with the SMA of the first `period` values.
+///
+/// Produces the same values as
+/// [`exponential_moving_average()`](crate::exponential_moving_average).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EmaState {
+    period: usize,
+    count: usize,
+    /// The sum of the inputs during warm-up, the EMA after.
+    ema: f64,
+}
+
+impl EmaState {
+    /// Returns [`Error::BadParam`] for a `period` of zero or one beyond
+    /// `i32::MAX`, like the wrapped functions.
+    pub fn new(period: usize) -> Result<Self, Error> {
+        let period = checked_period(period, 1)?;
+
+        Ok(Self {
+            period,
+            count: 0,
+            ema: 0.0,
+        })
+    }
+
+    /// Add the next input `value`.
+    ///
+    /// Returns `None` until `period` values were added.
+    pub fn next(&mut self, value: f64) -> Option<f64> {
+        self.count += 1;
+
+        if self.count <= self.period {
+            self.ema += value;
+
+            if self.count < self.period {
+                return None;
+            }
+
+            self.ema /= self.period as f64;
+        } else {
+            let k = 2.0 / (self.period + 1) as f64;
+            self.ema += (value - self.ema) * k;
+        }
+
+        Some(self.ema)
+    }
+}
+
+/// Incremental [Relative Strength Index](https://www.tadoc.org/indicator/RSI.htm).
+///
+/// Produces the same values as
+/// [`relative_strength_index()`](crate::relative_strength_index).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RsiState {
+    period: usize,
+    previous: Option<f64>,
+    count: usize,
+    /// Wilder's average gain and loss. Sums during warm-up.
+    gain: f64,
+    loss: f64,
+}
+
+impl RsiState {
+    /// Returns [`Error::BadParam`] for a `period` below two or beyond
+    /// `i32::MAX`, like the wrapped function.
+    pub fn new(period: usize) -> Result<Self, Error> {
+        let period = checked_period(period, 2)?;
+
+        Ok(Self {
+            period,
+            previous: None,
+            count: 0,
+            gain: 0.0,
+            loss: 0.0,
+        })
+    }
+
+    /// Add the next input `value`.
+    ///
+    /// Returns `None` until `period + 1` values were added as the first change
+    /// needs a previous value.
+    pub fn next(&mut self, value: f64) -> Option<f64> {
+        let change = value - self.previous.replace(value)?;
+        let period = self.period as f64;
+
+        self.count += 1;
+
+        if self.count <= self.period {
+            if change < 0.0 {
+                self.loss -= change;
+            } else {
+                self.gain += change;
+            }
+
+            if self.count < self.period {
+                return None;
+            }
+
+            self.loss /= period;
+            self.gain /= period;
+        } else {
+            self.loss *= period - 1.0;
+            self.gain *= period - 1.0;
+
+            if change < 0.0 {
+                self.loss -= change;
+            } else {
+                self.gain += change;
+            }
+
+            self.loss /= period;
+            self.gain /= period;
+        }
+
+        let total = self.gain + self.loss;
+
+        // TA-Lib treats everything this close to zero as zero.
+        Some(if (-0.00000001..0.00000001).contains(&total) {
+            0.0
+        } else {
+            100.0 * (self.gain / total)
+        })
+    }
+}
+
+#[cfg(test)]
+use crate::{exponential_moving_average, relative_strength_index, simple_moving_average};
+#[cfg(test)]
+use proptest::prelude::*;
+
+#[cfg(test)]
+/// Assert that streaming `input` yields the same values at the same
+/// indices as the batch function.
+fn assert_equivalent(
+    input: &[f64],
+    mut next: impl FnMut(f64) -> Option<f64>,
+    batch: Result<crate::Output, crate::Error>,
+) {
+    let streamed = input
+        .iter()
+        .enumerate()
+        .filter_map(|(index, value)| next(*value).map(|value| (index, value)))
+        .collect::<Vec<_>>();
+
+    match batch {
+        Ok(crate::Output { values, begin }) => {
+            assert_eq!(values.len(), streamed.len());
+            assert_eq!(begin, streamed[0].0);
+
+            for (value, (_, streamed)) in values.iter().zip(&streamed) {
+                assert!(
+                    (value - streamed).abs() <= 1e-9 * value.abs().max(1.0),
+                    "{} != {}",
+                    value,
+                    streamed
+                );
+            }
+        }
+        Err(crate::Error::InsufficientData { .. }) => assert!(streamed.is_empty()),
+        Err(error) => panic!("{:?}", error),
+    }
+}
+
+#[cfg(test)]
+fn assert_all_equivalent(input: &[f64], period: usize) {
+    let mut sma = SmaState::new(period).unwrap();
+    assert_equivalent(
+        input,
+        |value| sma.next(value),
+        simple_moving_average(input, Some(period)),
+    );
+
+    let mut ema = EmaState::new(period).unwrap();
+    assert_equivalent(
+        input,
+        |value| ema.next(value),
+        exponential_moving_average(input, Some(period)),
+    );
+
+    let mut rsi = RsiState::new(period).unwrap();
+    assert_equivalent(
+        input,
+        |value| rsi.next(value),
+        relative_strength_index(input, Some(period)),
+    );
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn test_streaming_equivalence(
+        input in prop::collection::vec(1.0f64..1000.0, 1..200),
+        period in 2usize..30,
+    ) {
+        assert_all_equivalent(&input, period);
+    }
+
+    #[test]
+    fn test_streaming_equivalence_alternating(
+        base in 1.0f64..1000.0,
+        step in 0.0f64..10.0,
+        len in 1usize..200,
+        period in 2usize..30,
+    ) {
+        let input = (0..len)
+            .map(|index| if index % 2 == 0 { base } else { base + step })
+            .collect::<Vec<_>>();
+
+        assert_all_equivalent(&input, period);
+    }
+}
+
+#[test]
+fn test_streaming_equivalence_constant() {
+    assert_all_equivalent(&[42.0; 50], 14);
+}
+
+#[test]
+fn test_streaming_equivalence_single_value() {
+    assert_all_equivalent(&[42.0], 2);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_resume_serialized_state() {
+    let input = (0..40)
+        .map(|index| 100.0 + (index as f64 * 0.7).sin() * 5.0)
+        .collect::<Vec<_>>();
+
+    let mut rsi = RsiState::new(14).unwrap();
+    let mut values = input[..39].iter().filter_map(|value| rsi.next(*value));
+    assert_eq!(25, values.by_ref().count());
+
+    let snapshot = serde_json::to_string(&rsi).unwrap();
+    let mut resumed = serde_json::from_str::<RsiState>(&snapshot).unwrap();
+
+    assert_eq!(
+        rsi.next(input[39]).unwrap(),
+        resumed.next(input[39]).unwrap()
+    );
+}
@@ -0,0 +1,146 @@
+//! A bounded buffer of the most recent values to compute indicators over.
+use crate::{
+    exponential_moving_average, relative_strength_index, simple_moving_average, Error, Output,
+};
+
+/// The last `N` values pushed, oldest first.
+///
+/// Unlike [`SmaState`](crate::SmaState) and friends this computes with the C
+/// functions, over exactly the retained values. Memory stays bounded at `N`
+/// values regardless of how many were pushed.
+///
+/// ```
+/// # use ta_lib::*;
+/// let mut window = Window::<3>::new();
+///
+/// for close in [1.0, 2.0, 3.0, 4.0] {
+///     window.push(close);
+/// }
+///
+/// assert_eq!(&[2.0, 3.0, 4.0], window.values());
+/// assert_eq!(Output::from((vec![3.0], 2)), window.sma(Some(3))?);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Window<const N: usize> {
+    buffer: [f64; N],
+    /// The index of the oldest value in `buffer`.
+    start: usize,
+    len: usize,
+}
+
+impl<const N: usize> Default for Window<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Window<N> {
+    pub fn new() -> Self {
+        assert!(0 < N);
+
+        Self {
+            buffer: [0.0; N],
+            start: 0,
+            len: 0,
+        }
+    }
+
+    /// Add the next value, dropping the oldest one if the window is full.
+    pub fn push(&mut self, value: f64) {
+        if self.len < N {
+            self.buffer[(self.start + self.len) % N] = value;
+            self.len += 1;
+        } else {
+            self.buffer[self.start] = value;
+            self.start = (self.start + 1) % N;
+        }
+    }
+
+    /// The number of values in the window, at most `N`.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        0 == self.len
+    }
+
+    pub fn is_full(&self) -> bool {
+        N == self.len
+    }
+
+    /// The values in the window, oldest first.
+    ///
+    /// This rotates the buffer in place so the values are contiguous.
+    pub fn values(&mut self) -> &[f64] {
+        self.buffer.rotate_left(self.start);
+        self.start = 0;
+
+        &self.buffer[..self.len]
+    }
+
+    /// The [`simple_moving_average()`] of the values.
+    ///
+    /// `begin` is an index into [`Window::values()`].
+    pub fn sma(&mut self, period: Option<usize>) -> Result<Output, Error> {
+        simple_moving_average(self.values(), period)
+    }
+
+    /// The [`exponential_moving_average()`] of the values.
+    ///
+    /// `begin` is an index into [`Window::values()`].
+    pub fn ema(&mut self, period: Option<usize>) -> Result<Output, Error> {
+        exponential_moving_average(self.values(), period)
+    }
+
+    /// The [`relative_strength_index()`] of the values.
+    ///
+    /// `begin` is an index into [`Window::values()`].
+    pub fn rsi(&mut self, period: Option<usize>) -> Result<Output, Error> {
+        relative_strength_index(self.values(), period)
+    }
+}
+
+#[test]
+fn test_window() {
+    let input = (0..25)
+        .map(|index| 100.0 + (index as f64 * 0.9).sin())
+        .collect::<Vec<_>>();
+
+    let mut window = Window::<10>::new();
+    assert!(window.sma(Some(10)).is_err());
+
+    for (count, value) in input.iter().enumerate() {
+        window.push(*value);
+        assert_eq!((count + 1).min(10), window.len());
+    }
+
+    assert!(window.is_full());
+    assert_eq!(&input[15..], window.values());
+
+    // Only the last ten values are averaged.
+    let Output { values: sma, begin } = window.sma(Some(10)).unwrap();
+    assert_eq!(9, begin);
+    assert_eq!(
+        simple_moving_average(&input[15..], Some(10))
+            .unwrap()
+            .values,
+        sma
+    );
+    assert!((input[15..].iter().sum::<f64>() / 10.0 - sma[0]).abs() < 1e-9);
+
+    assert_eq!(
+        exponential_moving_average(&input[15..], Some(5)).unwrap(),
+        window.ema(Some(5)).unwrap()
+    );
+    assert_eq!(
+        relative_strength_index(&input[15..], Some(5)).unwrap(),
+        window.rsi(Some(5)).unwrap()
+    );
+
+    // Values stay in order across repeated rotations.
+    window.push(1.0);
+    assert_eq!(&input[16..], &window.values()[..9]);
+    assert_eq!(1.0, window.values()[9]);
+}
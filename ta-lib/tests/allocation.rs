@@ -0,0 +1,62 @@
+//! The `_into` variants must not allocate once their output buffer is large
+//! enough. This lives in its own test binary as it replaces the global
+//! allocator with one counting the allocations of the current thread.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+use ta_lib::*;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.with(|allocations| allocations.set(allocations.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+fn test_into_does_not_allocate() {
+    let input = (0..1000)
+        .map(|index| 100.0 + (index as f64 / 10.0).sin())
+        .collect::<Vec<_>>();
+
+    let mut out = Vec::new();
+
+    // The first call sizes `out`.
+    simple_moving_average_into(&input, Some(14), &mut out).unwrap();
+
+    let before = allocations();
+    for _ in 0..10 {
+        simple_moving_average_into(&input, Some(14), &mut out).unwrap();
+        exponential_moving_average_into(&input, Some(14), &mut out).unwrap();
+        relative_strength_index_into(&input, Some(14), &mut out).unwrap();
+    }
+    assert_eq!(before, allocations());
+
+    // The allocating variant does, i.e. we are counting.
+    let Output { values: rsi, .. } = relative_strength_index(&input, Some(14)).unwrap();
+    assert!(before < allocations());
+    assert_eq!(rsi, out);
+}
@@ -0,0 +1,306 @@
+//! Every indicator outputs one value for each input from `begin` on, i.e.
+//! `values.len() + begin` is the input length. A wrapper for which this does
+//! not hold got the output size or `begin` of the C function wrong.
+//!
+//! Most of these wrappers are left out with the `pure_rust` feature.
+#![cfg(not(feature = "pure_rust"))]
+use ta_lib::*;
+
+const LEN: usize = 50;
+
+struct Prices {
+    open: Vec<f64>,
+    high: Vec<f64>,
+    low: Vec<f64>,
+    close: Vec<f64>,
+    volume: Vec<f64>,
+}
+
+/// A fixed, wiggly price series.
+fn prices() -> Prices {
+    let close = (0..LEN)
+        .map(|index| 100.0 + 5.0 * (index as f64 * 0.3).sin() + index as f64 * 0.2)
+        .collect::<Vec<_>>();
+
+    Prices {
+        open: close
+            .iter()
+            .enumerate()
+            .map(|(index, close)| close + (index as f64 * 1.7).cos())
+            .collect(),
+        high: close.iter().map(|close| close + 2.0).collect(),
+        low: close.iter().map(|close| close - 2.0).collect(),
+        volume: (0..LEN)
+            .map(|index| 1000.0 + 100.0 * (index % 7) as f64)
+            .collect(),
+        close,
+    }
+}
+
+#[track_caller]
+fn assert_begin(name: &str, output: impl Into<Output>) {
+    let Output { values, begin } = output.into();
+    assert_eq!(LEN, values.len() + begin, "{}", name);
+}
+
+#[track_caller]
+fn assert_columns(name: &str, output: IndicatorOutput) {
+    assert!(!output.columns.is_empty(), "{}", name);
+
+    for (column, values) in output.columns {
+        assert_eq!(LEN, values.len() + output.begin, "{}: {}", name, column);
+    }
+}
+
+#[test]
+fn test_begin_high_low_close() {
+    let Prices {
+        high, low, close, ..
+    } = prices();
+
+    for (name, values) in [
+        (
+            "average_directional_movement_index",
+            average_directional_movement_index(&high, &low, &close, Some(10)),
+        ),
+        (
+            "average_directional_movement_index_rating",
+            average_directional_movement_index_rating(&high, &low, &close, Some(10)),
+        ),
+        (
+            "average_true_range",
+            average_true_range(&high, &low, &close, Some(10)),
+        ),
+        (
+            "directional_movement_index",
+            directional_movement_index(&high, &low, &close, Some(10)),
+        ),
+        (
+            "normalized_average_true_range",
+            normalized_average_true_range(&high, &low, &close, Some(10)),
+        ),
+        (
+            "negative_directional_indicator",
+            negative_directional_indicator(&high, &low, &close, Some(10)),
+        ),
+        (
+            "positive_directional_indicator",
+            positive_directional_indicator(&high, &low, &close, Some(10)),
+        ),
+        ("true_range", true_range(&high, &low, &close)),
+        ("typical_price", typical_price(&high, &low, &close)),
+        (
+            "chaikin_volatility",
+            chaikin_volatility(&high, &low, 10, 10).map(Output::from),
+        ),
+        (
+            "williams_ad",
+            williams_ad(&high, &low, &close).map(Output::from),
+        ),
+    ] {
+        assert_begin(name, values.unwrap());
+    }
+
+    for (name, output) in [
+        (
+            "stochastic",
+            stochastic_columns(&high, &low, &close, None, None, None, None, None),
+        ),
+        (
+            "fast_stochastic",
+            fast_stochastic_columns(&high, &low, &close, None, None, None),
+        ),
+        (
+            "keltner_channels",
+            keltner_channels_columns(&high, &low, &close, 20, 10, 2.0),
+        ),
+        (
+            "donchian_channels",
+            donchian_channels_columns(&high, &low, 20),
+        ),
+        (
+            "super_trend",
+            super_trend_columns(&high, &low, &close, 10, 3.0),
+        ),
+        ("elder_ray", elder_ray_columns(&high, &low, &close, 13)),
+        (
+            "fisher_transform",
+            fisher_transform_columns(&high, &low, 10),
+        ),
+        (
+            "vortex_indicator",
+            vortex_indicator_columns(&high, &low, &close, 14),
+        ),
+    ] {
+        assert_columns(name, output.unwrap());
+    }
+}
+
+#[test]
+fn test_begin_values() {
+    let Prices {
+        open,
+        close,
+        volume,
+        ..
+    } = prices();
+
+    for (name, values) in [
+        (
+            "exponential_moving_average",
+            exponential_moving_average(&close, Some(10)),
+        ),
+        (
+            "exponential_moving_average_seeded",
+            exponential_moving_average_seeded(&close, Some(10), EmaSeed::FirstValue)
+                .map(Output::from),
+        ),
+        (
+            "simple_moving_average",
+            simple_moving_average(&close, Some(10)),
+        ),
+        (
+            "simple_moving_average_iter",
+            simple_moving_average_iter(close.iter().copied(), Some(10)),
+        ),
+        (
+            "weighted_moving_average",
+            weighted_moving_average(&close, Some(10)),
+        ),
+        ("rolling_max", rolling_max(&close, Some(10))),
+        ("rolling_min", rolling_min(&close, Some(10))),
+        ("rolling_sum", rolling_sum(&close, Some(10))),
+        ("rate_of_change", rate_of_change(&close, Some(10))),
+        (
+            "relative_strength_index",
+            relative_strength_index(&close, Some(10)),
+        ),
+        (
+            "absolute_price_oscillator",
+            absolute_price_oscillator(&close, Some(5), Some(10), None),
+        ),
+        (
+            "percentage_price_oscillator",
+            percentage_price_oscillator(&close, Some(5), Some(10), None),
+        ),
+        (
+            "wilders_smoothing",
+            wilders_smoothing(&close, 10).map(Output::from),
+        ),
+        (
+            "hull_moving_average",
+            hull_moving_average(&close, 16).map(Output::from),
+        ),
+        (
+            "moving_average",
+            moving_average(&close, Some(10), None).map(Output::from),
+        ),
+        (
+            "standard_deviation",
+            standard_deviation(&close, Some(10), None).map(Output::from),
+        ),
+        (
+            "pearson_correlation",
+            pearson_correlation(&close, &open, Some(10)).map(Output::from),
+        ),
+        (
+            "on_balance_volume",
+            on_balance_volume(&close, &volume).map(Output::from),
+        ),
+        ("divide", divide(&close, &volume).map(Output::from)),
+        (
+            "divide_checked",
+            divide_checked(&close, &volume, OnZero::Error).map(Output::from),
+        ),
+        ("multiply", multiply(&close, &volume).map(Output::from)),
+        (
+            "rolling_percent_rank",
+            rolling_percent_rank(&close, 10).map(Output::from),
+        ),
+        (
+            "rolling_skewness",
+            rolling_skewness(&close, 10).map(Output::from),
+        ),
+        (
+            "rolling_kurtosis",
+            rolling_kurtosis(&close, 10).map(Output::from),
+        ),
+        (
+            "rolling_quantile",
+            rolling_quantile(&close, 10, 0.05).map(Output::from),
+        ),
+        (
+            "rolling_zscore",
+            rolling_zscore(&close, Some(10)).map(Output::from),
+        ),
+        (
+            "rolling_sharpe",
+            rolling_sharpe(&close, 10, 0.0).map(Output::from),
+        ),
+        (
+            "rolling_apply",
+            rolling_apply(&close, 10, |window| window[0]).map(Output::from),
+        ),
+        ("vwap", vwap(&close, &volume, 10).map(Output::from)),
+        (
+            "coppock_curve",
+            coppock_curve(&close, 14, 11, 10).map(Output::from),
+        ),
+        (
+            "hilbert_dominant_cycle_period",
+            hilbert_dominant_cycle_period(&close).map(Output::from),
+        ),
+        ("adaptive_rsi", adaptive_rsi(&close).map(Output::from)),
+    ] {
+        assert_begin(name, values.unwrap());
+    }
+
+    for (name, output) in [
+        (
+            "bollinger_bands",
+            bollinger_bands_columns(&close, Some(10), None, None, None),
+        ),
+        (
+            "moving_average_convergence_divergence",
+            moving_average_convergence_divergence_columns(&close, None, None, None),
+        ),
+        (
+            "mesa_adaptive_moving_average",
+            mesa_adaptive_moving_average_columns(&close, None, None),
+        ),
+        (
+            "stochastic_relative_strength_index",
+            stochastic_relative_strength_index_columns(&close, None, None, None, None),
+        ),
+        (
+            "rolling_alpha_beta",
+            rolling_alpha_beta_columns(&close, &open, 10),
+        ),
+        (
+            "percentage_volume_oscillator",
+            percentage_volume_oscillator_columns(&volume, 12, 26, 9),
+        ),
+    ] {
+        assert_columns(name, output.unwrap());
+    }
+
+    for (period, values, begin) in simple_moving_average_multi(&close, &[5, 10, 20]).unwrap() {
+        assert_begin(
+            &format!("simple_moving_average_multi {}", period),
+            (values, begin),
+        );
+    }
+
+    for (moving_average_type, values, begin) in all_moving_averages(&close, 5).unwrap() {
+        assert_begin(&format!("{:?}", moving_average_type), (values, begin));
+    }
+
+    for row in correlation_matrix(&[&close, &open, &volume], 10).unwrap() {
+        for values in row {
+            assert_begin("correlation_matrix", values);
+        }
+    }
+
+    let (impulses, begin) = elder_impulse(&close, 13, 12, 26, 9).unwrap();
+    assert_eq!(LEN, impulses.len() + begin, "elder_impulse");
+}
@@ -0,0 +1,65 @@
+//! The compatibility mode is global and changes RSI's lookback. This lives in
+//! its own test binary so it can not affect the RSI computations of the unit
+//! tests running concurrently.
+//!
+//! With the `pure_rust` feature RSI does not use the settings.
+#![cfg(not(feature = "pure_rust"))]
+use ta_lib::*;
+
+#[test]
+fn test_compatibility() {
+    // The sample prices of the unit tests, which are private to the crate.
+    let close = [
+        1.087010, 1.087120, 1.087080, 1.087170, 1.087110, 1.087010, 1.087100, 1.087120, 1.087110,
+        1.087080, 1.087000, 1.086630, 1.086630, 1.086610, 1.086630, 1.086640, 1.086650, 1.086650,
+        1.086670, 1.086630,
+    ];
+
+    assert_eq!(Compatibility::Default, compatibility());
+
+    let Output {
+        values: default,
+        begin: default_begin,
+    } = relative_strength_index(close, Some(7)).unwrap();
+
+    let Output {
+        values: metastock,
+        begin: metastock_begin,
+    } = with_compatibility(Compatibility::Metastock, || {
+        assert_eq!(Compatibility::Metastock, compatibility());
+        relative_strength_index(close, Some(7)).unwrap()
+    });
+
+    assert_eq!(Compatibility::Default, compatibility());
+    // Metastock seeds the averages one close earlier, which gives an extra
+    // value. From there on both agree.
+    assert_eq!(default_begin - 1, metastock_begin);
+    assert_eq!(default.len() + 1, metastock.len());
+    assert_ne!(default[..], metastock[..default.len()]);
+    assert_eq!(default[..], metastock[1..]);
+
+    // Changing the mode from within the closure does not deadlock.
+    with_compatibility(Compatibility::Metastock, || {
+        set_compatibility(Compatibility::Default);
+        assert_eq!(Compatibility::Default, compatibility());
+    });
+    assert_eq!(Compatibility::Default, compatibility());
+
+    set_compatibility(Compatibility::Metastock);
+    assert_eq!(
+        Output {
+            values: metastock,
+            begin: metastock_begin
+        },
+        relative_strength_index(close, Some(7)).unwrap()
+    );
+
+    set_compatibility(Compatibility::Default);
+    assert_eq!(
+        Output {
+            values: default,
+            begin: default_begin
+        },
+        relative_strength_index(close, Some(7)).unwrap()
+    );
+}
@@ -0,0 +1,41 @@
+//! Resetting the settings changes them globally. This lives in its own test
+//! binary so it can not affect the computations of the unit tests running
+//! concurrently.
+//!
+//! The settings belong to the C library, which the `pure_rust` feature leaves
+//! out.
+#![cfg(not(feature = "pure_rust"))]
+use ta_lib::*;
+
+#[test]
+fn test_reset_global_settings() {
+    let close = (0..40)
+        .map(|index| 100.0 + (index as f64 * 0.4).sin() * 5.0)
+        .collect::<Vec<_>>();
+    let high = close.iter().map(|close| close + 1.0).collect::<Vec<_>>();
+    let low = close.iter().map(|close| close - 1.0).collect::<Vec<_>>();
+
+    let compute = || {
+        (
+            relative_strength_index(&close, Some(14)).unwrap(),
+            average_true_range(&high, &low, &close, Some(14)).unwrap(),
+        )
+    };
+
+    let fresh = compute();
+
+    set_unstable_period(UnstablePeriodFunction::All, 5).unwrap();
+    set_unstable_period(UnstablePeriodFunction::AverageTrueRange, 10).unwrap();
+    set_compatibility(Compatibility::Metastock);
+    assert_ne!(fresh.1, compute().1);
+
+    reset_global_settings();
+
+    assert_eq!(DEFAULT_UNSTABLE_PERIOD, rsi_unstable_period());
+    assert_eq!(
+        DEFAULT_UNSTABLE_PERIOD,
+        unstable_period(UnstablePeriodFunction::AverageTrueRange)
+    );
+    assert_eq!(Compatibility::Default, compatibility());
+    assert_eq!(fresh, compute());
+}
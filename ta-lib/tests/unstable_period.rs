@@ -0,0 +1,54 @@
+//! The unstable period is global and changes RSI's `begin`. This lives in its
+//! own test binary so it can not affect the RSI computations of the unit tests
+//! running concurrently.
+//!
+//! With the `pure_rust` feature RSI does not use the settings.
+#![cfg(not(feature = "pure_rust"))]
+use ta_lib::*;
+
+#[test]
+fn test_rsi_unstable_period() {
+    let close = (0..40)
+        .map(|index| 100.0 + (index as f64 * 0.4).sin() * 5.0)
+        .collect::<Vec<_>>();
+
+    assert_eq!(DEFAULT_UNSTABLE_PERIOD, rsi_unstable_period());
+    assert_eq!(DEFAULT_UNSTABLE_PERIOD, cmo_unstable_period());
+
+    let Output {
+        values: default,
+        begin: default_begin,
+    } = relative_strength_index(&close, Some(14)).unwrap();
+    assert_eq!(14, default_begin);
+
+    set_unstable_period(UnstablePeriodFunction::RelativeStrengthIndex, 10).unwrap();
+    assert_eq!(10, rsi_unstable_period());
+    // Other functions keep theirs.
+    assert_eq!(DEFAULT_UNSTABLE_PERIOD, cmo_unstable_period());
+
+    let Output {
+        values: unstable,
+        begin: unstable_begin,
+    } = relative_strength_index(&close, Some(14)).unwrap();
+
+    // The first ten values are held back. Wilder's smoothing starts at the
+    // first input either way so the remaining ones are the same.
+    assert_eq!(default_begin + 10, unstable_begin);
+    assert_eq!(default.len() - 10, unstable.len());
+    for (default, unstable) in default[10..].iter().zip(&unstable) {
+        assert!((default - unstable).abs() < 1e-9);
+    }
+
+    set_unstable_period(
+        UnstablePeriodFunction::RelativeStrengthIndex,
+        DEFAULT_UNSTABLE_PERIOD,
+    )
+    .unwrap();
+    assert_eq!(
+        Output {
+            values: default,
+            begin: default_begin
+        },
+        relative_strength_index(&close, Some(14)).unwrap()
+    );
+}